@@ -0,0 +1,87 @@
+//! Move generation timings, run with `cargo bench --features bench`.
+//!
+//! A plain timing loop rather than Criterion, so the benchmark builds
+//! without extra dependencies. For a quick end-to-end number use
+//! `enoch --headless --bench`.
+
+use enoch::engine::game::Game;
+use enoch::engine::moves::{
+    compute_bishops_moves, compute_king_moves, compute_knights_moves, compute_pawns_moves,
+    compute_queens_moves, compute_rooks_moves,
+};
+use enoch::engine::types::Army;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const WARMUP: u32 = 1_000;
+const ITERATIONS: u32 = 20_000;
+
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    for _ in 0..WARMUP {
+        black_box(f());
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let per_iteration = start.elapsed() / ITERATIONS;
+    println!("{:<45} {:>12?}", name, per_iteration.max(Duration::from_nanos(1)));
+}
+
+/// The starting position after 24 plies, each picking a legal move by a
+/// fixed stride through the generated list, so every run sees the same
+/// crowded board.
+fn middlegame() -> Game {
+    let mut game = Game::default();
+    for ply in 0..24 {
+        let army = game.current_army();
+        let moves = game.generate_legal_moves(army);
+        if moves.is_empty() {
+            break;
+        }
+        let mv = moves[(ply * 7) % moves.len()];
+        game.apply_move(army, mv.from, mv.to, None)
+            .expect("generated moves are legal");
+    }
+    game
+}
+
+fn main() {
+    let positions = [("start", Game::default()), ("middlegame", middlegame())];
+
+    for (label, game) in &positions {
+        let army = game.current_army();
+        bench(&format!("generate_legal_moves/{}", label), || {
+            game.generate_legal_moves(army)
+        });
+
+        let board = &game.board;
+        bench(&format!("compute_king_moves/{}", label), || {
+            compute_king_moves(board, army)
+        });
+        bench(&format!("compute_queens_moves/{}", label), || {
+            compute_queens_moves(board, army)
+        });
+        bench(&format!("compute_rooks_moves/{}", label), || {
+            compute_rooks_moves(board, army)
+        });
+        bench(&format!("compute_bishops_moves/{}", label), || {
+            compute_bishops_moves(board, army)
+        });
+        bench(&format!("compute_knights_moves/{}", label), || {
+            compute_knights_moves(board, army)
+        });
+        bench(&format!("compute_pawns_moves/{}", label), || {
+            compute_pawns_moves(board, army)
+        });
+    }
+
+    // Every army, since each one advances in a different direction.
+    let game = middlegame();
+    for army in Army::ALL {
+        bench(
+            &format!("generate_legal_moves/middlegame/{}", army.display_name()),
+            || game.generate_legal_moves(army),
+        );
+    }
+}
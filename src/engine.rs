@@ -1,8 +1,17 @@
 pub mod arrays;
 pub mod board;
+pub mod compare;
+pub mod events;
 pub mod game;
+pub mod load;
 pub mod macros;
 pub mod moves;
+pub mod notation;
 pub mod piece_kind;
+pub mod protocol;
+pub mod rules;
+pub mod stats;
 pub mod types;
+pub mod viewer;
+pub mod zobrist;
 pub mod ai;
@@ -1,6 +1,7 @@
 use crate::engine::game::Game;
-use crate::engine::types::{Army, Move};
+use crate::engine::types::{Army, Move, PieceKind, Square, Team};
 use rand::prelude::*;
+use std::time::{Duration, Instant};
 
 /// Simple random AI that picks a random legal move
 pub fn random_move(game: &mut Game, army: Army) -> Option<Move> {
@@ -20,9 +21,14 @@ pub fn capture_preferring_move(game: &mut Game, army: Army) -> Option<Move> {
         return None;
     }
     
+    // Capturing a king freezes its army, so it beats any other capture
+    if let Some(mv) = moves.iter().find(|m| m.captures_king) {
+        return Some(*mv);
+    }
+    
     // Separate captures from non-captures
     let captures: Vec<Move> = moves.iter()
-        .filter(|m| game.board.piece_at(m.to).is_some())
+        .filter(|m| game.board.is_occupied(m.to))
         .copied()
         .collect();
     
@@ -35,3 +41,318 @@ pub fn capture_preferring_move(game: &mut Game, army: Army) -> Option<Move> {
         moves.choose(&mut rng).copied()
     }
 }
+
+const WIN_SCORE: i32 = 100_000;
+
+/// Material value used by the search evaluation. Kings are weighted heavily
+/// because losing one freezes the whole army.
+pub fn piece_value(kind: PieceKind) -> i32 {
+    match kind {
+        PieceKind::King => 1_000,
+        PieceKind::Queen => 9,
+        PieceKind::Rook => 5,
+        PieceKind::Bishop => 3,
+        PieceKind::Knight => 3,
+        PieceKind::Pawn => 1,
+    }
+}
+
+/// Positional terms of the static evaluation, on the `piece_value` scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalWeights {
+    /// Taken off a queen with no enemy piece it could capture. It only
+    /// leaps two squares and can't take queens, so without a target it is
+    /// worth far less than its material.
+    pub idle_queen_penalty: i32,
+    /// Added for each enemy piece a queen could capture right now.
+    pub queen_target_bonus: i32,
+}
+
+impl EvalWeights {
+    pub const DEFAULT: EvalWeights = EvalWeights {
+        idle_queen_penalty: 3,
+        queen_target_bonus: 1,
+    };
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        EvalWeights::DEFAULT
+    }
+}
+
+/// Static evaluation from `team`'s point of view with the default
+/// `EvalWeights`.
+pub fn evaluate(game: &Game, team: Team) -> i32 {
+    evaluate_with(game, team, &EvalWeights::DEFAULT)
+}
+
+/// Own material minus the opposing team's, each queen adjusted by what it
+/// threatens (`EvalWeights`). Frozen armies contribute nothing.
+pub fn evaluate_with(game: &Game, team: Team, weights: &EvalWeights) -> i32 {
+    if let Some(winner) = game.winning_team() {
+        return if winner == team { WIN_SCORE } else { -WIN_SCORE };
+    }
+    let mut score = 0;
+    for army in Army::ALL {
+        if game.army_is_frozen(army) {
+            continue;
+        }
+        let counts = game.piece_counts(army);
+        let material: i32 = PieceKind::ALL
+            .iter()
+            .map(|&kind| counts[kind.index()] as i32 * piece_value(kind))
+            .sum::<i32>()
+            + queen_adjustment(game, army, weights);
+        if army.team() == team {
+            score += material;
+        } else {
+            score -= material;
+        }
+    }
+    score
+}
+
+/// Sum over `army`'s queens of the target bonus, or the idle penalty for a
+/// queen with nothing to capture.
+fn queen_adjustment(game: &Game, army: Army, weights: &EvalWeights) -> i32 {
+    let mut queens = game.board.pieces(army, PieceKind::Queen);
+    let mut adjustment = 0;
+    while queens != 0 {
+        let square = queens.trailing_zeros() as Square;
+        queens &= queens - 1;
+        adjustment += match game.capturable_from(army, PieceKind::Queen, square).count_ones() {
+            0 => -weights.idle_queen_penalty,
+            targets => targets as i32 * weights.queen_target_bonus,
+        };
+    }
+    adjustment
+}
+
+/// Penalty per king left attacked in the team-aware evaluation. It outweighs
+/// any piece short of a king (a queen is 9), so the search won't leave a king
+/// hanging to win material, but stays below `LIVE_ALLY_BONUS`: an attacked
+/// king still costs less than an army actually frozen.
+const KING_EXPOSED_PENALTY: i32 = 50;
+
+/// Worth of each allied army still in play, on top of its material, so a
+/// revival pays off even when the frozen army has little left.
+const LIVE_ALLY_BONUS: i32 = 200;
+
+/// Cost per king step between a live allied king and the nearest throne of
+/// a frozen ally. Far below `KING_EXPOSED_PENALTY`, so the king never walks
+/// into an attack to get closer.
+const REVIVAL_STEP_PENALTY: i32 = 2;
+
+/// Worth of a fork (`Game::find_forks`) open to the army to move: it wins
+/// at least the lesser victim unless the opponent finds a single answer.
+const FORK_BONUS: i32 = 2;
+
+/// Team evaluation plus king safety: each allied king under attack costs
+/// `KING_EXPOSED_PENALTY`, each attacked enemy king earns it. Allied armies
+/// in play earn `LIVE_ALLY_BONUS`, and while an ally is frozen the team's
+/// kings are drawn toward its thrones to revive it. A fork available to the
+/// army to move is worth `FORK_BONUS` to its side.
+pub fn evaluate_team(game: &Game, team: Team) -> i32 {
+    let mut score = evaluate(game, team);
+    if score.abs() == WIN_SCORE {
+        return score;
+    }
+    for army in Army::ALL {
+        if game.army_is_frozen(army) || !game.king_in_check(army) {
+            continue;
+        }
+        if army.team() == team {
+            score -= KING_EXPOSED_PENALTY;
+        } else {
+            score += KING_EXPOSED_PENALTY;
+        }
+    }
+    for &ally in team.armies().iter() {
+        if game.army_is_frozen(ally) {
+            score -= REVIVAL_STEP_PENALTY * revival_distance(game, ally).unwrap_or(0);
+        } else {
+            score += LIVE_ALLY_BONUS;
+        }
+    }
+    let to_move = game.current_army();
+    if !game.find_forks(to_move).is_empty() {
+        score += if to_move.team() == team { FORK_BONUS } else { -FORK_BONUS };
+    }
+    score
+}
+
+/// King steps from the nearest live allied king to one of `frozen`'s
+/// throne squares, ignoring what stands in between. `None` when no allied
+/// king is left to make the trip.
+fn revival_distance(game: &Game, frozen: Army) -> Option<i32> {
+    let thrones = game.board.armies[frozen.index()].throne_squares;
+    frozen
+        .team()
+        .armies()
+        .iter()
+        .filter(|&&ally| ally != frozen && !game.army_is_frozen(ally))
+        .filter_map(|&ally| game.state.king_square(ally))
+        .flat_map(|king| thrones.iter().map(move |&throne| king_steps(king, throne)))
+        .min()
+}
+
+/// Moves a king needs between two squares on an empty board.
+fn king_steps(from: Square, to: Square) -> i32 {
+    let files = (from % 8).abs_diff(to % 8);
+    let ranks = (from / 8).abs_diff(to / 8);
+    files.max(ranks) as i32
+}
+
+/// Marker returned when a search runs past its deadline.
+struct SearchAborted;
+
+/// Fixed-depth alpha-beta search for `army`, which must be the army to move.
+/// The team of `army` maximises; the opposing team minimises.
+pub fn search(game: &Game, army: Army, depth: u8) -> Option<Move> {
+    search_root(game, army, depth.max(1), None, evaluate).unwrap_or(None)
+}
+
+/// Search that plays for `army`'s team rather than the army alone: it scores
+/// positions with [`evaluate_team`] and passes over captures that leave an
+/// allied king attacked whenever another move is available.
+pub fn team_aware_move(game: &Game, army: Army, depth: u8) -> Option<Move> {
+    let team = army.team();
+    let exposes_ally = |mv: &Move| {
+        if game.board.is_empty(mv.to) {
+            return false;
+        }
+        let Ok(next) = game.peek_move(army, mv.from, mv.to, None) else {
+            return false;
+        };
+        team.armies()
+            .iter()
+            .any(|&ally| !next.army_is_frozen(ally) && next.king_in_check(ally))
+    };
+
+    let moves = game.ordered_legal_moves(army);
+    if let Some(mv) = moves.iter().find(|m| m.captures_king) {
+        return Some(*mv);
+    }
+    let safe: Vec<Move> = moves.iter().filter(|m| !exposes_ally(m)).copied().collect();
+    let candidates = if safe.is_empty() { &moves } else { &safe };
+    search_moves(game, army, candidates, depth.max(1), None, evaluate_team).unwrap_or(None)
+}
+
+/// Iterative-deepening search bounded by `max_millis`. Depth 1 always runs
+/// to completion so a legal move is returned whenever one exists; deeper
+/// iterations only replace it once they finish inside the budget.
+pub fn search_timed(game: &Game, army: Army, max_millis: u64) -> Option<Move> {
+    let start = Instant::now();
+    let deadline = start + Duration::from_millis(max_millis);
+
+    let mut best = search_root(game, army, 1, None, evaluate).unwrap_or(None)?;
+
+    let mut depth = 2;
+    while Instant::now() < deadline {
+        match search_root(game, army, depth, Some(deadline), evaluate) {
+            Ok(Some(mv)) => best = mv,
+            Ok(None) | Err(SearchAborted) => break,
+        }
+        depth = depth.saturating_add(1);
+        if depth == u8::MAX {
+            break;
+        }
+    }
+    Some(best)
+}
+
+/// Position evaluator used by the search, scored from a team's point of view.
+type Evaluator = fn(&Game, Team) -> i32;
+
+fn search_root(
+    game: &Game,
+    army: Army,
+    depth: u8,
+    deadline: Option<Instant>,
+    eval: Evaluator,
+) -> Result<Option<Move>, SearchAborted> {
+    let moves = game.ordered_legal_moves(army);
+    // A king capture is decisive; take it without searching further.
+    if let Some(mv) = moves.iter().find(|m| m.captures_king) {
+        return Ok(Some(*mv));
+    }
+    search_moves(game, army, &moves, depth, deadline, eval)
+}
+
+fn search_moves(
+    game: &Game,
+    army: Army,
+    moves: &[Move],
+    depth: u8,
+    deadline: Option<Instant>,
+    eval: Evaluator,
+) -> Result<Option<Move>, SearchAborted> {
+    let team = army.team();
+    let mut best: Option<(i32, Move)> = None;
+    let mut alpha = -WIN_SCORE - 1;
+    let beta = WIN_SCORE + 1;
+
+    for &mv in moves {
+        let mut next = game.clone();
+        if next.apply_move(army, mv.from, mv.to, None).is_err() {
+            continue;
+        }
+        let score = alpha_beta(&next, team, depth - 1, alpha, beta, deadline, eval)?;
+        match best {
+            Some((best_score, _)) if best_score >= score => {}
+            _ => best = Some((score, mv)),
+        }
+        alpha = alpha.max(score);
+    }
+    Ok(best.map(|(_, mv)| mv))
+}
+
+/// Alpha-beta over the four-army turn order. Nodes where an army of `team`
+/// is to move maximise, all others minimise.
+fn alpha_beta(
+    game: &Game,
+    team: Team,
+    depth: u8,
+    mut alpha: i32,
+    mut beta: i32,
+    deadline: Option<Instant>,
+    eval: Evaluator,
+) -> Result<i32, SearchAborted> {
+    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        return Err(SearchAborted);
+    }
+    if depth == 0 || game.winning_team().is_some() {
+        return Ok(eval(game, team));
+    }
+
+    let army = game.current_army();
+    let moves = game.ordered_legal_moves(army);
+    if moves.is_empty() {
+        return Ok(eval(game, team));
+    }
+
+    let maximizing = army.team() == team;
+    let mut best = if maximizing { i32::MIN } else { i32::MAX };
+    for mv in moves {
+        let mut next = game.clone();
+        if next.apply_move(army, mv.from, mv.to, None).is_err() {
+            continue;
+        }
+        let score = alpha_beta(&next, team, depth - 1, alpha, beta, deadline, eval)?;
+        if maximizing {
+            best = best.max(score);
+            alpha = alpha.max(score);
+        } else {
+            best = best.min(score);
+            beta = beta.min(score);
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+    if best == i32::MIN || best == i32::MAX {
+        return Ok(eval(game, team));
+    }
+    Ok(best)
+}
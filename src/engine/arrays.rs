@@ -1,5 +1,8 @@
 use crate::engine::board::{ArmyState, Board, DEFAULT_PROMOTION_ZONES};
-use crate::engine::types::{Army, Piece, PieceKind, PlayerId, Square, ARMY_COUNT};
+use crate::engine::types::{
+    file_char, rank_char, Army, PieceKind, PlayerId, Square, ARMY_COUNT, PIECE_KIND_COUNT,
+};
+use serde::Serialize;
 
 #[derive(Debug, Clone)]
 pub struct ArraySpec {
@@ -10,33 +13,138 @@ pub struct ArraySpec {
     pub throne_squares: [[Square; 2]; ARMY_COUNT],
     pub promotion_zones: [u64; ARMY_COUNT],
     pub placements: &'static [(Army, PieceKind, u64)],
+    /// Skips the complement check in `board()` while a layout is still
+    /// being transcribed.
+    pub allow_incomplete: bool,
 }
 
 impl ArraySpec {
+    /// Builds the starting board. Panics if two placements share a square;
+    /// `validate_complement` reports that as an error.
     pub fn board(&self) -> Board {
-        let placements = self.expand_placements();
-        Board::with_state(&placements, self.army_states(), self.promotion_zones)
+        debug_assert_eq!(self.validate_promotion_zones(), Ok(()));
+        if cfg!(debug_assertions) && !self.allow_incomplete {
+            if let Err(message) = self.validate_complement() {
+                panic!("{}", message);
+            }
+        }
+        let mut board = Board::with_state(&[], self.army_states(), self.promotion_zones);
+        for army in Army::ALL {
+            for kind in PieceKind::ALL {
+                for square in self.squares_of(army, kind) {
+                    if let Err(message) = board.try_place_piece(army, kind, square) {
+                        panic!("{}: {}", self.name, message);
+                    }
+                }
+            }
+        }
+        board
+    }
+
+    fn squares_of(&self, army: Army, kind: PieceKind) -> impl Iterator<Item = Square> + '_ {
+        self.placements
+            .iter()
+            .filter(move |&&(owner, piece, _)| owner == army && piece == kind)
+            .flat_map(|&(_, _, bitboard)| bits(bitboard))
     }
 
-    fn expand_placements(&self) -> Vec<(Army, Piece, u64)> {
-        let mut pieces = Vec::new();
+    /// Checks that no two placements share a square, that every army has
+    /// exactly one king and no more of any other piece than a standard
+    /// complement.
+    pub fn validate_complement(&self) -> Result<(), String> {
+        let mut raw = Board::new(&[]);
         for &(army, kind, bitboard) in self.placements {
-            let mut mask = bitboard;
-            while mask != 0 {
-                let square = mask.trailing_zeros() as Square;
-                pieces.push((
-                    army,
-                    Piece {
-                        army,
-                        kind,
-                        pawn_type: None,
-                    },
-                    1u64 << square,
+            for square in bits(bitboard) {
+                if let Some((owner, piece)) = raw.piece_at(square) {
+                    return Err(format!(
+                        "{}: {} {} and {} {} both start on {}{}",
+                        self.name,
+                        owner.display_name(),
+                        piece.name().to_lowercase(),
+                        army.display_name(),
+                        kind.name().to_lowercase(),
+                        file_char(square),
+                        rank_char(square)
+                    ));
+                }
+            }
+            *raw.pieces_mut(army, kind) |= bitboard;
+        }
+        let material = raw.total_material();
+        for army in Army::ALL {
+            let kings = material[army.index()][PieceKind::King.index()];
+            if kings != 1 {
+                return Err(format!(
+                    "{}: {} has {} kings (expected exactly 1)",
+                    self.name,
+                    army.display_name(),
+                    kings
                 ));
-                mask &= mask - 1;
+            }
+            for kind in PieceKind::ALL {
+                let count = material[army.index()][kind.index()];
+                let max = MAX_COMPLEMENT[kind.index()];
+                if count > max {
+                    return Err(format!(
+                        "{}: {} has {} {}s (at most {})",
+                        self.name,
+                        army.display_name(),
+                        count,
+                        kind.name().to_lowercase(),
+                        max
+                    ));
+                }
             }
         }
-        pieces
+        Ok(())
+    }
+
+    /// Checks that every army fielding pawns has somewhere to promote, that
+    /// no pawn starts inside its own promotion zone, and that every pawn has
+    /// the whole zone ahead of it along `Army::pawn_direction`, the way the
+    /// move generator advances pawns. The last check catches a zone left on
+    /// the wrong edge, or running along the pawns' files, when an array
+    /// rotates armies.
+    pub fn validate_promotion_zones(&self) -> Result<(), String> {
+        for army in Army::ALL {
+            let zone = self.promotion_zones[army.index()];
+            let pawns = self
+                .placements
+                .iter()
+                .filter(|&&(owner, kind, _)| owner == army && kind == PieceKind::Pawn)
+                .fold(0u64, |acc, &(_, _, bitboard)| acc | bitboard);
+            if pawns == 0 {
+                continue;
+            }
+            if zone == 0 {
+                return Err(format!(
+                    "{}: {} has pawns but no promotion zone",
+                    self.name,
+                    army.display_name()
+                ));
+            }
+            if zone & pawns != 0 {
+                return Err(format!(
+                    "{}: {} pawns start inside their own promotion zone",
+                    self.name,
+                    army.display_name()
+                ));
+            }
+            // Distance along the advance, so "ahead" is simply "greater".
+            let progress = |square: Square| army.pawn_direction() as i16 * (square / 8) as i16;
+            let nearest = bits(zone).map(progress).min().unwrap_or(i16::MAX);
+            if let Some(pawn) = bits(pawns).find(|&pawn| progress(pawn) >= nearest) {
+                return Err(format!(
+                    "{}: {} pawns march {} but their promotion zone is not ahead of {}{}",
+                    self.name,
+                    army.display_name(),
+                    march_name(army),
+                    (b'a' + pawn % 8) as char,
+                    pawn / 8 + 1
+                ));
+            }
+        }
+        Ok(())
     }
 
     pub fn army_states(&self) -> [ArmyState; ARMY_COUNT] {
@@ -48,6 +156,115 @@ impl ArraySpec {
         }
         states
     }
+
+    /// The spec as `--list-arrays --format json` reports it. Piece counts
+    /// come from the board `board()` builds.
+    pub fn summary(&self) -> ArraySummary {
+        let board = self.board();
+        let name = |sq: Square| format!("{}{}", file_char(sq), rank_char(sq));
+        ArraySummary {
+            name: self.name,
+            description: self.description,
+            turn_order: self.turn_order,
+            controller_map: self.controller_map,
+            throne_squares: self
+                .throne_squares
+                .iter()
+                .map(|thrones| thrones.map(name))
+                .collect(),
+            armies: Army::ALL
+                .iter()
+                .map(|&army| ArmyPieces {
+                    army,
+                    by_kind: PieceKind::ALL
+                        .iter()
+                        .map(|&kind| PieceCount {
+                            kind,
+                            count: board.pieces(army, kind).count_ones(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PieceCount {
+    pub kind: PieceKind,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArmyPieces {
+    pub army: Army,
+    /// One entry per piece kind, in `PieceKind::ALL` order.
+    pub by_kind: Vec<PieceCount>,
+}
+
+/// What a front end's array picker shows: the spec's setup plus how many
+/// pieces of each kind every army starts with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArraySummary {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub turn_order: [Army; ARMY_COUNT],
+    pub controller_map: [PlayerId; ARMY_COUNT],
+    /// Each army's throne squares ("d1"), by `Army::index`.
+    pub throne_squares: Vec<[String; 2]>,
+    /// One entry per army, in `Army::ALL` order.
+    pub armies: Vec<ArmyPieces>,
+}
+
+fn march_name(army: Army) -> &'static str {
+    if army.pawn_direction() > 0 {
+        "north"
+    } else {
+        "south"
+    }
+}
+
+/// Most pieces of each kind an army may start with, by `PieceKind::index`.
+const MAX_COMPLEMENT: [u32; PIECE_KIND_COUNT] = [1, 1, 2, 2, 2, 8];
+
+fn bits(mut bitboard: u64) -> impl Iterator<Item = Square> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let square = bitboard.trailing_zeros() as Square;
+        bitboard &= bitboard - 1;
+        Some(square)
+    })
+}
+
+/// Turns a bitboard 90° clockwise as drawn with rank 8 at the top, so a1
+/// lands on a8 and a8 on h8. Two turns give `u64::reverse_bits`.
+pub fn rotate_bitboard_90(bitboard: u64) -> u64 {
+    bits(bitboard).fold(0, |rotated, sq| {
+        let (file, rank) = (sq % 8, sq / 8);
+        rotated | 1 << square(rank, 7 - file)
+    })
+}
+
+/// Turns every placement 90° clockwise; see `rotate_bitboard_90`. Rotating a
+/// tablet and relabeling it with `remap_armies` derives its neighbour.
+pub fn rotate_placements_90(placements: &[(Army, PieceKind, u64)]) -> Vec<(Army, PieceKind, u64)> {
+    placements
+        .iter()
+        .map(|&(army, kind, bitboard)| (army, kind, rotate_bitboard_90(bitboard)))
+        .collect()
+}
+
+/// Relabels each placement's army as `map[army.index()]`.
+pub fn remap_armies(
+    placements: &[(Army, PieceKind, u64)],
+    map: [Army; ARMY_COUNT],
+) -> Vec<(Army, PieceKind, u64)> {
+    placements
+        .iter()
+        .map(|&(army, kind, bitboard)| (map[army.index()], kind, bitboard))
+        .collect()
 }
 
 const fn square(file: u8, rank: u8) -> Square {
@@ -64,7 +281,6 @@ pub const TABLET_OF_FIRE_PLACEMENTS: &[(Army, PieceKind, u64)] = &[
     (Army::Blue, PieceKind::Knight, 1 << 6),
     (Army::Blue, PieceKind::Rook, 1 << 7),
     (Army::Blue, PieceKind::Pawn, 0xFF00),
-    (Army::Red, PieceKind::Rook, 1 << 56),
     (Army::Red, PieceKind::Knight, 1 << 57),
     (Army::Red, PieceKind::Bishop, 1 << 58),
     (Army::Red, PieceKind::Queen, 1 << 59),
@@ -72,25 +288,17 @@ pub const TABLET_OF_FIRE_PLACEMENTS: &[(Army, PieceKind, u64)] = &[
     (Army::Red, PieceKind::Bishop, 1 << 61),
     (Army::Red, PieceKind::Knight, 1 << 62),
     (Army::Red, PieceKind::Rook, 1 << 63),
-    (Army::Red, PieceKind::Pawn, 0xFF000000000000),
+    (Army::Red, PieceKind::Pawn, 0xFE000000000000),
     (Army::Black, PieceKind::Rook, 1 << 24),
     (Army::Black, PieceKind::Knight, 1 << 16),
-    (Army::Black, PieceKind::Bishop, 1 << 8),
-    (Army::Black, PieceKind::Queen, 1 << 0),
     (Army::Black, PieceKind::King, 1 << 32),
     (Army::Black, PieceKind::Bishop, 1 << 40),
     (Army::Black, PieceKind::Knight, 1 << 48),
     (Army::Black, PieceKind::Rook, 1 << 56),
-    (Army::Black, PieceKind::Pawn, 0x101010101010101),
     (Army::Yellow, PieceKind::Rook, 1 << 31),
     (Army::Yellow, PieceKind::Knight, 1 << 23),
-    (Army::Yellow, PieceKind::Bishop, 1 << 15),
-    (Army::Yellow, PieceKind::Queen, 1 << 7),
     (Army::Yellow, PieceKind::King, 1 << 39),
     (Army::Yellow, PieceKind::Bishop, 1 << 47),
-    (Army::Yellow, PieceKind::Knight, 1 << 55),
-    (Army::Yellow, PieceKind::Rook, 1 << 63),
-    (Army::Yellow, PieceKind::Pawn, 0x8080808080808080),
 ];
 
 pub const TABLET_OF_FIRE_PROTOTYPE: ArraySpec = ArraySpec {
@@ -111,6 +319,7 @@ pub const TABLET_OF_FIRE_PROTOTYPE: ArraySpec = ArraySpec {
     ],
     promotion_zones: DEFAULT_PROMOTION_ZONES,
     placements: TABLET_OF_FIRE_PLACEMENTS,
+    allow_incomplete: false,
 };
 
 pub const PLACEHOLDER_PLACEMENTS: &[(Army, PieceKind, u64)] = &[];
@@ -133,6 +342,7 @@ pub const TABLET_OF_WATER_PLACEHOLDER: ArraySpec = ArraySpec {
     ],
     promotion_zones: DEFAULT_PROMOTION_ZONES,
     placements: PLACEHOLDER_PLACEMENTS,
+    allow_incomplete: true,
 };
 
 pub const TABLET_OF_AIR_PLACEHOLDER: ArraySpec = ArraySpec {
@@ -153,6 +363,7 @@ pub const TABLET_OF_AIR_PLACEHOLDER: ArraySpec = ArraySpec {
     ],
     promotion_zones: DEFAULT_PROMOTION_ZONES,
     placements: PLACEHOLDER_PLACEMENTS,
+    allow_incomplete: true,
 };
 
 pub const TABLET_OF_EARTH_PLACEHOLDER: ArraySpec = ArraySpec {
@@ -173,6 +384,7 @@ pub const TABLET_OF_EARTH_PLACEHOLDER: ArraySpec = ArraySpec {
     ],
     promotion_zones: DEFAULT_PROMOTION_ZONES,
     placements: PLACEHOLDER_PLACEMENTS,
+    allow_incomplete: true,
 };
 
 pub const ARRAY_5_PLACEHOLDER: ArraySpec = ArraySpec {
@@ -193,6 +405,7 @@ pub const ARRAY_5_PLACEHOLDER: ArraySpec = ArraySpec {
     ],
     promotion_zones: DEFAULT_PROMOTION_ZONES,
     placements: PLACEHOLDER_PLACEMENTS,
+    allow_incomplete: true,
 };
 
 pub const ARRAY_6_PLACEHOLDER: ArraySpec = ArraySpec {
@@ -213,6 +426,7 @@ pub const ARRAY_6_PLACEHOLDER: ArraySpec = ArraySpec {
     ],
     promotion_zones: DEFAULT_PROMOTION_ZONES,
     placements: PLACEHOLDER_PLACEMENTS,
+    allow_incomplete: true,
 };
 
 pub const ARRAY_7_PLACEHOLDER: ArraySpec = ArraySpec {
@@ -233,6 +447,7 @@ pub const ARRAY_7_PLACEHOLDER: ArraySpec = ArraySpec {
     ],
     promotion_zones: DEFAULT_PROMOTION_ZONES,
     placements: PLACEHOLDER_PLACEMENTS,
+    allow_incomplete: true,
 };
 
 pub const ARRAY_8_PLACEHOLDER: ArraySpec = ArraySpec {
@@ -253,6 +468,7 @@ pub const ARRAY_8_PLACEHOLDER: ArraySpec = ArraySpec {
     ],
     promotion_zones: DEFAULT_PROMOTION_ZONES,
     placements: PLACEHOLDER_PLACEMENTS,
+    allow_incomplete: true,
 };
 
 pub const ALL_ARRAYS: [&ArraySpec; 8] = [
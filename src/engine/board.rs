@@ -1,9 +1,10 @@
 use crate::engine::types::{
-    Army, Piece, PieceKind, PlayerId, Square, Team, ARMY_COUNT, PIECE_KIND_COUNT, TEAM_COUNT,
+    file_char, rank_char, Army, Piece, PieceKind, PlayerId, Square, Team, ARMY_COUNT,
+    PIECE_KIND_COUNT, TEAM_COUNT,
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ArmyState {
     pub army: Army,
     pub throne_squares: [Square; 2],
@@ -35,8 +36,27 @@ pub struct Board {
     pub free: u64,
     pub armies: [ArmyState; ARMY_COUNT],
     pub promotion_zones: [u64; ARMY_COUNT],
+    /// For each army, the pawns that belong to a given officer ("pawn of
+    /// knight" etc.), indexed by the officer's kind. Pawns without an
+    /// officer type are absent from every bitboard here.
+    #[serde(default)]
+    pub pawn_types: [[u64; PIECE_KIND_COUNT]; ARMY_COUNT],
+}
+
+/// Boards are equal when they hold the same pieces with the same army states
+/// and promotion zones. The occupancy fields are derived from `by_army_kind`
+/// and are not compared.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.by_army_kind == other.by_army_kind
+            && self.armies == other.armies
+            && self.promotion_zones == other.promotion_zones
+            && self.pawn_types == other.pawn_types
+    }
 }
 
+impl Eq for Board {}
+
 impl Board {
     pub fn new(initial_placements: &[(Army, Piece, u64)]) -> Board {
         Board::with_state(
@@ -52,8 +72,12 @@ impl Board {
         promotion_zones: [u64; ARMY_COUNT],
     ) -> Board {
         let mut by_army_kind = [[0u64; PIECE_KIND_COUNT]; ARMY_COUNT];
+        let mut pawn_types = [[0u64; PIECE_KIND_COUNT]; ARMY_COUNT];
         for (army, piece, bitboard) in initial_placements {
             by_army_kind[army.index()][piece.kind.index()] |= *bitboard;
+            if let (PieceKind::Pawn, Some(officer)) = (piece.kind, piece.pawn_type) {
+                pawn_types[army.index()][officer.index()] |= *bitboard;
+            }
         }
 
         let occupancy_by_army = compute_occupancy_by_army(&by_army_kind);
@@ -68,9 +92,13 @@ impl Board {
             free: !all_occupancy,
             armies: army_states,
             promotion_zones,
+            pawn_types,
         }
     }
 
+    /// The piece on `square`. Layers are searched army by army in
+    /// `Army::ALL` order, then kind by kind in `PieceKind::ALL` order; a
+    /// valid board (see `validate`) has at most one match.
     pub fn piece_at(&self, square: Square) -> Option<(Army, PieceKind)> {
         let mask = 1u64 << square;
         for army in Army::ALL {
@@ -82,6 +110,34 @@ impl Board {
         }
         None
     }
+
+    pub fn is_occupied(&self, square: Square) -> bool {
+        self.all_occupancy & (1u64 << square) != 0
+    }
+
+    pub fn is_empty(&self, square: Square) -> bool {
+        !self.is_occupied(square)
+    }
+
+    /// The bitboard of `army`'s pieces of `kind`.
+    pub fn pieces(&self, army: Army, kind: PieceKind) -> u64 {
+        self.by_army_kind[army.index()][kind.index()]
+    }
+
+    /// Mutable access to the bitboard of `army`'s pieces of `kind`. The
+    /// occupancy caches are not updated; call `refresh_occupancy` after.
+    pub fn pieces_mut(&mut self, army: Army, kind: PieceKind) -> &mut u64 {
+        &mut self.by_army_kind[army.index()][kind.index()]
+    }
+
+    /// The army with a piece on `square`. Cheaper than `piece_at` when the
+    /// kind doesn't matter, as only the per-army occupancy is checked.
+    pub fn occupant_army(&self, square: Square) -> Option<Army> {
+        let mask = 1u64 << square;
+        Army::ALL
+            .into_iter()
+            .find(|army| self.occupancy_by_army[army.index()] & mask != 0)
+    }
 }
 
 impl Board {
@@ -101,6 +157,15 @@ impl Board {
         self.armies[army.index()].controller
     }
 
+    /// Squares holding a piece of any army `player` controls, frozen armies
+    /// included.
+    pub fn squares_controlled_by(&self, player: PlayerId) -> u64 {
+        Army::ALL
+            .iter()
+            .filter(|&&army| self.controller_for(army) == player)
+            .fold(0, |squares, &army| squares | self.occupancy_by_army[army.index()])
+    }
+
     pub fn king_square(&self, army: Army) -> Option<Square> {
         let mask = self.by_army_kind[army.index()][PieceKind::King.index()];
         if mask == 0 {
@@ -115,11 +180,39 @@ impl Board {
         for army in Army::ALL {
             for kind in PieceKind::ALL {
                 self.by_army_kind[army.index()][kind.index()] &= !bit;
+                self.pawn_types[army.index()][kind.index()] &= !bit;
             }
         }
         self.refresh_occupancy();
     }
 
+    /// The officer a pawn on `square` belongs to, if it has been assigned one.
+    pub fn pawn_type_at(&self, army: Army, square: Square) -> Option<PieceKind> {
+        let bit = 1u64 << square;
+        if self.by_army_kind[army.index()][PieceKind::Pawn.index()] & bit == 0 {
+            return None;
+        }
+        PieceKind::ALL
+            .into_iter()
+            .find(|kind| self.pawn_types[army.index()][kind.index()] & bit != 0)
+    }
+
+    /// Places a pawn belonging to `officer` (a "pawn of knight" and so on).
+    pub fn place_pawn_of(&mut self, army: Army, officer: PieceKind, square: Square) {
+        self.place_piece(army, PieceKind::Pawn, square);
+        self.set_pawn_type(army, square, Some(officer));
+    }
+
+    fn set_pawn_type(&mut self, army: Army, square: Square, officer: Option<PieceKind>) {
+        let bit = 1u64 << square;
+        for kind in PieceKind::ALL {
+            self.pawn_types[army.index()][kind.index()] &= !bit;
+        }
+        if let Some(officer) = officer {
+            self.pawn_types[army.index()][officer.index()] |= bit;
+        }
+    }
+
     pub fn refresh_occupancy(&mut self) {
         self.occupancy_by_army = compute_occupancy_by_army(&self.by_army_kind);
         self.occupancy_by_team = compute_occupancy_by_team(&self.occupancy_by_army);
@@ -127,20 +220,51 @@ impl Board {
         self.free = !self.all_occupancy;
     }
 
+    /// Adds a piece without checking the square is free. Only for callers
+    /// that already know it is; use `try_place_piece` otherwise.
     pub fn place_piece(&mut self, army: Army, kind: PieceKind, square: Square) {
         let mask = 1u64 << square;
         self.by_army_kind[army.index()][kind.index()] |= mask;
         self.refresh_occupancy();
     }
 
+    /// Adds a piece, refusing squares that already hold one.
+    pub fn try_place_piece(
+        &mut self,
+        army: Army,
+        kind: PieceKind,
+        square: Square,
+    ) -> Result<(), String> {
+        if let Some((occupant_army, occupant_kind)) = self.piece_at(square) {
+            return Err(format!(
+                "Cannot place {} {} on {}{}: occupied by {} {}",
+                army.display_name(),
+                kind.name(),
+                file_char(square),
+                rank_char(square),
+                occupant_army.display_name(),
+                occupant_kind.name()
+            ));
+        }
+        self.place_piece(army, kind, square);
+        Ok(())
+    }
+
     pub fn remove_piece(&mut self, army: Army, kind: PieceKind, square: Square) {
         let mask = 1u64 << square;
         self.by_army_kind[army.index()][kind.index()] &= !mask;
+        if kind == PieceKind::Pawn {
+            self.set_pawn_type(army, square, None);
+        }
         self.refresh_occupancy();
     }
 
+    /// Turns one of `army`'s `kind` pieces back into that officer's pawn and
+    /// returns its square. With more than one on the board, the piece on the
+    /// lowest square (a1, b1, ... h8) goes. Kings and pawns are never
+    /// demoted.
     pub fn demote_piece_to_pawn(&mut self, army: Army, kind: PieceKind) -> Option<Square> {
-        if kind == PieceKind::Pawn {
+        if kind == PieceKind::Pawn || kind == PieceKind::King {
             return None;
         }
         let mask = self.by_army_kind[army.index()][kind.index()];
@@ -151,15 +275,33 @@ impl Board {
         let bit = 1u64 << square;
         self.by_army_kind[army.index()][kind.index()] &= !bit;
         self.by_army_kind[army.index()][PieceKind::Pawn.index()] |= bit;
+        // A demoted officer becomes that officer's pawn.
+        self.set_pawn_type(army, square, Some(kind));
         self.refresh_occupancy();
         Some(square)
     }
 
+    /// Turns the pawn on `square` into `kind`, dropping its pawn type.
+    pub fn promote_in_place(&mut self, army: Army, square: Square, kind: PieceKind) {
+        let bit = 1u64 << square;
+        self.set_pawn_type(army, square, None);
+        self.by_army_kind[army.index()][PieceKind::Pawn.index()] &= !bit;
+        self.by_army_kind[army.index()][kind.index()] |= bit;
+        self.refresh_occupancy();
+    }
+
     pub fn move_piece(&mut self, army: Army, kind: PieceKind, from: Square, to: Square) {
         let from_mask = 1u64 << from;
         let to_mask = 1u64 << to;
         self.by_army_kind[army.index()][kind.index()] &= !from_mask;
         self.by_army_kind[army.index()][kind.index()] |= to_mask;
+        if kind == PieceKind::Pawn {
+            let officer = PieceKind::ALL
+                .into_iter()
+                .find(|officer| self.pawn_types[army.index()][officer.index()] & from_mask != 0);
+            self.set_pawn_type(army, from, None);
+            self.set_pawn_type(army, to, officer);
+        }
         self.refresh_occupancy();
     }
 
@@ -171,6 +313,48 @@ impl Board {
         counts
     }
 
+    /// Checks the piece bitboards describe a real position: no square held
+    /// by two (army, kind) layers and at most one king per army. Saved
+    /// games are checked on load, since `piece_at` picks an arbitrary layer
+    /// when two overlap.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut claimed: [Option<(Army, PieceKind)>; 64] = [None; 64];
+        for army in Army::ALL {
+            for kind in PieceKind::ALL {
+                let mut bits = self.by_army_kind[army.index()][kind.index()];
+                while bits != 0 {
+                    let square = bits.trailing_zeros() as Square;
+                    bits &= bits - 1;
+                    if let Some((other_army, other_kind)) = claimed[square as usize] {
+                        return Err(format!(
+                            "Square {}{} is held by both {} {} and {} {}",
+                            file_char(square),
+                            rank_char(square),
+                            other_army.display_name(),
+                            other_kind.name(),
+                            army.display_name(),
+                            kind.name()
+                        ));
+                    }
+                    claimed[square as usize] = Some((army, kind));
+                }
+            }
+
+            let kings = self.by_army_kind[army.index()][PieceKind::King.index()];
+            if kings.count_ones() > 1 {
+                let second = (kings & (kings - 1)).trailing_zeros() as Square;
+                return Err(format!(
+                    "{} has {} kings (extra king on {}{})",
+                    army.display_name(),
+                    kings.count_ones(),
+                    file_char(second),
+                    rank_char(second)
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub fn ascii_rows(&self) -> Vec<String> {
         let mut rows = Vec::with_capacity(8);
         for rank in (0..8).rev() {
@@ -190,17 +374,99 @@ impl Board {
         rows
     }
 
+    /// Like `ascii_rows`, but turned so that `orientation`'s back rank is at
+    /// the bottom: Red sees the board upside down, Black and Yellow see it
+    /// turned on its side. Rows are labelled by rank or file as appropriate,
+    /// and a final row labels the columns.
+    pub fn ascii_rows_oriented(&self, orientation: Army) -> Vec<String> {
+        // (file, rank) shown at `row` from the top and `col` from the left.
+        let cell = |row: u8, col: u8| match orientation {
+            Army::Blue => (col, 7 - row),
+            Army::Red => (7 - col, row),
+            Army::Yellow => (row, col),
+            Army::Black => (7 - row, 7 - col),
+        };
+        let sideways = matches!(orientation, Army::Black | Army::Yellow);
+        let label = |square: Square| if sideways { file_char(square) } else { rank_char(square) };
+        let column_label = |square: Square| if sideways { rank_char(square) } else { file_char(square) };
+
+        let mut rows = Vec::with_capacity(9);
+        for row in 0..8 {
+            let (file, rank) = cell(row, 0);
+            let mut line = format!("{} ", label(square_index(file, rank)));
+            for col in 0..8 {
+                let (file, rank) = cell(row, col);
+                let ch = match self.piece_at(square_index(file, rank)) {
+                    Some((army, kind)) => piece_char(army, kind),
+                    None => '.',
+                };
+                line.push(ch);
+                line.push(' ');
+            }
+            rows.push(line.trim_end().to_string());
+        }
+        let footer: Vec<String> = (0..8)
+            .map(|col| {
+                let (file, rank) = cell(0, col);
+                column_label(square_index(file, rank)).to_string()
+            })
+            .collect();
+        rows.push(format!("  {}", footer.join(" ")));
+        rows
+    }
+
+    /// `army`'s pieces in ascending square order (a1, b1, ... h8), so move
+    /// generation and perft divide list moves by origin square. The
+    /// occupancy is rebuilt from `by_army_kind`, so the order holds even
+    /// before `refresh_occupancy`.
     pub fn all_pieces_for_army(&self, army: Army) -> impl Iterator<Item = (Square, PieceKind)> + '_ {
-        let mut pieces = Vec::new();
-        for kind in PieceKind::ALL {
-            let mut bitboard = self.by_army_kind[army.index()][kind.index()];
-            while bitboard != 0 {
-                let square = bitboard.trailing_zeros() as Square;
-                pieces.push((square, kind));
-                bitboard &= bitboard - 1;
+        let layers = &self.by_army_kind[army.index()];
+        let mut remaining = layers.iter().fold(0u64, |occupied, layer| occupied | layer);
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            let square = remaining.trailing_zeros() as Square;
+            remaining &= remaining - 1;
+            let kind = PieceKind::ALL
+                .into_iter()
+                .find(|kind| layers[kind.index()] & (1u64 << square) != 0)?;
+            Some((square, kind))
+        })
+    }
+
+    /// Every `by_army_kind` bitboard, labelled and drawn with
+    /// `print_bitboard`, army by army.
+    pub fn debug_layers(&self) -> String {
+        let mut out = String::new();
+        for army in Army::ALL {
+            for kind in PieceKind::ALL {
+                let bb = self.by_army_kind[army.index()][kind.index()];
+                out.push_str(&format!(
+                    "{} {} ({:#018x})\n{}\n\n",
+                    army.display_name(),
+                    kind.name(),
+                    bb,
+                    print_bitboard(bb)
+                ));
             }
         }
-        pieces.into_iter()
+        out.truncate(out.trim_end().len());
+        out
+    }
+
+    /// Piece counts indexed by `[army.index()][kind.index()]`.
+    pub fn total_material(&self) -> [[u32; PIECE_KIND_COUNT]; ARMY_COUNT] {
+        self.by_army_kind.map(|kinds| kinds.map(u64::count_ones))
+    }
+
+    /// Every piece on the board as `(square, army, kind)`, in ascending
+    /// square order.
+    pub fn iter_pieces(&self) -> impl Iterator<Item = (Square, Army, PieceKind)> + '_ {
+        (0..64u8).filter_map(move |square| {
+            self.piece_at(square)
+                .map(|(army, kind)| (square, army, kind))
+        })
     }
 
     pub fn throne_owner(&self, square: Square) -> Option<Army> {
@@ -214,6 +480,48 @@ impl Board {
 
 }
 
+/// Draws `bb` as an 8x8 grid, rank 8 at the top, with `X` for set bits.
+pub fn print_bitboard(bb: u64) -> String {
+    let mut rows = Vec::with_capacity(9);
+    for rank in (0..8).rev() {
+        let cells: Vec<&str> = (0..8)
+            .map(|file| if bb & (1u64 << square_index(file, rank)) != 0 { "X" } else { "." })
+            .collect();
+        rows.push(format!("{} {}", rank + 1, cells.join(" ")));
+    }
+    rows.push("  a b c d e f g h".to_string());
+    rows.join("\n")
+}
+
+/// A grid of per-square counts laid out like `print_bitboard`, with `.` for
+/// zero and counts above 9 shown as 9.
+pub fn print_heatmap(counts: &[u8; 64]) -> String {
+    let mut rows = Vec::with_capacity(9);
+    for rank in (0..8).rev() {
+        let cells: Vec<String> = (0..8)
+            .map(|file| match counts[square_index(file, rank) as usize] {
+                0 => ".".to_string(),
+                n => n.min(9).to_string(),
+            })
+            .collect();
+        rows.push(format!("{} {}", rank + 1, cells.join(" ")));
+    }
+    rows.push("  a b c d e f g h".to_string());
+    rows.join("\n")
+}
+
+/// Two `print_bitboard` grids next to each other, each under its title.
+pub fn print_bitboards_side_by_side(left_title: &str, left: u64, right_title: &str, right: u64) -> String {
+    const WIDTH: usize = 17;
+    let left_grid = print_bitboard(left);
+    let right_grid = print_bitboard(right);
+    let mut rows = vec![format!("{:<WIDTH$}    {}", left_title, right_title)];
+    for (l, r) in left_grid.lines().zip(right_grid.lines()) {
+        rows.push(format!("{:<WIDTH$}    {}", l, r));
+    }
+    rows.join("\n")
+}
+
 const fn square_index(file: u8, rank: u8) -> Square {
     rank * 8 + file
 }
@@ -300,9 +608,9 @@ const DEFAULT_ARMY_STATES: [ArmyState; ARMY_COUNT] = [
 
 pub const DEFAULT_PROMOTION_ZONES: [u64; ARMY_COUNT] = [
     MASK_RANK_8, // Blue marches north
-    MASK_FILE_H, // Black moves east
+    MASK_RANK_1, // Black marches south, as Red does
     MASK_RANK_1, // Red marches south
-    MASK_FILE_A, // Yellow moves west
+    MASK_RANK_8, // Yellow marches north, as Blue does
 ];
 
 fn compute_occupancy_by_army(
@@ -0,0 +1,103 @@
+use crate::engine::board::Board;
+use crate::engine::game::{is_pass, Game};
+use crate::engine::types::{Army, PieceKind, Square};
+
+pub type HistoryEntry = (Army, Square, Square, Option<PieceKind>);
+
+/// A square whose contents differ between two boards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SquareDiff {
+    pub square: Square,
+    pub left: Option<(Army, PieceKind)>,
+    pub right: Option<(Army, PieceKind)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    /// Both games contain exactly the same moves.
+    Identical,
+    /// One history is a strict prefix of the other. `left_is_shorter` tells
+    /// which side stops first; `plies` is the length of the shorter game.
+    Prefix { left_is_shorter: bool, plies: usize },
+    /// The games first differ at `ply` (1-based). `board_diff` lists the
+    /// squares that differ once each side has played its own move there.
+    Diverged {
+        ply: usize,
+        left: HistoryEntry,
+        right: HistoryEntry,
+        board_diff: Vec<SquareDiff>,
+    },
+}
+
+/// Squares whose contents differ between `left` and `right`.
+pub fn diff_boards(left: &Board, right: &Board) -> Vec<SquareDiff> {
+    let mut left_pieces: [Option<(Army, PieceKind)>; 64] = [None; 64];
+    let mut right_pieces: [Option<(Army, PieceKind)>; 64] = [None; 64];
+    for (square, army, kind) in left.iter_pieces() {
+        left_pieces[square as usize] = Some((army, kind));
+    }
+    for (square, army, kind) in right.iter_pieces() {
+        right_pieces[square as usize] = Some((army, kind));
+    }
+    (0..64u8)
+        .filter(|&sq| left_pieces[sq as usize] != right_pieces[sq as usize])
+        .map(|sq| SquareDiff {
+            square: sq,
+            left: left_pieces[sq as usize],
+            right: right_pieces[sq as usize],
+        })
+        .collect()
+}
+
+/// Walks the move histories of `left` and `right` in lockstep. `start` is
+/// the position both games began from and is used to replay up to the
+/// point of divergence.
+pub fn compare_games(start: &Game, left: &Game, right: &Game) -> Comparison {
+    let common = left
+        .move_history
+        .iter()
+        .zip(right.move_history.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let left_len = left.move_history.len();
+    let right_len = right.move_history.len();
+    if common == left_len && common == right_len {
+        return Comparison::Identical;
+    }
+    if common == left_len || common == right_len {
+        return Comparison::Prefix {
+            left_is_shorter: left_len < right_len,
+            plies: common,
+        };
+    }
+
+    let mut base = start.clone();
+    for &entry in &left.move_history[..common] {
+        play(&mut base, entry);
+    }
+    let left_entry = left.move_history[common];
+    let right_entry = right.move_history[common];
+    let mut left_board = base.clone();
+    let mut right_board = base;
+    play(&mut left_board, left_entry);
+    play(&mut right_board, right_entry);
+
+    Comparison::Diverged {
+        ply: common + 1,
+        left: left_entry,
+        right: right_entry,
+        board_diff: diff_boards(&left_board.board, &right_board.board),
+    }
+}
+
+fn play(game: &mut Game, entry: HistoryEntry) {
+    let (army, from, to, promotion) = entry;
+    if is_pass(&entry) {
+        game.record_pass(army, PieceKind::King);
+        return;
+    }
+    // Histories come from games that already accepted these moves, so a
+    // failure here means `start` does not match; the diff is best-effort.
+    let _ = game.apply_move(army, from, to, promotion);
+}
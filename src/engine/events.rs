@@ -0,0 +1,74 @@
+//! Notifications for code embedding the engine, so a GUI or server can react
+//! to a game as it is played instead of polling it.
+
+use crate::engine::types::{Army, PieceKind, PlayerId, Square, Team};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    Moved {
+        army: Army,
+        kind: PieceKind,
+        from: Square,
+        to: Square,
+    },
+    /// A piece other than a king was taken. En passant reports the square
+    /// the pawn stood on.
+    Captured {
+        by: Army,
+        army: Army,
+        kind: PieceKind,
+        square: Square,
+    },
+    /// `army` lost its king and is now frozen.
+    KingCaptured { army: Army },
+    /// A frozen army can move again.
+    ArmyRevived { army: Army },
+    /// `army`'s king moved onto `ally`'s throne and took control of it.
+    ThroneSeized {
+        army: Army,
+        ally: Army,
+        square: Square,
+    },
+    /// `army` changed hands, after a throne seizure or when players were
+    /// reassigned.
+    ControllerChanged {
+        army: Army,
+        from: PlayerId,
+        to: PlayerId,
+    },
+    /// The game is over; `winner` is `None` for a draw.
+    GameEnded { winner: Option<Team> },
+}
+
+pub trait GameObserver {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+/// Holds a game's observer. Clones start without one, so the copies made
+/// for search and lookahead never report events.
+#[derive(Default)]
+pub(crate) struct ObserverSlot(Option<Box<dyn GameObserver>>);
+
+impl ObserverSlot {
+    pub(crate) fn set(&mut self, observer: Option<Box<dyn GameObserver>>) -> Option<Box<dyn GameObserver>> {
+        std::mem::replace(&mut self.0, observer)
+    }
+
+    /// Whether anyone is listening, so callers can skip work done only to
+    /// build an event.
+    pub(crate) fn is_set(&self) -> bool {
+        self.0.is_some()
+    }
+
+    pub(crate) fn notify(&mut self, event: GameEvent) {
+        if let Some(observer) = self.0.as_mut() {
+            observer.on_event(&event);
+        }
+    }
+}
+
+impl Clone for ObserverSlot {
+    fn clone(&self) -> Self {
+        ObserverSlot(None)
+    }
+}
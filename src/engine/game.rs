@@ -1,17 +1,20 @@
 use crate::engine::arrays::{ArraySpec, TABLET_OF_FIRE_PROTOTYPE};
-use crate::engine::board::{diagonal_system, Board, MASK_FILE_A, MASK_FILE_H};
+use crate::engine::events::{GameEvent, GameObserver, ObserverSlot};
+use crate::engine::zobrist;
+use crate::engine::board::{diagonal_system, Board, ARIES_DIAGONALS, MASK_FILE_A, MASK_FILE_H};
 use crate::engine::moves::{
     compute_bishops_moves, compute_king_moves, compute_knights_moves, compute_pawns_moves,
     compute_queens_moves, compute_rooks_moves, find_blocker_mask, get_sliding_attacks,
-    BISHOP_RAYS_DIRECTIONS, KING_MOVES, KNIGHT_MOVES, QUEEN_LEAPS, QUEEN_RAYS,
+    pawn_attacks, BISHOP_RAYS_DIRECTIONS, KING_MOVES, KNIGHT_MOVES, QUEEN_LEAPS, QUEEN_RAYS,
     ROOK_RAYS_DIRECTIONS,
 };
 use crate::engine::piece_kind::{parse_move, ParsedMove, SpecialMove};
 use crate::engine::types::{
-    file_char, rank_char, Army, Move, PieceKind, PlayerId, Square, Team, ARMY_COUNT,
-    PIECE_KIND_COUNT,
+    file_char, parse_square, rank_char, square_name, Army, Move, PieceKind, PlayerId, Square, Team,
+    ARMY_COUNT, PIECE_KIND_COUNT,
 };
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 
 /// Game struct responsible for all game logics (pin, check, valid captures, etc)
 #[derive(Clone, Serialize, Deserialize)]
@@ -22,10 +25,125 @@ pub struct Game {
     pub status: Status,
     #[serde(skip)]
     cached_legal_moves: Option<(Army, Vec<Move>)>,
-    #[serde(default)]
+    #[serde(skip)]
+    attack_cache: Cell<AttackCache>,
+    #[serde(default, with = "packed_history")]
     pub move_history: Vec<(Army, Square, Square, Option<PieceKind>)>,
+    /// Parallel to `move_history`. Games saved before it existed load with
+    /// it empty.
+    #[serde(default)]
+    pub move_details: Vec<MoveDetail>,
+    /// Review annotations by ply, parallel to `move_history`. Unannotated
+    /// plies hold an empty string and trailing ones may be missing.
+    #[serde(default)]
+    pub annotations: Vec<String>,
+    #[serde(default)]
+    pub metadata: GameMetadata,
+    #[serde(skip)]
+    observer: ObserverSlot,
     #[serde(skip)]
     pub state_history: Vec<(Board, GameState, Status)>,
+    /// Turn state before each `apply_null_move` still to be undone, with
+    /// the `state_history` length it was made at.
+    #[serde(skip)]
+    null_moves: Vec<(GameState, usize)>,
+    /// Where play began, recorded as the first ply is played. Games saved
+    /// before it was kept have none (see `replay_start`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<StartPosition>,
+}
+
+/// The position, turn and rules a game's first ply was played from.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StartPosition {
+    pub board: Board,
+    pub state: GameState,
+    pub config: GameConfig,
+}
+
+/// Attack maps by army, valid for the occupancy they were built from.
+/// `clear_move_cache` empties it for changes occupancy doesn't show, such
+/// as a promotion.
+#[derive(Debug, Clone, Copy, Default)]
+struct AttackCache {
+    occupancy: [u64; ARMY_COUNT],
+    /// Bit `army.index()` is set when `maps[army.index()]` is filled in.
+    filled: u8,
+    maps: [u64; ARMY_COUNT],
+}
+
+/// What `apply_move` knew about a move beyond its coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveDetail {
+    pub kind: PieceKind,
+    pub captured: Option<(Army, PieceKind)>,
+}
+
+/// PGN header values carried with the game, so an export and re-import
+/// keeps them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameMetadata {
+    pub event: Option<String>,
+    pub date: Option<String>,
+    pub players: Option<String>,
+    pub result: Option<String>,
+    /// Name of the `ArraySpec` the game started from.
+    pub array: Option<String>,
+}
+
+impl GameMetadata {
+    /// Stores a PGN header such as `Event`; tags it doesn't keep are
+    /// ignored.
+    pub fn set_header(&mut self, tag: &str, value: &str) {
+        let field = match tag {
+            "Event" => &mut self.event,
+            "Date" => &mut self.date,
+            "Players" => &mut self.players,
+            "Result" => &mut self.result,
+            "Array" => &mut self.array,
+            _ => return,
+        };
+        *field = Some(value.to_string());
+    }
+}
+
+/// Everything a move changed, returned by `apply_move`. Its `Display`
+/// is the one-line summary the CLI and TUI print.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MoveOutcome {
+    pub army: Army,
+    pub moved: Move,
+    /// The piece taken and the square it stood on (the passed pawn's square
+    /// for en passant).
+    pub captured: Option<(Army, PieceKind, Square)>,
+    /// The army frozen by losing its king on this move.
+    pub king_captured: Option<Army>,
+    /// Frozen armies brought back, by seizing an ally's throne.
+    pub revived: Vec<Army>,
+    /// The ally whose throne the moving king took.
+    pub throne_seized: Option<Army>,
+    pub promoted: Option<PieceKind>,
+    /// A privileged pawn promoted without naming a piece and became a
+    /// queen.
+    pub promotion_defaulted: bool,
+    /// `Won` once a team has won, `Draw` for any draw.
+    pub status_after: Status,
+}
+
+impl std::fmt::Display for MoveOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} moved {} to {}",
+            self.army.display_name(),
+            Game::piece_name(self.moved.kind),
+            square_name(self.moved.to)
+        )?;
+        if self.promotion_defaulted {
+            write!(f, " (promoted to Queen by default)")?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +152,22 @@ pub struct GameConfig {
     pub turn_order: [Army; ARMY_COUNT],
     pub controller_map: [PlayerId; ARMY_COUNT],
     pub divination_mode: bool,
+    /// Variant rule: pawns on their home rank may advance two squares and
+    /// can then be captured en passant on the next ply.
+    #[serde(default)]
+    pub double_step_and_en_passant: bool,
+    /// Variant rule: an army left without legal moves (and not in check)
+    /// draws the game instead of having its turns skipped.
+    #[serde(default)]
+    pub stalemate_is_draw: bool,
+    /// An army in check must move its king when the king has a legal move.
+    /// Off, any move that escapes check is allowed.
+    #[serde(default = "default_true")]
+    pub force_king_move_in_check: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for GameConfig {
@@ -48,16 +182,29 @@ impl Default for GameConfig {
                 PlayerId::PLAYER_TWO,
             ],
             divination_mode: false,
+            double_step_and_en_passant: false,
+            stalemate_is_draw: false,
+            force_king_move_in_check: true,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GameState {
     pub current_turn_index: usize,
     pub army_frozen: [bool; ARMY_COUNT],
     pub king_positions: [Option<Square>; ARMY_COUNT],
     pub stalemated_armies: [bool; ARMY_COUNT],
+    /// Square skipped by a pawn double-step on the previous ply, if any.
+    #[serde(default)]
+    pub en_passant: Option<Square>,
+    /// Divination passes recorded since the last move was played.
+    #[serde(default)]
+    pub consecutive_passes: usize,
+    /// An army that asked to take back its last move and awaits an
+    /// opponent's consent.
+    #[serde(default)]
+    pub pending_takeback: Option<Army>,
 }
 
 impl GameState {
@@ -67,6 +214,9 @@ impl GameState {
             army_frozen: [false; ARMY_COUNT],
             king_positions: [None; ARMY_COUNT],
             stalemated_armies: [false; ARMY_COUNT],
+            en_passant: None,
+            consecutive_passes: 0,
+            pending_takeback: None,
         }
     }
 
@@ -123,9 +273,10 @@ pub enum InvalidMoveReason {
     KingCaptureMove,
     PawnNonDiagonalCapture,
     PawnInvalidPromotion,
+    PawnInvalidPromotionTarget,
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum MoveError {
     AmbiguousSource,
     InvalidMove(InvalidMoveReason),
@@ -133,15 +284,219 @@ pub enum MoveError {
     Checked,
     ParseError,
     GameOver,
+    /// Any other refusal from `apply_move`, explained for the player.
+    Rejected(String),
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::AmbiguousSource => write!(f, "More than one piece can make that move"),
+            MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotion) => {
+                write!(f, "Only a pawn reaching its promotion zone can promote")
+            }
+            MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotionTarget) => {
+                write!(f, "A pawn cannot promote to a King or a Pawn")
+            }
+            MoveError::InvalidMove(_) => write!(f, "Invalid move"),
+            MoveError::Pinned => write!(f, "That move would leave the King in check"),
+            MoveError::Checked => write!(f, "The King is in check and that move does not resolve it"),
+            MoveError::ParseError => write!(f, "Could not read the move"),
+            MoveError::GameOver => write!(f, "The game is over"),
+            MoveError::Rejected(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<MoveError> for String {
+    fn from(error: MoveError) -> String {
+        error.to_string()
+    }
 }
 
-#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+/// How the game stands. Kings are captured rather than mated, so a game is
+/// won once a team has the only kings left.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+#[serde(from = "SavedStatus")]
 pub enum Status {
     Ongoing,
     Draw,
+    Won(Team),
+}
+
+/// `Status` as saves may hold it. Older saves could name a `Checkmate`
+/// without a winner; it loads as `Ongoing` and `refresh_after_load` works
+/// out the winner from the kings left.
+#[derive(Deserialize)]
+enum SavedStatus {
+    Ongoing,
+    Draw,
+    Won(Team),
     Checkmate,
 }
 
+impl From<SavedStatus> for Status {
+    fn from(saved: SavedStatus) -> Status {
+        match saved {
+            SavedStatus::Ongoing | SavedStatus::Checkmate => Status::Ongoing,
+            SavedStatus::Draw => Status::Draw,
+            SavedStatus::Won(team) => Status::Won(team),
+        }
+    }
+}
+
+/// Full rounds of divination passes, with no move played by any army,
+/// after which the game is drawn.
+pub const DIVINATION_PASS_ROUNDS: usize = 10;
+
+/// Occurrences of one position that make it a repetition draw.
+pub const REPETITION_LIMIT: usize = 3;
+
+/// Full rounds without a capture or pawn move after which the game may be
+/// called drawn (the fifty-move rule, counted per army).
+pub const NO_PROGRESS_ROUNDS: usize = 50;
+
+/// Deepest tree `Game::move_tree` builds: each ply multiplies it about
+/// twentyfold.
+pub const MAX_TREE_DEPTH: u8 = 3;
+
+/// A position in `Game::move_tree`: the move that reached it, by which
+/// army (neither at the root), and a child for every legal continuation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MoveNode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub army: Option<Army>,
+    #[serde(rename = "move", skip_serializing_if = "Option::is_none")]
+    pub mv: Option<Move>,
+    pub children: Vec<MoveNode>,
+}
+
+/// Where `generate_legal_moves` spends its time, from `Game::profile_movegen`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoveGenProfile {
+    /// Positions whose moves were generated.
+    pub positions: u64,
+    /// Pseudo-legal destination time, by the kind of piece moving.
+    pub pseudo_legal: [std::time::Duration; PIECE_KIND_COUNT],
+    /// Pieces of each kind whose destinations were generated.
+    pub pieces: [u64; PIECE_KIND_COUNT],
+    /// Time spent cloning the game per candidate move and testing it for
+    /// self-check.
+    pub self_check_filter: std::time::Duration,
+    /// Candidate moves that went through the self-check filter.
+    pub candidates: u64,
+}
+
+/// Outcome of `Game::apply_divination_roll`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DivinationResult {
+    /// The rolled kind had exactly one legal move, and it was played.
+    Moved(Move),
+    /// The rolled kind has several legal moves; the player picks one and
+    /// plays it with `apply_move`. Nothing was applied.
+    Choose(Vec<Move>),
+    /// The rolled kind cannot move. A pass was recorded and the turn advanced.
+    Passed,
+    /// As `Passed`, and every army has now passed for
+    /// `DIVINATION_PASS_ROUNDS` rounds in a row, so the game is drawn.
+    Drawn,
+}
+
+/// Movers listed by `/timeline` and `--timeline`.
+pub const TIMELINE_MOVERS: usize = 8;
+
+/// A turn in `Game::turn_timeline`: an army that moves, or one passed over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineSlot {
+    Moves(Army),
+    Frozen(Army),
+    Stalemated(Army),
+}
+
+impl std::fmt::Display for TimelineSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimelineSlot::Moves(army) => write!(f, "{}", army.display_name()),
+            TimelineSlot::Frozen(army) => write!(f, "[{} frozen]", army.display_name()),
+            TimelineSlot::Stalemated(army) => write!(f, "[{} stalemated]", army.display_name()),
+        }
+    }
+}
+
+/// Symbols accepted by `Game::annotate`, longest first.
+pub const ANNOTATION_SYMBOLS: [&str; 6] = ["!!", "??", "!?", "?!", "!", "?"];
+
+/// Splits a trailing annotation symbol off a move token: `e2-e4!?` gives
+/// `("e2-e4", Some("!?"))`.
+pub fn split_annotation(token: &str) -> (&str, Option<&str>) {
+    ANNOTATION_SYMBOLS
+        .iter()
+        .find_map(|&symbol| token.strip_suffix(symbol).map(|rest| (rest, Some(symbol))))
+        .unwrap_or((token, None))
+}
+
+/// Whether a `move_history` entry is a divination pass rather than a move.
+/// Passes are recorded with the same square as source and destination.
+pub fn is_pass(&(_, from, to, _): &(Army, Square, Square, Option<PieceKind>)) -> bool {
+    from == to
+}
+
+/// Packs a `move_history` entry with `Move::encode`. Entries don't record
+/// the moving piece (`move_details` does), so its bits are left zero, the
+/// king's index.
+pub fn pack_history_entry(&(army, from, to, promotion): &(Army, Square, Square, Option<PieceKind>)) -> (Army, u16) {
+    let mv = Move {
+        from,
+        to,
+        kind: PieceKind::King,
+        promotion,
+        captures_king: false,
+    };
+    (army, mv.encode())
+}
+
+pub fn unpack_history_entry((army, packed): (Army, u16)) -> Result<(Army, Square, Square, Option<PieceKind>), String> {
+    let mv = Move::decode(packed)?;
+    Ok((army, mv.from, mv.to, mv.promotion))
+}
+
+/// Saves store `move_history` as `[army, packed move]` pairs. Unpacked
+/// entries from older saves still load.
+mod packed_history {
+    use super::{pack_history_entry, unpack_history_entry};
+    use crate::engine::types::{Army, PieceKind, Square};
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    type History = Vec<(Army, Square, Square, Option<PieceKind>)>;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Packed(Army, u16),
+        Unpacked(Army, Square, Square, Option<PieceKind>),
+    }
+
+    pub fn serialize<S: Serializer>(
+        history: &[(Army, Square, Square, Option<PieceKind>)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(history.iter().map(pack_history_entry))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<History, D::Error> {
+        Vec::<Entry>::deserialize(deserializer)?
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Packed(army, packed) => unpack_history_entry((army, packed)).map_err(D::Error::custom),
+                Entry::Unpacked(army, from, to, promotion) => Ok((army, from, to, promotion)),
+            })
+            .collect()
+    }
+}
+
 impl Game {
     pub fn new(board: Board) -> Game {
         let config = GameConfig::default();
@@ -153,7 +508,9 @@ impl Game {
         config.turn_order = spec.turn_order;
         config.controller_map = spec.controller_map;
         let board = spec.board();
-        Game::with_config(board, config)
+        let mut game = Game::with_config(board, config);
+        game.metadata.array = Some(spec.name.to_string());
+        game
     }
 
     pub fn with_config(board: Board, config: GameConfig) -> Game {
@@ -165,8 +522,15 @@ impl Game {
             state,
             status: Status::Ongoing,
             cached_legal_moves: None,
+            attack_cache: Cell::default(),
             move_history: Vec::new(),
+            move_details: Vec::new(),
+            annotations: Vec::new(),
+            metadata: GameMetadata::default(),
+            observer: ObserverSlot::default(),
             state_history: Vec::new(),
+            null_moves: Vec::new(),
+            start: None,
         }
     }
 
@@ -174,20 +538,213 @@ impl Game {
     /// This MUST be called after loading a game from JSON.
     pub fn refresh_after_load(&mut self) {
         self.board.refresh_occupancy();
+        if let Some(start) = &mut self.start {
+            start.board.refresh_occupancy();
+        }
         self.state.sync_with_board(&self.board);
         self.cached_legal_moves = None;
+        self.attack_cache.take();
+        self.record_win();
+    }
+
+    /// Sets `Status::Won` once a team holds the only kings left.
+    fn record_win(&mut self) {
+        if self.status == Status::Ongoing {
+            if let Some(team) = self.winning_team() {
+                self.status = Status::Won(team);
+            }
+        }
+    }
+
+    /// Name of the array the game started from, as saved with it. Games
+    /// built some other way, or saved before arrays were recorded, have
+    /// none; the name may also belong to an array this build doesn't know.
+    pub fn array_name(&self) -> Option<&str> {
+        self.metadata.array.as_deref()
     }
 
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
     }
 
+    /// Loads a saved game, rejecting boards that fail `Board::validate`.
     pub fn from_json(json: &str) -> Result<Game, serde_json::Error> {
         let mut game: Game = serde_json::from_str(json)?;
+        game.board.validate().map_err(serde::de::Error::custom)?;
         game.refresh_after_load();
         Ok(game)
     }
 
+    /// One `army:Ke1,Qd1,...` line per army with pieces on the board.
+    pub fn to_compact(&self) -> String {
+        let mut out = String::new();
+        for army in Army::ALL {
+            let pieces: Vec<String> = self
+                .board
+                .iter_pieces()
+                .filter(|&(_, owner, _)| owner == army)
+                .map(|(square, _, kind)| {
+                    format!("{}{}{}", kind.letter(), file_char(square), rank_char(square))
+                })
+                .collect();
+            if !pieces.is_empty() {
+                out.push_str(&format!(
+                    "{}:{}\n",
+                    army.display_name().to_lowercase(),
+                    pieces.join(",")
+                ));
+            }
+        }
+        out
+    }
+
+    /// Checks that the cached `GameState` agrees with the `Board` and that
+    /// the derived occupancy bitboards match a fresh recompute. Intended for
+    /// catching desyncs after code mutates the board directly.
+    pub fn validate_invariants(&self) -> Result<(), String> {
+        for army in Army::ALL {
+            let cached = self.state.king_square(army);
+            let actual = self.board.king_square(army);
+            if cached != actual {
+                return Err(format!(
+                    "{} king cached at {:?} but board has {:?}",
+                    army.display_name(),
+                    cached,
+                    actual
+                ));
+            }
+            let cached = self.state.army_frozen[army.index()];
+            let actual = self.board.is_army_frozen(army);
+            if cached != actual {
+                return Err(format!(
+                    "{} frozen flag is {} in state but {} on board",
+                    army.display_name(),
+                    cached,
+                    actual
+                ));
+            }
+            if self.state.is_stalemated(army) {
+                if self.state.army_frozen[army.index()] {
+                    return Err(format!(
+                        "{} is marked stalemated but is frozen",
+                        army.display_name()
+                    ));
+                }
+                if self.king_in_check(army) || !self.generate_legal_moves(army).is_empty() {
+                    return Err(format!(
+                        "{} is marked stalemated but is in check or has legal moves",
+                        army.display_name()
+                    ));
+                }
+            }
+        }
+
+        let mut fresh = self.board;
+        fresh.refresh_occupancy();
+        if fresh.occupancy_by_army != self.board.occupancy_by_army
+            || fresh.occupancy_by_team != self.board.occupancy_by_team
+            || fresh.all_occupancy != self.board.all_occupancy
+            || fresh.free != self.board.free
+        {
+            return Err("occupancy bitboards are stale".to_string());
+        }
+        Ok(())
+    }
+
+    /// Zobrist hash of the pieces, the army to move, frozen armies and
+    /// controllers. Move history and en passant rights are not included, so
+    /// transpositions share a hash.
+    pub fn position_hash(&self) -> u64 {
+        Self::hash_position(&self.board, &self.state, &self.config)
+    }
+
+    fn hash_position(board: &Board, state: &GameState, config: &GameConfig) -> u64 {
+        let keys = &zobrist::KEYS;
+        let mut hash = keys.turn[state.current_army(config).index()];
+        for (square, army, kind) in board.iter_pieces() {
+            hash ^= keys.pieces[army.index()][kind.index()][square as usize];
+        }
+        for army in Army::ALL {
+            if state.army_frozen[army.index()] {
+                hash ^= keys.frozen[army.index()];
+            }
+            let controller = board.controller_for(army).0 as usize % ARMY_COUNT;
+            hash ^= keys.controllers[army.index()][controller];
+        }
+        hash
+    }
+
+    /// How often the current position has occurred, itself included. Only
+    /// positions reached since the game was created or loaded are known.
+    pub fn repetition_count(&self) -> usize {
+        let current = self.position_hash();
+        1 + self
+            .state_history
+            .iter()
+            .filter(|(board, state, _)| Self::hash_position(board, state, &self.config) == current)
+            .count()
+    }
+
+    /// Plies since the last capture or pawn move. Passes count as no progress.
+    pub fn plies_without_progress(&self) -> usize {
+        self.no_progress_movers().len()
+    }
+
+    /// Rounds since the last capture or pawn move: the most plies any one
+    /// army has played since, so armies skipped as frozen or stalemated
+    /// don't stretch a round.
+    pub fn rounds_without_progress(&self) -> usize {
+        let mut plies = [0usize; ARMY_COUNT];
+        for army in self.no_progress_movers() {
+            plies[army.index()] += 1;
+        }
+        plies.into_iter().max().unwrap_or(0)
+    }
+
+    /// Who played each ply since the last capture or pawn move, newest
+    /// first.
+    fn no_progress_movers(&self) -> Vec<Army> {
+        let details = self.aligned_move_details();
+        self.move_history
+            .iter()
+            .zip(details.iter())
+            .rev()
+            .take_while(|(entry, detail)| {
+                is_pass(entry) || (detail.captured.is_none() && detail.kind != PieceKind::Pawn)
+            })
+            .map(|(&(army, ..), _)| army)
+            .collect()
+    }
+
+    /// `move_details` when it lines up with `move_history`, else the details
+    /// a replay from `replay_start` records, so older saves and imports
+    /// still describe their moves. Plies past a failed replay have none.
+    pub fn aligned_move_details(&self) -> std::borrow::Cow<'_, [MoveDetail]> {
+        if self.move_details.len() == self.move_history.len() {
+            return std::borrow::Cow::Borrowed(&self.move_details);
+        }
+        let mut details = Vec::with_capacity(self.move_history.len());
+        let _ = self.replay_plies_with(self.move_history.len(), |replay| {
+            if let Some(&detail) = replay.move_details.get(details.len()) {
+                details.push(detail);
+            }
+        });
+        std::borrow::Cow::Owned(details)
+    }
+
+    /// Why an unfinished game may be called drawn: "repetition" once the
+    /// position has occurred `REPETITION_LIMIT` times, or "fifty-move rule"
+    /// after `NO_PROGRESS_ROUNDS` rounds without progress.
+    pub fn claimable_draw(&self) -> Option<&'static str> {
+        if self.repetition_count() >= REPETITION_LIMIT {
+            Some("repetition")
+        } else if self.rounds_without_progress() >= NO_PROGRESS_ROUNDS {
+            Some("fifty-move rule")
+        } else {
+            None
+        }
+    }
+
     pub fn army_is_frozen(&self, army: Army) -> bool {
         self.state.army_frozen[army.index()]
     }
@@ -216,49 +773,201 @@ impl Game {
             | compute_king_moves(&self.board, army)
     }
 
-    pub fn is_square_attacked_by_army(&self, square: Square, army: Army) -> bool {
+    /// Every square `team` controls: squares its pieces could capture on,
+    /// including squares held by its own pieces (which are "defended").
+    /// Unlike `army_moves_bitboard`, pawn pushes are excluded and occupied
+    /// squares are kept only when the capture restrictions would allow
+    /// taking the piece there, so a queen never defends another queen and
+    /// a bishop never attacks or defends another bishop.
+    pub fn control_map(&self, team: Team) -> u64 {
+        let mut control = 0u64;
+        for &army in team.armies().iter() {
+            if self.army_is_frozen(army) {
+                continue;
+            }
+            for (from_sq, kind) in self.board.all_pieces_for_army(army) {
+                control |= self.piece_control_from(army, kind, from_sq);
+            }
+        }
+        control
+    }
+
+    fn piece_control_from(&self, army: Army, kind: PieceKind, from_sq: Square) -> u64 {
+        use crate::engine::moves::*;
+
+        let occupied = self.board.all_occupancy;
+        let sliding = |directions: &[usize]| {
+            let rays = QUEEN_RAYS[from_sq as usize];
+            let mut control = 0u64;
+            for &dir in directions {
+                let (blocked_bit, blocked_mask) = find_blocker_mask(rays[dir], occupied, dir);
+                control |= (rays[dir] & !blocked_mask) | blocked_bit;
+            }
+            control
+        };
+
+        match kind {
+            PieceKind::King => KING_MOVES[from_sq as usize],
+            PieceKind::Knight => KNIGHT_MOVES[from_sq as usize],
+            PieceKind::Rook => sliding(&ROOK_RAYS_DIRECTIONS),
+            // Bishops never take bishops (docs/enochian-rules.md), so a
+            // bishop's square is not controlled by another bishop.
+            PieceKind::Bishop => {
+                let bishops = Army::ALL
+                    .iter()
+                    .fold(0u64, |acc, &owner| acc | self.board.pieces(owner, PieceKind::Bishop));
+                sliding(&BISHOP_RAYS_DIRECTIONS) & !bishops
+            }
+            PieceKind::Pawn => pawn_capture_mask(army, from_sq),
+            PieceKind::Queen => {
+                let diag_system = diagonal_system(from_sq);
+                let mut control = 0u64;
+                let mut targets = QUEEN_LEAPS[from_sq as usize];
+                while targets != 0 {
+                    let dest = targets.trailing_zeros() as Square;
+                    targets &= targets - 1;
+                    let capturable = match self.board.piece_at(dest) {
+                        Some((_, PieceKind::Queen)) => false,
+                        Some((_, PieceKind::Bishop)) => diagonal_system(dest) == diag_system,
+                        _ => true,
+                    };
+                    if capturable {
+                        control |= 1u64 << dest;
+                    }
+                }
+                control
+            }
+        }
+    }
+
+    /// Every square `army` attacks, for check detection. Queen and bishop
+    /// capture restrictions don't apply: a piece that may not take another
+    /// still threatens a king. Cached until any piece moves.
+    pub fn attack_map(&self, army: Army) -> u64 {
         if self.army_is_frozen(army) {
-            return false;
+            return 0;
         }
-        let mask = 1u64 << square;
-        let _enemy_mask = self.board.all_occupancy & !self.board.occupancy_by_army[army.index()];
-        let (_pawn_moves, pawn_attacks) = compute_pawns_moves(&self.board, army);
-        if pawn_attacks & mask != 0 {
-            return true;
+        let mut cache = self.attack_cache.get();
+        if cache.occupancy != self.board.occupancy_by_army {
+            cache = AttackCache {
+                occupancy: self.board.occupancy_by_army,
+                ..AttackCache::default()
+            };
         }
-        let king_moves = compute_king_moves(&self.board, army);
-        if king_moves & mask != 0 {
-            return true;
+        let bit = 1u8 << army.index();
+        if cache.filled & bit == 0 {
+            cache.maps[army.index()] = self.compute_attack_map(army);
+            cache.filled |= bit;
+            self.attack_cache.set(cache);
         }
-        let knight_moves = compute_knights_moves(&self.board, army);
-        if knight_moves & mask != 0 {
-            return true;
+        cache.maps[army.index()]
+    }
+
+    fn compute_attack_map(&self, army: Army) -> u64 {
+        let (_pawn_moves, pawn_attacks) = compute_pawns_moves(&self.board, army);
+        let sliding = |kind: PieceKind, directions: &[usize]| {
+            get_sliding_attacks(
+                self.board.pieces(army, kind),
+                directions,
+                self.board.all_occupancy,
+            )
+        };
+        // Queens leap exactly two squares, so nothing blocks them.
+        let mut queen_attacks = 0u64;
+        let mut queens = self.board.pieces(army, PieceKind::Queen);
+        while queens != 0 {
+            queen_attacks |= QUEEN_LEAPS[queens.trailing_zeros() as usize];
+            queens &= queens - 1;
         }
-        let bishops_attacks = get_sliding_attacks(
-            self.board.by_army_kind[army.index()][PieceKind::Bishop.index()],
-            &crate::engine::moves::BISHOP_RAYS_DIRECTIONS,
-            self.board.all_occupancy,
-        );
-        if bishops_attacks & mask != 0 {
-            return true;
+        pawn_attacks
+            | compute_king_moves(&self.board, army)
+            | compute_knights_moves(&self.board, army)
+            | sliding(PieceKind::Bishop, &crate::engine::moves::BISHOP_RAYS_DIRECTIONS)
+            | sliding(PieceKind::Rook, &crate::engine::moves::ROOK_RAYS_DIRECTIONS)
+            | queen_attacks
+    }
+
+    pub fn is_square_attacked_by_army(&self, square: Square, army: Army) -> bool {
+        self.attack_map(army) & (1u64 << square) != 0
+    }
+
+    /// How many of `team`'s pieces attack or defend each square, counted
+    /// per piece over the squares `control_map` takes as controlled. Frozen
+    /// armies cover nothing.
+    pub fn coverage_counts(&self, team: Team) -> [u8; 64] {
+        let mut counts = [0u8; 64];
+        for (square, army, kind) in self.board.iter_pieces() {
+            if army.team() != team || self.army_is_frozen(army) {
+                continue;
+            }
+            let mut covered = self.piece_control_from(army, kind, square);
+            while covered != 0 {
+                let dest = covered.trailing_zeros() as usize;
+                covered &= covered - 1;
+                counts[dest] = counts[dest].saturating_add(1);
+            }
         }
-        let rooks_attacks = get_sliding_attacks(
-            self.board.by_army_kind[army.index()][PieceKind::Rook.index()],
-            &crate::engine::moves::ROOK_RAYS_DIRECTIONS,
-            self.board.all_occupancy,
-        );
-        if rooks_attacks & mask != 0 {
-            return true;
+        counts
+    }
+
+    /// Enemy pieces the `kind` on `from_sq` could take right now, under the
+    /// capture restrictions (a queen never takes a queen) but ignoring pins.
+    /// Pieces of frozen armies are left out.
+    pub fn capturable_from(&self, army: Army, kind: PieceKind, from_sq: Square) -> u64 {
+        let enemies = army
+            .team()
+            .opponent()
+            .armies()
+            .iter()
+            .filter(|&&enemy| !self.army_is_frozen(enemy))
+            .fold(0u64, |occupied, &enemy| occupied | self.board.occupancy_by_army[enemy.index()]);
+        self.piece_control_from(army, kind, from_sq) & enemies
+    }
+
+    /// Legal moves after which the moved piece could take two or more enemy
+    /// pieces that no enemy defends, with the squares of those pieces. A
+    /// king counts as a victim even when defended, since taking it freezes
+    /// its army. Capture restrictions apply, so a queen's leap never forks
+    /// a queen.
+    pub fn find_forks(&self, army: Army) -> Vec<(Move, Vec<Square>)> {
+        let enemy_team = army.team().opponent();
+        let mut forks = Vec::new();
+        for mv in self.generate_legal_moves(army) {
+            let Some((after, _)) = self.after_move(army, mv.kind, mv.from, mv.to) else {
+                continue;
+            };
+            let defended = after.control_map(enemy_team);
+            let kings = enemy_team
+                .armies()
+                .iter()
+                .fold(0u64, |kings, &enemy| {
+                    kings | after.board.pieces(enemy, PieceKind::King)
+                });
+            let mut victims = after.capturable_from(army, mv.kind, mv.to) & (!defended | kings);
+            if victims.count_ones() < 2 {
+                continue;
+            }
+            let mut squares = Vec::new();
+            while victims != 0 {
+                squares.push(victims.trailing_zeros() as Square);
+                victims &= victims - 1;
+            }
+            forks.push((mv, squares));
         }
-        let queens_attacks = get_sliding_attacks(
-            self.board.by_army_kind[army.index()][PieceKind::Queen.index()],
-            &crate::engine::moves::QUEEN_RAYS_DIRECTIONS,
-            self.board.all_occupancy,
-        );
-        if queens_attacks & mask != 0 {
-            return true;
+        forks
+    }
+
+    /// Every square `army` can move to or threatens: `army_moves_bitboard`
+    /// plus what its pieces control, own pieces they defend included.
+    pub fn army_coverage(&self, army: Army) -> u64 {
+        if self.army_is_frozen(army) {
+            return 0;
         }
-        false
+        self.board
+            .all_pieces_for_army(army)
+            .fold(self.army_moves_bitboard(army), |covered, (from_sq, kind)| {
+                covered | self.piece_control_from(army, kind, from_sq)
+            })
     }
 
     pub fn is_square_attacked_by_team(&self, square: Square, team: Team) -> bool {
@@ -294,10 +1003,67 @@ impl Game {
         }
         self.freeze_army(army);
         self.state.set_king_square(army, None);
+        self.debug_assert_king_squares();
+        self.observer.notify(GameEvent::KingCaptured { army });
+        self.record_win();
+    }
+
+    /// `GameState` tracks every king's square as the board changes, so
+    /// checks never have to scan the king bitboards. Each method that moves,
+    /// removes or places a king keeps it current; this confirms it did.
+    fn debug_assert_king_squares(&self) {
+        for army in Army::ALL {
+            debug_assert_eq!(
+                self.state.king_square(army),
+                self.board.king_square(army),
+                "{} king square out of sync",
+                army.display_name()
+            );
+        }
+    }
+
+    /// Reports game events to `observer` from now on, replacing any earlier
+    /// observer. Clones of the game do not inherit it.
+    pub fn set_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observer.set(Some(observer));
+    }
+
+    pub fn take_observer(&mut self) -> Option<Box<dyn GameObserver>> {
+        self.observer.set(None)
+    }
+
+    /// Assigns armies to `players` distinct controllers: one player controls
+    /// everything, two players split by team, and four players get one army
+    /// each in turn order. Three players can't be split evenly and are
+    /// rejected.
+    pub fn assign_players(&mut self, players: u8) -> Result<(), String> {
+        let controller_for = |position: usize, army: Army| match players {
+            1 => Ok(PlayerId::PLAYER_ONE),
+            2 => Ok(match army.team() {
+                Team::Air => PlayerId::PLAYER_ONE,
+                Team::Earth => PlayerId::PLAYER_TWO,
+            }),
+            4 => Ok(PlayerId::new(position as u8)),
+            _ => Err(format!(
+                "Unsupported player count {} (expected 1, 2 or {})",
+                players,
+                PlayerId::MAX_PLAYERS
+            )),
+        };
+        for (position, army) in self.config.turn_order.into_iter().enumerate() {
+            let controller = controller_for(position, army)?;
+            self.config.controller_map[army.index()] = controller;
+            self.hand_over(army, controller);
+        }
+        Ok(())
     }
 
-    pub fn seize_throne_at(&mut self, army: Army, square: Square) {
+    /// A king entering an ally's throne takes control of that army and
+    /// revives it if frozen. Its own throne and enemy thrones change nothing;
+    /// see `enemy_on_throne` for what an enemy king there does.
+    pub fn seize_throne_at(&mut self, army: Army, square: Square) -> Option<Army> {
         let team = army.team();
+        let mut seized = None;
         for &ally in team.armies().iter() {
             if ally == army {
                 continue;
@@ -307,10 +1073,61 @@ impl Game {
                 .contains(&square)
             {
                 let controller = self.board.controller_for(army);
-                self.board.set_controller(ally, controller);
+                let was_frozen = self.army_is_frozen(ally);
                 self.unfreeze_army(ally);
+                self.observer.notify(GameEvent::ThroneSeized { army, ally, square });
+                self.hand_over(ally, controller);
+                if was_frozen {
+                    self.observer.notify(GameEvent::ArmyRevived { army: ally });
+                }
+                seized = Some(ally);
             }
         }
+        seized
+    }
+
+    /// Each frozen army, whether it looks revivable, and its throne squares.
+    /// Revivable is a rough guess: an allied king is still alive
+    /// (`kings_alive`) and one of the thrones isn't blocked by the team's
+    /// own pieces, so a king could in principle step onto it. Distance and
+    /// danger on the way are not considered.
+    pub fn revival_status(&self) -> Vec<(Army, bool, [Square; 2])> {
+        Army::ALL
+            .into_iter()
+            .filter(|&army| self.army_is_frozen(army))
+            .map(|army| {
+                let thrones = self.board.armies[army.index()].throne_squares;
+                let team_pieces = self.board.occupancy_by_team[army.team().index()];
+                let open = thrones.iter().any(|&square| team_pieces & (1u64 << square) == 0);
+                (army, open && self.state.kings_alive(army.team()) > 0, thrones)
+            })
+            .collect()
+    }
+
+    /// Gives `army` to `controller`, reporting the change if it is one.
+    fn hand_over(&mut self, army: Army, controller: PlayerId) {
+        let previous = self.board.controller_for(army);
+        self.board.set_controller(army, controller);
+        if previous != controller {
+            self.observer.notify(GameEvent::ControllerChanged {
+                army,
+                from: previous,
+                to: controller,
+            });
+        }
+    }
+
+    /// The enemy army whose king stands on one of `army`'s throne squares.
+    /// It gains no control there, but `army` cannot be revived by a prisoner
+    /// exchange while the throne is held.
+    pub fn enemy_on_throne(&self, army: Army) -> Option<Army> {
+        self.board.armies[army.index()]
+            .throne_squares
+            .iter()
+            .find_map(|&square| match self.board.piece_at(square) {
+                Some((occupant, PieceKind::King)) if occupant.team() != army.team() => Some(occupant),
+                _ => None,
+            })
     }
 
     pub fn winning_team(&self) -> Option<Team> {
@@ -326,6 +1143,9 @@ impl Game {
     }
 
     pub fn draw_condition(&self) -> bool {
+        if self.status == Status::Draw {
+            return true;
+        }
         let air_kings = self.state.kings_alive(Team::Air);
         let earth_kings = self.state.kings_alive(Team::Earth);
         if air_kings == 0 && earth_kings == 0 {
@@ -340,6 +1160,53 @@ impl Game {
         false
     }
 
+    /// True when neither team can ever capture an enemy king, whatever is
+    /// played. `apply_move` declares such positions drawn.
+    pub fn is_dead_position(&self) -> bool {
+        self.winning_team().is_none() && Team::ALL.iter().all(|&team| !self.team_can_win(team))
+    }
+
+    /// Whether any sequence of moves could let `team` capture an enemy
+    /// king. Kings never count as attackers: a king may not step next to
+    /// another. Pawns, knights and rooks reach every square, and queens
+    /// check along their lines, so any of them keeps the team alive. Bishops
+    /// stay on one diagonal system; when they all share a system and the
+    /// board holds nothing but them, one king of their own and one lone enemy
+    /// king, that king can always answer a check by stepping orthogonally
+    /// onto the other system.
+    fn team_can_win(&self, team: Team) -> bool {
+        let kings_of = |team: Team| -> u64 {
+            team.armies()
+                .iter()
+                .filter(|&&army| !self.army_is_frozen(army))
+                .map(|&army| self.board.pieces(army, PieceKind::King))
+                .fold(0, |acc, kings| acc | kings)
+        };
+        let mut bishops = 0u64;
+        for &army in team.armies().iter() {
+            if self.army_is_frozen(army) {
+                continue;
+            }
+            let counts = self.piece_counts(army);
+            if [PieceKind::Pawn, PieceKind::Knight, PieceKind::Rook, PieceKind::Queen]
+                .iter()
+                .any(|kind| counts[kind.index()] > 0)
+            {
+                return true;
+            }
+            bishops |= self.board.pieces(army, PieceKind::Bishop);
+        }
+        if bishops == 0 {
+            return false;
+        }
+
+        let one_system = bishops & ARIES_DIAGONALS == 0 || bishops & !ARIES_DIAGONALS == 0;
+        let own_kings = kings_of(team);
+        let enemy_kings = kings_of(team.opponent());
+        let others = self.board.all_occupancy & !(bishops | own_kings | enemy_kings);
+        !(one_system && own_kings.count_ones() <= 1 && enemy_kings.count_ones() == 1 && others == 0)
+    }
+
     pub fn piece_counts(&self, army: Army) -> [u32; PIECE_KIND_COUNT] {
         self.board.piece_counts(army)
     }
@@ -382,9 +1249,20 @@ impl Game {
         (zone >> square) & 1 != 0
     }
 
+    /// Squares on which `army`'s pawns promote, in ascending square order.
+    pub fn promotion_squares(&self, army: Army) -> Vec<Square> {
+        let mut zone = self.board.promotion_zones[army.index()];
+        let mut squares = Vec::with_capacity(zone.count_ones() as usize);
+        while zone != 0 {
+            squares.push(zone.trailing_zeros() as Square);
+            zone &= zone - 1;
+        }
+        squares
+    }
+
     pub fn promote_pawn(&mut self, army: Army, pawn_square: Square, target: PieceKind) -> bool {
         let pawn_mask = 1u64 << pawn_square;
-        let pawn_bits = self.board.by_army_kind[army.index()][PieceKind::Pawn.index()];
+        let pawn_bits = self.board.pieces(army, PieceKind::Pawn);
         if pawn_bits & pawn_mask == 0 {
             return false;
         }
@@ -392,34 +1270,46 @@ impl Game {
             return false;
         }
 
+        // A privileged pawn may choose freely; otherwise a pawn becomes the
+        // officer it belongs to, or a queen if it has no officer type.
         let target_kind = if self.is_privileged_pawn(army) {
             target
         } else {
-            PieceKind::Queen
+            match self.board.pawn_type_at(army, pawn_square) {
+                Some(PieceKind::King) | Some(PieceKind::Pawn) | None => PieceKind::Queen,
+                Some(officer) => officer,
+            }
         };
 
         if target_kind == PieceKind::Pawn || target_kind == PieceKind::King {
             return false;
         }
 
-        if self.board.by_army_kind[army.index()][target_kind.index()] != 0 {
+        // An army keeps one officer of each kind: promoting to a kind still
+        // on the board demotes that piece back to its pawn where it stands
+        // (docs/enochian-rules.md, privileged pawns). Kings are never
+        // promotion targets, so none is ever demoted.
+        if self.board.pieces(army, target_kind) != 0 {
             self.board.demote_piece_to_pawn(army, target_kind);
         }
 
-        self.board.by_army_kind[army.index()][PieceKind::Pawn.index()] &= !pawn_mask;
-        self.board.by_army_kind[army.index()][target_kind.index()] |= pawn_mask;
-        self.board.refresh_occupancy();
+        self.board.promote_in_place(army, pawn_square, target_kind);
+        self.debug_assert_king_squares();
         true
     }
 
     pub fn update_stalemate_status(&mut self, army: Army) {
-        if self.king_in_check(army) {
+        // A frozen army never moves, so stalemate means nothing for it.
+        if self.army_is_frozen(army) || self.king_in_check(army) {
             self.state.set_stalemate(army, false);
             return;
         }
 
         if self.generate_legal_moves(army).is_empty() {
             self.state.set_stalemate(army, true);
+            if self.config.stalemate_is_draw {
+                self.status = Status::Draw;
+            }
         } else {
             self.state.set_stalemate(army, false);
         }
@@ -431,16 +1321,24 @@ impl Game {
 
     pub fn restore_king_to_throne(&mut self, army: Army) {
         let throne = self.board.armies[army.index()].throne_squares[0];
+        if let Some((displaced, PieceKind::King)) = self.board.piece_at(throne) {
+            self.state.set_king_square(displaced, None);
+        }
         self.board.clear_square(throne);
         self.board.place_piece(army, PieceKind::King, throne);
         self.state.set_king_square(army, Some(throne));
+        self.debug_assert_king_squares();
         self.unfreeze_army(army);
+        self.observer.notify(GameEvent::ArmyRevived { army });
     }
 
     pub fn exchange_prisoners(&mut self, army_a: Army, army_b: Army) -> bool {
         if self.state.king_square(army_a).is_some() || self.state.king_square(army_b).is_some() {
             return false;
         }
+        if self.enemy_on_throne(army_a).is_some() || self.enemy_on_throne(army_b).is_some() {
+            return false;
+        }
         self.restore_king_to_throne(army_a);
         self.restore_king_to_throne(army_b);
         self.state.set_stalemate(army_a, false);
@@ -452,6 +1350,95 @@ impl Game {
         self.state.current_army(&self.config)
     }
 
+    /// Gives the move to `army`, for setting up puzzles and analysis
+    /// positions; the turn order carries on from it. En passant rights
+    /// belonged to the ply that was skipped and are dropped. Frozen armies
+    /// can't be given the move.
+    pub fn set_to_move(&mut self, army: Army) -> Result<(), String> {
+        if self.army_is_frozen(army) {
+            return Err(format!("{} is frozen and cannot move", army.display_name()));
+        }
+        let index = self
+            .config
+            .turn_order
+            .iter()
+            .position(|&other| other == army)
+            .ok_or_else(|| format!("{} is not in the turn order", army.display_name()))?;
+        if index != self.state.current_turn_index {
+            self.state.current_turn_index = index;
+            self.state.en_passant = None;
+            self.clear_move_cache();
+        }
+        Ok(())
+    }
+
+    pub fn current_team(&self) -> Team {
+        self.current_army().team()
+    }
+
+    /// Armies on the team to move that still have a king and aren't frozen.
+    pub fn team_to_move_count(&self) -> usize {
+        self.current_team()
+            .armies()
+            .iter()
+            .filter(|&&army| !self.army_is_frozen(army) && self.state.king_square(army).is_some())
+            .count()
+    }
+
+    /// One round of play starting with the army to move, leaving out the
+    /// frozen and stalemated armies `advance_to_next_army` would skip.
+    pub fn armies_in_turn_order(&self) -> Vec<Army> {
+        self.turn_slots()
+            .take(self.config.turn_order.len())
+            .filter_map(|slot| match slot {
+                TimelineSlot::Moves(army) => Some(army),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The turn order from the army to move on, round after round, until
+    /// `movers` armies have moved. Frozen and stalemated armies appear where
+    /// their turn is passed over. Nothing is played ahead, so an army that
+    /// gets frozen or revived on the way isn't foreseen. Ends early when
+    /// only the army to move could ever move.
+    pub fn turn_timeline(&self, movers: usize) -> Vec<TimelineSlot> {
+        let mut moved = 0;
+        self.turn_slots()
+            .take(movers * self.config.turn_order.len())
+            .take_while(|slot| {
+                let more = moved < movers;
+                moved += matches!(slot, TimelineSlot::Moves(_)) as usize;
+                more
+            })
+            .collect()
+    }
+
+    /// `turn_timeline` as `Blue → Red → [Black frozen] → Yellow → …`.
+    pub fn timeline_text(&self, movers: usize) -> String {
+        let slots: Vec<String> = self.turn_timeline(movers).iter().map(TimelineSlot::to_string).collect();
+        format!("{} → …", slots.join(" → "))
+    }
+
+    /// Endless turn order from the army to move, which moves first even if
+    /// it is stuck.
+    fn turn_slots(&self) -> impl Iterator<Item = TimelineSlot> + '_ {
+        let order = &self.config.turn_order;
+        let start = self.state.current_turn_index;
+        (0..).map(move |offset| {
+            let army = order[(start + offset) % order.len()];
+            if offset == 0 {
+                TimelineSlot::Moves(army)
+            } else if self.army_is_frozen(army) {
+                TimelineSlot::Frozen(army)
+            } else if self.state.is_stalemated(army) {
+                TimelineSlot::Stalemated(army)
+            } else {
+                TimelineSlot::Moves(army)
+            }
+        })
+    }
+
     /// Roll a die for divination mode (1-6)
     pub fn roll_die() -> u8 {
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -490,6 +1477,43 @@ impl Game {
             .collect()
     }
 
+    /// The pawn `army` would capture by moving onto the en passant square:
+    /// the enemy pawn that just double-stepped over it. An ally's pawn is
+    /// never taken.
+    fn en_passant_victim(&self, army: Army) -> Option<(Army, Square)> {
+        if !self.config.double_step_and_en_passant {
+            return None;
+        }
+        let target = self.state.en_passant?;
+        army.team()
+            .opponent()
+            .armies()
+            .into_iter()
+            .find_map(|other| {
+                let square = target as i8 + 8 * other.pawn_direction();
+                if !(0..64).contains(&square) {
+                    return None;
+                }
+                let square = square as Square;
+                (self.board.piece_at(square) == Some((other, PieceKind::Pawn)))
+                    .then_some((other, square))
+            })
+    }
+
+    /// Destinations of the piece on `square` as `(pseudo_legal, legal)`
+    /// bitboards: where it moves by its own rules, and what is left once
+    /// moves exposing its king are removed. `None` for an empty square.
+    pub fn reach(&self, square: Square) -> Option<(u64, u64)> {
+        let (army, kind) = self.board.piece_at(square)?;
+        let pseudo_legal = self.piece_moves_from(army, kind, square);
+        let legal = self
+            .generate_legal_moves(army)
+            .iter()
+            .filter(|mv| mv.from == square)
+            .fold(0u64, |bb, mv| bb | (1u64 << mv.to));
+        Some((pseudo_legal, legal))
+    }
+
     fn piece_moves_from(&self, army: Army, kind: PieceKind, from_sq: Square) -> u64 {
         use crate::engine::moves::*;
         
@@ -573,28 +1597,174 @@ impl Game {
                 };
                 if forward & self.board.free != 0 {
                     moves |= forward;
+                    if self.config.double_step_and_en_passant {
+                        if let Some((_, dest)) = pawn_double_step(army, from_sq) {
+                            if self.board.is_empty(dest) {
+                                moves |= 1u64 << dest;
+                            }
+                        }
+                    }
                 }
                 
                 // Diagonal captures
                 let enemy_occupancy = self.board.all_occupancy & !own_pieces;
-                let left_capture = match direction {
-                    1 => (from_mask << 7) & !MASK_FILE_H,
-                    -1 => (from_mask >> 9) & !MASK_FILE_H,
-                    _ => 0,
-                };
-                let right_capture = match direction {
-                    1 => (from_mask << 9) & !MASK_FILE_A,
-                    -1 => (from_mask >> 7) & !MASK_FILE_A,
-                    _ => 0,
-                };
-                moves |= (left_capture | right_capture) & enemy_occupancy;
+                moves |= pawn_capture_mask(army, from_sq) & enemy_occupancy;
+                
+                if let Some(target) = self.state.en_passant {
+                    if self.en_passant_victim(army).is_some() {
+                        moves |= pawn_capture_mask(army, from_sq) & (1u64 << target);
+                    }
+                }
                 
                 moves
             }
         }
     }
 
+    /// The position after `army` moves its `kind` from `from` to `to`,
+    /// without checking the move's geometry, and whether it took a king.
+    /// `None` if `to` holds one of `army`'s own pieces.
+    fn after_move(
+        &self,
+        army: Army,
+        kind: PieceKind,
+        from: Square,
+        to: Square,
+    ) -> Option<(Game, bool)> {
+        let mut next_board = self.board.clone();
+        let mut next_state = self.state.clone();
+        let mut captures_king = false;
+
+        if let Some((target_army, target_kind)) = next_board.piece_at(to) {
+            if target_army == army {
+                return None;
+            }
+            next_board.remove_piece(target_army, target_kind, to);
+            // Mirror `capture_king`: the captured army is frozen and
+            // can no longer give check.
+            if target_kind == PieceKind::King {
+                captures_king = true;
+                next_state.set_frozen(target_army, true);
+                next_state.set_king_square(target_army, None);
+            }
+        }
+
+        if kind == PieceKind::Pawn && self.state.en_passant == Some(to) {
+            if let Some((victim_army, victim_sq)) = self.en_passant_victim(army) {
+                next_board.remove_piece(victim_army, PieceKind::Pawn, victim_sq);
+            }
+        }
+
+        next_board.move_piece(army, kind, from, to);
+        if kind == PieceKind::King {
+            next_state.set_king_square(army, Some(to));
+        }
+
+        let next_game = Game {
+            board: next_board,
+            config: self.config.clone(),
+            state: next_state,
+            status: self.status.clone(),
+            cached_legal_moves: None,
+            attack_cache: Cell::default(),
+            move_history: Vec::new(),
+            move_details: Vec::new(),
+            annotations: Vec::new(),
+            metadata: GameMetadata::default(),
+            observer: ObserverSlot::default(),
+            state_history: Vec::new(),
+            null_moves: Vec::new(),
+            start: None,
+        };
+        Some((next_game, captures_king))
+    }
+
+    /// Legal moves for `army` with captures first, ordered
+    /// most-valuable-victim, least-valuable-attacker: by victim value minus
+    /// attacker value (`ai::piece_value`), so the search prunes sooner.
+    /// Queens never take queens nor bishops bishops, so those pairs never
+    /// show up. Quiet moves follow in generated order.
+    pub fn ordered_legal_moves(&self, army: Army) -> Vec<Move> {
+        let mut moves = self.generate_legal_moves(army);
+        moves.sort_by_cached_key(|mv| std::cmp::Reverse(self.capture_gain(mv)));
+        moves
+    }
+
+    /// Victim value minus attacker value for a capture, `None` for a quiet
+    /// move. En passant takes a pawn that isn't on the destination.
+    fn capture_gain(&self, mv: &Move) -> Option<i32> {
+        use crate::engine::ai::piece_value;
+
+        let victim = match self.board.piece_at(mv.to) {
+            Some((_, kind)) => kind,
+            None if mv.kind == PieceKind::Pawn && self.state.en_passant == Some(mv.to) => PieceKind::Pawn,
+            None => return None,
+        };
+        Some(piece_value(victim) - piece_value(mv.kind))
+    }
+
+    /// Where a piece other than the king must land to answer a single
+    /// check: on the checker, between it and the king, or on one of the
+    /// checking army's kings (taking one freezes the army). `None` when
+    /// `army` isn't in check, faces more than one checker, or could take en
+    /// passant; callers then try every destination.
+    fn check_evasion_mask(&self, army: Army) -> Option<u64> {
+        if self.state.en_passant.is_some() {
+            return None;
+        }
+        let king_sq = self.state.king_square(army)?;
+        let king_bit = 1u64 << king_sq;
+
+        let mut checker = None;
+        for &enemy in army.team().opponent().armies().iter() {
+            if self.army_is_frozen(enemy) {
+                continue;
+            }
+            for (square, kind) in self.board.all_pieces_for_army(enemy) {
+                if self.piece_control_from(enemy, kind, square) & king_bit != 0 {
+                    if checker.is_some() {
+                        return None;
+                    }
+                    checker = Some((enemy, kind, square));
+                }
+            }
+        }
+
+        let (enemy, kind, square) = checker?;
+        let mut mask = (1u64 << square) | self.board.pieces(enemy, PieceKind::King);
+        // Queens leap, so only rook and bishop checks can be blocked.
+        if matches!(kind, PieceKind::Bishop | PieceKind::Rook) {
+            let df = (king_sq % 8) as i8 - (square % 8) as i8;
+            let dr = (king_sq / 8) as i8 - (square / 8) as i8;
+            let (step_f, step_r) = (df.signum(), dr.signum());
+            let (mut file, mut rank) = ((square % 8) as i8 + step_f, (square / 8) as i8 + step_r);
+            while (file, rank) != ((king_sq % 8) as i8, (king_sq / 8) as i8) {
+                mask |= 1u64 << (rank * 8 + file);
+                file += step_f;
+                rank += step_r;
+            }
+        }
+        Some(mask)
+    }
+
     pub fn generate_legal_moves(&self, army: Army) -> Vec<Move> {
+        self.legal_moves_within(army, self.check_evasion_mask(army))
+    }
+
+    /// `generate_legal_moves` without the single-check shortcut: every
+    /// pseudo-legal move is played out and tested. Kept as the reference the
+    /// fast path is checked against.
+    pub fn generate_legal_moves_unpruned(&self, army: Army) -> Vec<Move> {
+        self.legal_moves_within(army, None)
+    }
+
+    /// Legal move count per army, indexed by `Army::index`; frozen and
+    /// stalemated armies count 0.
+    pub fn mobility_counts(&self) -> [usize; ARMY_COUNT] {
+        Army::ALL.map(|army| self.generate_legal_moves(army).len())
+    }
+
+    fn legal_moves_within(&self, army: Army, evasion_mask: Option<u64>) -> Vec<Move> {
         if self.army_is_frozen(army) {
             return Vec::new();
         }
@@ -602,35 +1772,18 @@ impl Game {
         let mut legal_moves = Vec::new();
         for (from_sq, kind) in self.board.all_pieces_for_army(army) {
             let pseudo_legal_destinations = self.piece_moves_from(army, kind, from_sq);
-            let mut destinations = pseudo_legal_destinations;
+            let mut destinations = match evasion_mask {
+                Some(mask) if kind != PieceKind::King => pseudo_legal_destinations & mask,
+                _ => pseudo_legal_destinations,
+            };
 
             while destinations != 0 {
                 let to_sq = destinations.trailing_zeros() as Square;
                 destinations &= destinations - 1;
 
-                let mut next_board = self.board.clone();
-                let mut next_state = self.state.clone();
-
-                if let Some((target_army, target_kind)) = next_board.piece_at(to_sq) {
-                    if target_army == army {
-                        continue;
-                    }
-                    next_board.remove_piece(target_army, target_kind, to_sq);
-                }
-
-                next_board.move_piece(army, kind, from_sq, to_sq);
-                if kind == PieceKind::King {
-                    next_state.set_king_square(army, Some(to_sq));
-                }
-
-                let next_game = Game {
-                    board: next_board,
-                    config: self.config.clone(),
-                    state: next_state,
-                    status: self.status.clone(),
-                    cached_legal_moves: None,
-                    move_history: Vec::new(),
-                    state_history: Vec::new(),
+                let Some((next_game, captures_king)) = self.after_move(army, kind, from_sq, to_sq)
+                else {
+                    continue;
                 };
 
                 if !next_game.king_in_check(army) {
@@ -639,15 +1792,18 @@ impl Game {
                         to: to_sq,
                         kind,
                         promotion: None,
+                        captures_king,
                     });
                 }
             }
         }
 
-        if self.king_in_check(army) {
+        // In check, the king must move if it can (unless the variant is
+        // off); capturing an enemy king is always allowed as well.
+        if self.config.force_king_move_in_check && self.king_in_check(army) {
             let king_moves: Vec<Move> = legal_moves
                 .iter()
-                .filter(|m| m.kind == PieceKind::King)
+                .filter(|m| m.kind == PieceKind::King || m.captures_king)
                 .cloned()
                 .collect();
             if !king_moves.is_empty() {
@@ -658,6 +1814,124 @@ impl Game {
         legal_moves
     }
 
+    /// Legal moves for every army, indexed by `Army::index`. Frozen and
+    /// stalemated armies get an empty list. Each army is generated exactly as
+    /// `generate_legal_moves` would; every candidate is still checked against
+    /// its own resulting position, so no attack maps are shared between them.
+    pub fn all_legal_moves(&self) -> [Vec<Move>; ARMY_COUNT] {
+        Army::ALL.map(|army| {
+            if self.state.is_stalemated(army) {
+                Vec::new()
+            } else {
+                self.generate_legal_moves(army)
+            }
+        })
+    }
+
+    /// Every square holding one of `army`'s `kind` pieces that can legally
+    /// move to `to`, in ascending order. More than one result means SAN input
+    /// needs a file or rank hint to pick the source.
+    pub fn disambiguate(&self, army: Army, kind: PieceKind, to: Square) -> Vec<Square> {
+        let mut sources: Vec<Square> = self
+            .generate_legal_moves(army)
+            .into_iter()
+            .filter(|m| m.kind == kind && m.to == to)
+            .map(|m| m.from)
+            .collect();
+        sources.sort_unstable();
+        sources.dedup();
+        sources
+    }
+
+    /// `army`'s move from `from` to `to` in SAN, as played from the current
+    /// position: "Nbd2", "exd5", "e8=Q+". Every army's pawns advance along
+    /// files, so a plain pawn move is just its destination. `+` marks a move
+    /// that leaves an enemy king attacked.
+    pub fn san(&self, army: Army, from: Square, to: Square, promotion: Option<PieceKind>) -> Result<String, String> {
+        let square = |sq: Square| format!("{}{}", file_char(sq), rank_char(sq));
+        let kind = match self.board.piece_at(from) {
+            Some((owner, kind)) if owner == army => kind,
+            _ => return Err(format!("No {} piece on {}", army.display_name(), square(from))),
+        };
+        let mut after = self.clone();
+        after.apply_move(army, from, to, promotion)?;
+
+        let mut san = if kind == PieceKind::Pawn {
+            if pawn_capture_mask(army, from) & (1u64 << to) != 0 {
+                format!("{}x{}", file_char(from), square(to))
+            } else {
+                square(to)
+            }
+        } else {
+            let mut san = kind.letter().to_string();
+            let others: Vec<Square> = self
+                .disambiguate(army, kind, to)
+                .into_iter()
+                .filter(|&other| other != from)
+                .collect();
+            if !others.is_empty() {
+                let shares_file = others.iter().any(|&other| file_char(other) == file_char(from));
+                let shares_rank = others.iter().any(|&other| rank_char(other) == rank_char(from));
+                if !shares_file || shares_rank {
+                    san.push(file_char(from));
+                }
+                if shares_file {
+                    san.push(rank_char(from));
+                }
+            }
+            if self.board.is_occupied(to) {
+                san.push('x');
+            }
+            san.push_str(&square(to));
+            san
+        };
+
+        if kind == PieceKind::Pawn {
+            if let Some((_, promoted)) = after.board.piece_at(to).filter(|&(_, k)| k != PieceKind::Pawn) {
+                san.push('=');
+                san.push(promoted.letter());
+            }
+        }
+        if army
+            .team()
+            .opponent()
+            .armies()
+            .iter()
+            .any(|&enemy| after.king_in_check(enemy))
+        {
+            san.push('+');
+        }
+        Ok(san)
+    }
+
+    /// Applies a move written in SAN (e.g. "Nf3", "Rae1", "exd6=Q") for
+    /// `army`, using `disambiguate` to resolve the source square.
+    pub fn apply_san(&mut self, army: Army, san: &str) -> Result<MoveOutcome, MoveError> {
+        if self.status != Status::Ongoing {
+            return Err(MoveError::GameOver);
+        }
+        let parsed = parse_move(san.trim()).map_err(|_| MoveError::ParseError)?;
+        let to = parsed.to.trailing_zeros() as Square;
+        let sources: Vec<Square> = self
+            .disambiguate(army, parsed.piece, to)
+            .into_iter()
+            .filter(|&from| parsed.from_file.map_or(true, |file| file_char(from) == file))
+            .filter(|&from| parsed.from_rank.map_or(true, |rank| (from / 8 + 1) as u64 == rank))
+            .collect();
+        let from = match sources.as_slice() {
+            [] => return Err(MoveError::InvalidMove(InvalidMoveReason::InvalidSourceOrTarget)),
+            [from] => *from,
+            _ => return Err(MoveError::AmbiguousSource),
+        };
+        let promotion = parsed
+            .special_move
+            .map(|SpecialMove::Promotion(kind)| kind);
+        if let Some(error) = self.promotion_error(army, parsed.piece, to, promotion) {
+            return Err(error);
+        }
+        self.apply_move(army, from, to, promotion)
+    }
+
     /// Get legal moves for an army, using cache if available
     pub fn legal_moves(&mut self, army: Army) -> &[Move] {
         // Check if cache is valid
@@ -679,13 +1953,205 @@ impl Game {
         }
     }
     
+    /// Switches the forced king move rule, dropping cached moves generated
+    /// under the old setting.
+    pub fn set_force_king_move_in_check(&mut self, forced: bool) {
+        self.config.force_king_move_in_check = forced;
+        self.clear_move_cache();
+    }
+
     /// Clear the move cache (call after any move is made)
     fn clear_move_cache(&mut self) {
         self.cached_legal_moves = None;
+        self.attack_cache.take();
     }
 
-    pub fn is_legal_move(&mut self, army: Army, from: Square, to: Square) -> bool {
-        self.legal_moves(army).iter().any(|m| m.from == from && m.to == to)
+    /// Whether `army` may move `from` to `to` right now: it must be the
+    /// army to move and not frozen. Answers from the move cache when it
+    /// holds `army`'s moves; otherwise only a pseudo-legal destination
+    /// costs a full legal move generation.
+    pub fn is_legal_move(&self, army: Army, from: Square, to: Square) -> bool {
+        if self.army_is_frozen(army) || army != self.current_army() {
+            return false;
+        }
+        let matches = |m: &Move| m.from == from && m.to == to;
+        if let Some((cached_army, moves)) = &self.cached_legal_moves {
+            if *cached_army == army {
+                return moves.iter().any(matches);
+            }
+        }
+        match self.board.piece_at(from) {
+            Some((owner, kind)) if owner == army => {
+                self.piece_moves_from(army, kind, from) & (1u64 << to) != 0
+                    && self.generate_legal_moves(army).iter().any(matches)
+            }
+            _ => false,
+        }
+    }
+
+    /// Explains why `army` may not move from `from` to `to`, checking the
+    /// reasons in the order a player would: whose turn it is, what is on the
+    /// source square, how the piece moves, and finally king safety.
+    pub fn explain_illegal(&self, army: Army, from: Square, to: Square) -> String {
+        let from_name = square_name(from);
+        let to_name = square_name(to);
+        if self.army_is_frozen(army) {
+            return format!("{}'s army is frozen", army.display_name());
+        }
+        if army != self.current_army() {
+            return format!(
+                "It is not {}'s turn ({} to move)",
+                army.display_name(),
+                self.current_army().display_name()
+            );
+        }
+        let kind = match self.board.piece_at(from) {
+            None => return format!("No piece at {}", from_name),
+            Some((owner, _)) if owner != army => {
+                return format!("The piece at {} belongs to {}", from_name, owner.display_name())
+            }
+            Some((_, kind)) => kind,
+        };
+        let piece = Self::piece_name(kind);
+        if let Some((owner, target)) = self.board.piece_at(to) {
+            if owner == army {
+                return format!(
+                    "Cannot capture your own {} on {}",
+                    Self::piece_name(target),
+                    to_name
+                );
+            }
+        }
+        match self.move_rejection(army, kind, from, to) {
+            Some(MoveError::Checked) => {
+                return format!(
+                    "{} is in check and {}-{} does not resolve it",
+                    army.display_name(),
+                    from_name,
+                    to_name
+                )
+            }
+            Some(MoveError::Pinned) => {
+                return format!(
+                    "Moving the {} from {} would leave the {} King in check",
+                    piece,
+                    from_name,
+                    army.display_name()
+                )
+            }
+            Some(_) => return format!("A {} cannot move from {} to {}", piece, from_name, to_name),
+            None => {}
+        }
+        let in_check = self.king_in_check(army);
+        let legal = self
+            .generate_legal_moves(army)
+            .iter()
+            .any(|m| m.from == from && m.to == to);
+        if in_check && !legal {
+            return format!("{} is in check and must move the King", army.display_name());
+        }
+        "Invalid move".to_string()
+    }
+
+    /// Why `army`'s `kind` on `from` may not go to `to` as far as its
+    /// movement and king safety are concerned: a square the piece can't
+    /// reach, or a move that leaves its king attacked (`Checked` when it
+    /// already was, `Pinned` otherwise). `None` when neither applies.
+    fn move_rejection(&self, army: Army, kind: PieceKind, from: Square, to: Square) -> Option<MoveError> {
+        if self.piece_moves_from(army, kind, from) & (1u64 << to) == 0 {
+            return Some(MoveError::InvalidMove(InvalidMoveReason::InvalidSourceOrTarget));
+        }
+        let leaves_check = self
+            .after_move(army, kind, from, to)
+            .map_or(true, |(next, _)| next.king_in_check(army));
+        if !leaves_check {
+            None
+        } else if self.king_in_check(army) {
+            Some(MoveError::Checked)
+        } else {
+            Some(MoveError::Pinned)
+        }
+    }
+
+    /// Why the `promotion` named for `army`'s `kind` landing on `to` can't
+    /// stand, checked before anything moves: it names a King or a Pawn, or
+    /// the move isn't a pawn reaching `army`'s promotion zone.
+    fn promotion_error(
+        &self,
+        army: Army,
+        kind: PieceKind,
+        to: Square,
+        promotion: Option<PieceKind>,
+    ) -> Option<MoveError> {
+        match promotion {
+            None => None,
+            Some(PieceKind::King | PieceKind::Pawn) => {
+                Some(MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotionTarget))
+            }
+            Some(_) if kind == PieceKind::Pawn && self.can_promote_at(army, to) => None,
+            Some(_) => Some(MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotion)),
+        }
+    }
+
+    /// The piece to ask `promote_pawn` for when `army`'s pawn lands on `to`,
+    /// and whether it was chosen by default: a privileged pawn that names
+    /// nothing becomes a queen, any other falls back to its officer.
+    fn promotion_target(&self, army: Army, to: Square, promotion: Option<PieceKind>) -> (PieceKind, bool) {
+        match promotion {
+            Some(kind) => (kind, false),
+            None if self.is_privileged_pawn(army) => (PieceKind::Queen, true),
+            None => (
+                self.board.pawn_type_at(army, to).unwrap_or(PieceKind::Queen),
+                false,
+            ),
+        }
+    }
+
+    /// The position after `army` plays `from`-`to`, for analysis. Only the
+    /// board and king squares change (a captured king's army is frozen, as
+    /// in `after_move`); the turn, history and stalemate flags are left as
+    /// they were, and `self` is untouched. `army` need not be on move.
+    pub fn peek_move(
+        &self,
+        army: Army,
+        from: Square,
+        to: Square,
+        promotion: Option<PieceKind>,
+    ) -> Result<Game, MoveError> {
+        if self.status != Status::Ongoing {
+            return Err(MoveError::GameOver);
+        }
+        let legal = self
+            .generate_legal_moves(army)
+            .into_iter()
+            .find(|m| m.from == from && m.to == to);
+        let Some(mv) = legal else {
+            // A safe move that still isn't legal is a non-king move while
+            // the rules force the king to answer check.
+            return Err(match self.board.piece_at(from) {
+                Some((owner, kind)) if owner == army => self
+                    .move_rejection(army, kind, from, to)
+                    .unwrap_or(MoveError::Checked),
+                _ => MoveError::InvalidMove(InvalidMoveReason::InvalidSourceOrTarget),
+            });
+        };
+        if let Some(error) = self.promotion_error(army, mv.kind, to, promotion) {
+            return Err(error);
+        }
+        let (next, _) = self
+            .after_move(army, mv.kind, from, to)
+            .ok_or(MoveError::InvalidMove(InvalidMoveReason::InvalidCaptureTarget))?;
+
+        let mut peeked = self.clone();
+        peeked.board = next.board;
+        peeked.state = next.state;
+        peeked.clear_move_cache();
+        if mv.kind == PieceKind::Pawn && peeked.can_promote_at(army, to) {
+            let (target, _) = peeked.promotion_target(army, to, promotion);
+            let promoted = peeked.promote_pawn(army, to, target);
+            debug_assert!(promoted, "promotion_error screens out every failing target");
+        }
+        Ok(peeked)
     }
 
     pub fn apply_move(
@@ -694,12 +2160,14 @@ impl Game {
         from: Square,
         to: Square,
         promotion: Option<PieceKind>,
-    ) -> Result<String, String> {
-        if self.army_is_frozen(army) {
-            return Err(format!("{}'s army is frozen", army.display_name()));
+    ) -> Result<MoveOutcome, MoveError> {
+        match self.status {
+            Status::Ongoing => {}
+            Status::Draw => return Err(MoveError::Rejected("The game is drawn".to_string())),
+            Status::Won(_) => return Err(MoveError::GameOver),
         }
-        if army != self.current_army() {
-            return Err(format!("It is not {}'s turn", army.display_name()));
+        if self.army_is_frozen(army) || army != self.current_army() {
+            return Err(MoveError::Rejected(self.explain_illegal(army, from, to)));
         }
 
         let legal_moves = self.generate_legal_moves(army);
@@ -707,55 +2175,558 @@ impl Game {
 
         let piece_kind = match current_move {
             Some(mv) => mv.kind,
-            None => return Err("Invalid move".to_string()),
+            None => return Err(MoveError::Rejected(self.explain_illegal(army, from, to))),
         };
+        if let Some(error) = self.promotion_error(army, piece_kind, to, promotion) {
+            return Err(error);
+        }
 
-        if let Some((target_army, target_kind)) = self.board.piece_at(to) {
-            if target_army == army {
-                return Err("Cannot capture own piece".to_string());
-            }
-            if target_kind == PieceKind::King {
-                self.capture_king(target_army);
-            } else {
-                self.board.remove_piece(target_army, target_kind, to);
-            }
+        let was_over =
+            self.observer.is_set() && (self.winning_team().is_some() || self.draw_condition());
+        let frozen_before = self.state.army_frozen;
+        let mut captured_at = to;
+        let mut captured = self.board.piece_at(to);
+        if matches!(captured, Some((target_army, _)) if target_army == army) {
+            return Err(MoveError::Rejected("Cannot capture own piece".to_string()));
         }
-        
-        // Save state before move for undo
+
+        self.remember_start();
+        // Save state before move for undo, ahead of any capture
         self.state_history.push((
             self.board.clone(),
             self.state.clone(),
             self.status.clone(),
         ));
 
+        if let Some((target_army, target_kind)) = captured {
+            if target_kind == PieceKind::King {
+                self.capture_king(target_army);
+            } else {
+                self.board.remove_piece(target_army, target_kind, to);
+                self.observer.notify(GameEvent::Captured {
+                    by: army,
+                    army: target_army,
+                    kind: target_kind,
+                    square: to,
+                });
+            }
+        } else if piece_kind == PieceKind::Pawn && self.state.en_passant == Some(to) {
+            if let Some((victim_army, victim_sq)) = self.en_passant_victim(army) {
+                self.board.remove_piece(victim_army, PieceKind::Pawn, victim_sq);
+                captured = Some((victim_army, PieceKind::Pawn));
+                captured_at = victim_sq;
+                self.observer.notify(GameEvent::Captured {
+                    by: army,
+                    army: victim_army,
+                    kind: PieceKind::Pawn,
+                    square: victim_sq,
+                });
+            }
+        }
+
+
         self.board.move_piece(army, piece_kind, from, to);
+        self.observer.notify(GameEvent::Moved {
+            army,
+            kind: piece_kind,
+            from,
+            to,
+        });
+        // A king taking a piece on an ally's throne: the capture above has
+        // already cleared the square, so the seizure sees the king there.
+        let mut throne_seized = None;
         if piece_kind == PieceKind::King {
             self.state.set_king_square(army, Some(to));
-            self.seize_throne_at(army, to);
+            throne_seized = self.seize_throne_at(army, to);
         }
 
-        if piece_kind == PieceKind::Pawn && self.can_promote_at(army, to) {
-            let target = promotion.unwrap_or(PieceKind::Queen);
-            if !self.promote_pawn(army, to, target) {
-                return Err("Promotion failed".to_string());
+        self.state.en_passant = None;
+        self.state.consecutive_passes = 0;
+        // Playing on declines any takeback still on offer.
+        self.state.pending_takeback = None;
+        if piece_kind == PieceKind::Pawn && self.config.double_step_and_en_passant {
+            if let Some((skipped, dest)) = pawn_double_step(army, from) {
+                if dest == to {
+                    self.state.en_passant = Some(skipped);
+                }
             }
         }
 
+        let mut promoted = None;
+        let mut promotion_defaulted = false;
+        if piece_kind == PieceKind::Pawn && self.can_promote_at(army, to) {
+            let target;
+            (target, promotion_defaulted) = self.promotion_target(army, to, promotion);
+            let accepted = self.promote_pawn(army, to, target);
+            debug_assert!(accepted, "promotion_error screens out every failing target");
+            promoted = self.board.piece_at(to).map(|(_, kind)| kind);
+        }
+
         for &other in Army::ALL.iter() {
             self.update_stalemate_status(other);
         }
+        if self.is_dead_position() {
+            self.status = Status::Draw;
+        }
         self.advance_to_next_army();
         self.clear_move_cache();
         
         // Track move in history
         self.move_history.push((army, from, to, promotion));
+        self.move_details.push(MoveDetail {
+            kind: piece_kind,
+            captured,
+        });
+        debug_assert_eq!(self.validate_invariants(), Ok(()));
+        self.record_win();
+        if !was_over {
+            self.notify_if_ended();
+        }
 
-        Ok(format!(
-            "{} moved {} to {}",
-            army.display_name(),
-            Self::piece_name(piece_kind),
-            Self::square_notation(to)
-        ))
+        let status_after = if self.status == Status::Ongoing && self.draw_condition() {
+            Status::Draw
+        } else {
+            self.status
+        };
+        Ok(MoveOutcome {
+            army,
+            moved: Move {
+                from,
+                to,
+                kind: piece_kind,
+                promotion,
+                captures_king: matches!(captured, Some((_, PieceKind::King))),
+            },
+            captured: captured.map(|(army, kind)| (army, kind, captured_at)),
+            king_captured: captured
+                .filter(|&(_, kind)| kind == PieceKind::King)
+                .map(|(army, _)| army),
+            revived: Army::ALL
+                .into_iter()
+                .filter(|a| frozen_before[a.index()] && !self.army_is_frozen(*a))
+                .collect(),
+            throne_seized,
+            promoted,
+            promotion_defaulted,
+            status_after,
+        })
+    }
+
+    /// Tags the 1-based `ply` with a review symbol from `ANNOTATION_SYMBOLS`,
+    /// replacing any earlier one. The move itself is not re-checked.
+    pub fn annotate(&mut self, ply: usize, symbol: &str) -> Result<(), String> {
+        if ply == 0 || ply > self.move_history.len() {
+            return Err(format!(
+                "No ply {} (the game has {})",
+                ply,
+                self.move_history.len()
+            ));
+        }
+        if !ANNOTATION_SYMBOLS.contains(&symbol) {
+            return Err(format!(
+                "Unknown annotation '{}' (use {})",
+                symbol,
+                ANNOTATION_SYMBOLS.join(" ")
+            ));
+        }
+        if self.annotations.len() < ply {
+            self.annotations.resize(ply, String::new());
+        }
+        self.annotations[ply - 1] = symbol.to_string();
+        Ok(())
+    }
+
+    /// The annotation on the 1-based `ply`, if it has one.
+    pub fn annotation(&self, ply: usize) -> Option<&str> {
+        self.annotations
+            .get(ply.checked_sub(1)?)
+            .map(String::as_str)
+            .filter(|symbol| !symbol.is_empty())
+    }
+
+    /// Plays `army`'s turn for a divination `roll`: a forced move when the
+    /// rolled kind has exactly one, a pass when it has none.
+    pub fn apply_divination_roll(
+        &mut self,
+        army: Army,
+        roll: u8,
+    ) -> Result<DivinationResult, String> {
+        if self.status != Status::Ongoing {
+            return Err("The game is over".to_string());
+        }
+        if self.army_is_frozen(army) {
+            return Err(format!("{}'s army is frozen", army.display_name()));
+        }
+        if army != self.current_army() {
+            return Err(format!(
+                "It is not {}'s turn ({} to move)",
+                army.display_name(),
+                self.current_army().display_name()
+            ));
+        }
+        let kinds = Self::die_to_piece_kind(roll);
+        let Some(&rolled) = kinds.first() else {
+            return Err(format!("Invalid die roll: {}", roll));
+        };
+
+        let mut moves = self.generate_divination_moves(army, roll);
+        match moves.len() {
+            0 => {
+                self.record_pass(army, rolled);
+                let active = self
+                    .config
+                    .turn_order
+                    .iter()
+                    .filter(|&&a| !self.army_is_frozen(a) && !self.state.is_stalemated(a))
+                    .count()
+                    .max(1);
+                if self.state.consecutive_passes >= active * DIVINATION_PASS_ROUNDS {
+                    self.status = Status::Draw;
+                    self.notify_if_ended();
+                    Ok(DivinationResult::Drawn)
+                } else {
+                    Ok(DivinationResult::Passed)
+                }
+            }
+            1 => {
+                let mv = moves.remove(0);
+                self.apply_move(army, mv.from, mv.to, None)?;
+                Ok(DivinationResult::Moved(mv))
+            }
+            _ => Ok(DivinationResult::Choose(moves)),
+        }
+    }
+
+    /// Records a divination "No Move" for `army`, which rolled `rolled`, and
+    /// passes the turn on. Undoable like a move.
+    pub(crate) fn record_pass(&mut self, army: Army, rolled: PieceKind) {
+        self.remember_start();
+        self.state_history
+            .push((self.board, self.state.clone(), self.status));
+        self.state.en_passant = None;
+        self.state.consecutive_passes += 1;
+        self.advance_to_next_army();
+        self.clear_move_cache();
+
+        let square = self.state.king_square(army).unwrap_or(0);
+        self.move_history.push((army, square, square, None));
+        self.move_details.push(MoveDetail {
+            kind: rolled,
+            captured: None,
+        });
+    }
+
+    fn notify_if_ended(&mut self) {
+        if !self.observer.is_set() {
+            return;
+        }
+        let winner = self.winning_team();
+        if winner.is_some() || self.draw_condition() {
+            self.observer.notify(GameEvent::GameEnded { winner });
+        }
+    }
+
+    /// Sets the game back to where it began (see `replay_start`), keeping
+    /// the rule toggles in `config` such as divination and the forced king
+    /// move. Moves, annotations, status and the recorded result are
+    /// cleared; the other headers and any observer stay.
+    pub fn reset_to_start(&mut self) {
+        let start = self.replay_start();
+        let config = GameConfig {
+            armies: start.config.armies,
+            turn_order: start.config.turn_order,
+            controller_map: start.config.controller_map,
+            ..self.config.clone()
+        };
+        let metadata = GameMetadata {
+            result: None,
+            ..self.metadata.clone()
+        };
+        let observer = std::mem::take(&mut self.observer);
+        *self = Game::with_config(start.board, config);
+        self.state = start.state;
+        self.metadata = metadata;
+        self.observer = observer;
+    }
+
+    /// Records the position about to be left as the game's start when no
+    /// ply has been played yet, so setup done after creation (pieces placed,
+    /// `set_to_move`, rule toggles) is part of it.
+    fn remember_start(&mut self) {
+        if self.move_history.is_empty() {
+            self.start = Some(self.start_position());
+        }
+    }
+
+    fn start_position(&self) -> StartPosition {
+        StartPosition {
+            board: self.board,
+            state: self.state.clone(),
+            config: self.config.clone(),
+        }
+    }
+
+    /// A fresh game at the position this one began from: the current one
+    /// if no ply has been played, else the one recorded with the first
+    /// ply. Games saved before that was recorded start from the array named
+    /// in `metadata.array` (or the first array sharing the game's turn
+    /// order) under the game's current rules.
+    pub fn replay_start(&self) -> Game {
+        let start = match &self.start {
+            _ if self.move_history.is_empty() => self.start_position(),
+            Some(start) => start.clone(),
+            None => self.array_start(),
+        };
+        let mut replay = Game::with_config(start.board, start.config);
+        replay.state = start.state;
+        replay.metadata.array = self.metadata.array.clone();
+        replay
+    }
+
+    fn array_start(&self) -> StartPosition {
+        let spec = self
+            .array_name()
+            .and_then(crate::engine::arrays::find_array_by_name)
+            .or_else(|| {
+                crate::engine::arrays::available_arrays()
+                    .iter()
+                    .find(|spec| spec.turn_order == self.config.turn_order)
+                    .copied()
+            })
+            .unwrap_or_else(crate::engine::arrays::default_array);
+        let fresh = Game::from_array_spec(spec);
+        StartPosition {
+            board: fresh.board,
+            state: fresh.state,
+            config: GameConfig {
+                armies: fresh.config.armies,
+                turn_order: fresh.config.turn_order,
+                controller_map: fresh.config.controller_map,
+                ..self.config.clone()
+            },
+        }
+    }
+
+    /// The board before the first ply and after each ply of
+    /// `move_history`, replayed and checked as `verify_history` does.
+    pub fn replay_boards(&self) -> Result<Vec<Board>, (usize, String)> {
+        let mut boards = Vec::with_capacity(self.move_history.len() + 1);
+        self.replay_plies_with(self.move_history.len(), |replay| boards.push(replay.board))?;
+        Ok(boards)
+    }
+
+    /// The game after replaying the first `plies` entries of `move_history`
+    /// from `replay_start`, checking each as `verify_history` does.
+    fn replay_plies(&self, plies: usize) -> Result<Game, (usize, String)> {
+        self.replay_plies_with(plies, |_| {})
+    }
+
+    /// `replay_plies`, handing `visit` the start and the game after each ply.
+    fn replay_plies_with(
+        &self,
+        plies: usize,
+        mut visit: impl FnMut(&Game),
+    ) -> Result<Game, (usize, String)> {
+        let mut replay = self.replay_start();
+        visit(&replay);
+        let details = (self.move_details.len() == self.move_history.len())
+            .then_some(&self.move_details);
+        let format_move = |from: Square, to: Square| {
+            format!("{}{}-{}{}", file_char(from), rank_char(from), file_char(to), rank_char(to))
+        };
+
+        for (ply, entry) in self.move_history[..plies].iter().enumerate() {
+            let (army, from, to, promotion) = *entry;
+            if is_pass(entry) {
+                if army != replay.current_army() {
+                    return Err((
+                        ply,
+                        format!("{} passed out of turn", army.display_name()),
+                    ));
+                }
+                let rolled = details.map_or(PieceKind::King, |details| details[ply].kind);
+                replay.record_pass(army, rolled);
+                visit(&replay);
+                continue;
+            }
+            replay
+                .apply_move(army, from, to, promotion)
+                .map_err(|e| (ply, format!("{} {}: {}", army.display_name(), format_move(from, to), e)))?;
+            if let Some(details) = details {
+                if replay.move_details[ply] != details[ply] {
+                    return Err((
+                        ply,
+                        format!(
+                            "{} {} was recorded as {:?} but replays as {:?}",
+                            army.display_name(),
+                            format_move(from, to),
+                            details[ply],
+                            replay.move_details[ply]
+                        ),
+                    ));
+                }
+            }
+            visit(&replay);
+        }
+
+        Ok(replay)
+    }
+
+    /// Replays `move_history` from `replay_start` and checks that every ply
+    /// was legal, matches its recorded piece and capture, and that the
+    /// replay ends on the stored position. On failure returns the 0-based
+    /// index of the first bad ply (the history length if only the final
+    /// position differs) and why.
+    pub fn verify_history(&self) -> Result<(), (usize, String)> {
+        let replay = self.replay_plies(self.move_history.len())?;
+        let ply = self.move_history.len();
+        if replay.board.iter_pieces().ne(self.board.iter_pieces()) {
+            return Err((ply, "replayed board differs from the saved board".to_string()));
+        }
+        if replay.current_army() != self.current_army() {
+            return Err((
+                ply,
+                format!(
+                    "replay leaves {} to move but the save has {}",
+                    replay.current_army().display_name(),
+                    self.current_army().display_name()
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Counts the leaf nodes of the legal move tree `depth` plies deep,
+    /// following the turn order from the current army.
+    pub fn perft(&mut self, depth: u8) -> u64 {
+        // Exploring the tree is not play, so the observer sits it out.
+        let observer = self.observer.set(None);
+        let nodes = self.perft_nodes(depth);
+        self.observer.set(observer);
+        nodes
+    }
+
+    /// Walks the perft tree to `depth`, generating every position's moves
+    /// the way `generate_legal_moves` does, with timers around each step.
+    pub fn profile_movegen(&mut self, depth: u8) -> MoveGenProfile {
+        let observer = self.observer.set(None);
+        let mut profile = MoveGenProfile::default();
+        self.profile_nodes(depth, &mut profile);
+        self.observer.set(observer);
+        profile
+    }
+
+    fn profile_nodes(&mut self, depth: u8, profile: &mut MoveGenProfile) {
+        use std::time::Instant;
+
+        if depth == 0 {
+            return;
+        }
+        let army = self.current_army();
+        if self.army_is_frozen(army) {
+            return;
+        }
+        profile.positions += 1;
+
+        let evasion_mask = self.check_evasion_mask(army);
+        let mut moves = Vec::new();
+        for (from, kind) in self.board.all_pieces_for_army(army) {
+            let start = Instant::now();
+            let mut destinations = self.piece_moves_from(army, kind, from);
+            if let Some(mask) = evasion_mask.filter(|_| kind != PieceKind::King) {
+                destinations &= mask;
+            }
+            profile.pseudo_legal[kind.index()] += start.elapsed();
+            profile.pieces[kind.index()] += 1;
+
+            while destinations != 0 {
+                let to = destinations.trailing_zeros() as Square;
+                destinations &= destinations - 1;
+                let start = Instant::now();
+                let legal = self
+                    .after_move(army, kind, from, to)
+                    .filter(|(next, _)| !next.king_in_check(army));
+                profile.self_check_filter += start.elapsed();
+                profile.candidates += 1;
+                if let Some((_, captures_king)) = legal {
+                    moves.push((from, to, kind == PieceKind::King || captures_king));
+                }
+            }
+        }
+        if self.config.force_king_move_in_check
+            && self.king_in_check(army)
+            && moves.iter().any(|&(_, _, forced)| forced)
+        {
+            moves.retain(|&(_, _, forced)| forced);
+        }
+
+        for (from, to, _) in moves {
+            let saved = self.clone();
+            if self.apply_move(army, from, to, None).is_ok() {
+                self.profile_nodes(depth - 1, profile);
+            }
+            *self = saved;
+        }
+    }
+
+    /// Every legal line `depth` plies deep from the current position,
+    /// following the turn order as `perft` does. Depth is capped at
+    /// `MAX_TREE_DEPTH`.
+    pub fn move_tree(&self, depth: u8) -> MoveNode {
+        let mut game = self.clone();
+        game.observer.set(None);
+        MoveNode {
+            army: None,
+            mv: None,
+            children: game.move_subtrees(depth.min(MAX_TREE_DEPTH)),
+        }
+    }
+
+    fn move_subtrees(&mut self, depth: u8) -> Vec<MoveNode> {
+        if depth == 0 || self.status != Status::Ongoing {
+            return Vec::new();
+        }
+        let army = self.current_army();
+        let moves = self.legal_moves(army).to_vec();
+        let mut children = Vec::with_capacity(moves.len());
+        for mv in moves {
+            let saved = self.clone();
+            let grandchildren = match self.apply_move(army, mv.from, mv.to, None) {
+                Ok(_) => self.move_subtrees(depth - 1),
+                Err(_) => Vec::new(),
+            };
+            *self = saved;
+            children.push(MoveNode {
+                army: Some(army),
+                mv: Some(mv),
+                children: grandchildren,
+            });
+        }
+        children
+    }
+
+    fn perft_nodes(&mut self, depth: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let army = self.current_army();
+        let moves = self.legal_moves(army).to_vec();
+
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        let mut nodes = 0u64;
+        for mv in moves {
+            let saved = self.clone();
+            if self.apply_move(army, mv.from, mv.to, None).is_ok() {
+                nodes += self.perft_nodes(depth - 1);
+            }
+            *self = saved;
+        }
+
+        nodes
     }
 
     /// Public for testing purposes only
@@ -769,6 +2740,64 @@ impl Game {
         }
     }
     
+    /// Passes the turn on while the army to move has no legal moves, so
+    /// drivers never wait on a stalemated army. Returns `false` once the
+    /// game is over, drawing it if every army is stuck. Each skip is
+    /// recorded as a pass, so `undo` and replays step through it.
+    ///
+    /// An army in check with no legal moves is not stalemated: it passes
+    /// this one turn and its king stays where it is, to be captured by the
+    /// checking army on its next move.
+    pub fn skip_stuck_armies(&mut self) -> bool {
+        for _ in 0..self.config.turn_order.len() {
+            if self.winning_team().is_some() || self.draw_condition() {
+                return false;
+            }
+            let army = self.current_army();
+            if !self.legal_moves(army).is_empty() {
+                return true;
+            }
+            let in_check = self.king_in_check(army);
+            self.record_pass(army, PieceKind::King);
+            if !in_check {
+                self.state.set_stalemate(army, true);
+            }
+        }
+        self.status = Status::Draw;
+        false
+    }
+    
+    /// Hands the turn to the next army without moving, for null-move
+    /// pruning and "what would they play" analysis. The board and move
+    /// history are untouched; a pending en passant lapses as it would after
+    /// any other ply. Not allowed while the side to move is in check.
+    pub fn apply_null_move(&mut self) -> Result<(), MoveError> {
+        if self.status != Status::Ongoing {
+            return Err(MoveError::GameOver);
+        }
+        if self.king_in_check(self.current_army()) {
+            return Err(MoveError::Checked);
+        }
+        self.null_moves.push((self.state.clone(), self.state_history.len()));
+        self.state.en_passant = None;
+        self.advance_to_next_army();
+        self.clear_move_cache();
+        Ok(())
+    }
+
+    /// Takes back the latest `apply_null_move`. Moves played after it must
+    /// be undone first.
+    pub fn undo_null_move(&mut self) -> Result<(), String> {
+        let &(_, plies) = self.null_moves.last().ok_or_else(|| "No null move to undo".to_string())?;
+        if plies != self.state_history.len() {
+            return Err("Undo the moves played since the null move first".to_string());
+        }
+        let (state, _) = self.null_moves.pop().unwrap();
+        self.state = state;
+        self.clear_move_cache();
+        Ok(())
+    }
+
     pub fn undo(&mut self, count: usize) -> Result<usize, String> {
         let available = self.state_history.len();
         if available == 0 {
@@ -783,13 +2812,77 @@ impl Game {
                 self.state = state;
                 self.status = status;
                 self.move_history.pop();
+                self.move_details.pop();
                 self.clear_move_cache();
             }
         }
+        self.annotations.truncate(self.move_history.len());
+        self.debug_assert_king_squares();
         
         Ok(to_undo)
     }
 
+    /// Asks to take back `army`'s last move. Nothing changes until an
+    /// opponent calls `grant_takeback`; a move played meanwhile declines it.
+    pub fn request_takeback(&mut self, army: Army) -> Result<(), String> {
+        if let Some(pending) = self.state.pending_takeback {
+            return Err(format!("{} already has a takeback pending", pending.display_name()));
+        }
+        if self.last_move_index(army).is_none() {
+            return Err(format!("{} has no move to take back", army.display_name()));
+        }
+        self.state.pending_takeback = Some(army);
+        Ok(())
+    }
+
+    /// `granter` grants the pending takeback: the game returns to just
+    /// before the requesting army's last move. Only an army of the opposing
+    /// team may grant it. Returns the number of plies undone.
+    pub fn grant_takeback(&mut self, granter: Army) -> Result<usize, String> {
+        let army = self
+            .state
+            .pending_takeback
+            .ok_or_else(|| "No takeback has been requested".to_string())?;
+        if granter.team() == army.team() {
+            return Err(format!(
+                "{} cannot grant {}'s takeback; an opponent must",
+                granter.display_name(),
+                army.display_name()
+            ));
+        }
+        let ply = self
+            .last_move_index(army)
+            .ok_or_else(|| format!("{} has no move to take back", army.display_name()))?;
+        let plies = self.move_history.len() - ply;
+
+        if self.state_history.len() >= plies {
+            self.undo(plies)?;
+        } else {
+            // Loaded games carry no undo snapshots, so rebuild by replay.
+            let replay = self
+                .replay_plies(ply)
+                .map_err(|(bad, reason)| format!("Cannot replay ply {}: {}", bad + 1, reason))?;
+            self.board = replay.board;
+            self.state = replay.state;
+            self.status = replay.status;
+            self.move_history = replay.move_history;
+            self.move_details = replay.move_details;
+            self.state_history = replay.state_history;
+            self.annotations.truncate(ply);
+            self.clear_move_cache();
+        }
+        self.state.pending_takeback = None;
+        self.debug_assert_king_squares();
+        Ok(plies)
+    }
+
+    /// Index in `move_history` of `army`'s last move, passes excluded.
+    fn last_move_index(&self, army: Army) -> Option<usize> {
+        self.move_history
+            .iter()
+            .rposition(|entry| entry.0 == army && !is_pass(entry))
+    }
+
     fn piece_name(kind: PieceKind) -> &'static str {
         match kind {
             PieceKind::King => "King",
@@ -800,16 +2893,50 @@ impl Game {
             PieceKind::Pawn => "Pawn",
         }
     }
-
-    fn square_notation(square: Square) -> String {
-        let file = (square % 8) as u8;
-        let rank = (square / 8) as u8;
-        format!("{}{}", (b'a' + file) as char, rank + 1)
-    }
 }
 
 impl Default for Game {
     fn default() -> Game {
         Self::from_array_spec(&TABLET_OF_FIRE_PROTOTYPE)
     }
-}
\ No newline at end of file
+}
+
+/// The rank `army`'s pawns start on, counted from 0. Pawns advance along
+/// ranks as in `piece_moves_from`, so Black shares Red's home rank and
+/// Yellow shares Blue's.
+fn pawn_home_rank(army: Army) -> u8 {
+    match army {
+        Army::Blue => 1,
+        Army::Red => 6,
+        Army::Black => 6,
+        Army::Yellow => 1,
+    }
+}
+
+/// The square skipped and the destination of a two-square pawn advance
+/// from `from_sq`, if the pawn is on its home rank.
+fn pawn_double_step(army: Army, from_sq: Square) -> Option<(Square, Square)> {
+    if from_sq / 8 != pawn_home_rank(army) {
+        return None;
+    }
+    let step = 8 * army.pawn_direction() as i16;
+    let skipped = from_sq as i16 + step;
+    Some((skipped as Square, (skipped + step) as Square))
+}
+
+/// Squares a pawn on `from_sq` could capture on, whether or not they are
+/// occupied.
+fn pawn_capture_mask(army: Army, from_sq: Square) -> u64 {
+    let from_mask = 1u64 << from_sq;
+    let left_capture = match army.pawn_direction() {
+        1 => (from_mask << 7) & !MASK_FILE_H,
+        -1 => (from_mask >> 9) & !MASK_FILE_H,
+        _ => 0,
+    };
+    let right_capture = match army.pawn_direction() {
+        1 => (from_mask << 9) & !MASK_FILE_A,
+        -1 => (from_mask >> 7) & !MASK_FILE_A,
+        _ => 0,
+    };
+    left_capture | right_capture
+}
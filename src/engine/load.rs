@@ -0,0 +1,349 @@
+use crate::engine::arrays::{default_array, find_array_by_name, ArraySpec};
+use crate::engine::board::Board;
+use crate::engine::notation::parse_coordinate_move;
+use crate::engine::game::{split_annotation, Game, GameMetadata};
+use crate::engine::types::{file_char, parse_square, rank_char, Army, PieceKind, Square, ARMY_COUNT};
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::SeedableRng;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+
+/// The file name that stands for stdin when importing and stdout when
+/// exporting, so games can be piped between commands.
+pub const STDIO_PATH: &str = "-";
+
+/// Why an array, generated position or imported file could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// No array has this name.
+    UnknownArray(String),
+    /// A piece list that can't be parsed: unknown army or piece letter,
+    /// missing separator, or no pieces at all.
+    BadPieceSpec(String),
+    /// A square name outside a1-h8.
+    BadSquare(String),
+    /// Two pieces were placed on the same square.
+    DuplicateSquare(Square),
+    /// The source file could not be read.
+    Io(String),
+    /// An array file that doesn't describe a playable array.
+    InvalidArray(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::UnknownArray(name) => write!(f, "Unknown array: {}", name),
+            LoadError::BadPieceSpec(reason) => write!(f, "{}", reason),
+            LoadError::BadSquare(square) => write!(f, "Invalid square {}", square),
+            LoadError::DuplicateSquare(square) => write!(
+                f,
+                "Square {}{} is occupied more than once",
+                file_char(*square),
+                rank_char(*square)
+            ),
+            LoadError::Io(reason) => write!(f, "{}", reason),
+            LoadError::InvalidArray(reason) => write!(f, "Invalid array: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// A fresh game from the named array, or the default array when `name` is
+/// `None`.
+pub fn from_array(name: Option<&str>) -> Result<Game, LoadError> {
+    let spec = match name {
+        Some(name) => {
+            find_array_by_name(name).ok_or_else(|| LoadError::UnknownArray(name.to_string()))?
+        }
+        None => default_array(),
+    };
+    Ok(Game::from_array_spec(spec))
+}
+
+/// Builds a game from `--generate` input such as `"Kb1,Qc2:blue Ke8:red"`.
+pub fn from_generate(spec: &str) -> Result<Game, LoadError> {
+    let mut board = Board::new(&[]);
+    let mut piece_count = 0;
+    for army_spec in spec.split_whitespace() {
+        let (pieces, army_name) = army_spec.split_once(':').ok_or_else(|| {
+            LoadError::BadPieceSpec("Invalid format. Use: 'Kb1,Qc2:blue Ke8:red'".to_string())
+        })?;
+        piece_count += place_pieces(&mut board, army_name, pieces)?;
+    }
+    finish(board, piece_count)
+}
+
+/// An army's starting complement apart from its king, which random
+/// positions draw from so no army has more of a piece than it could.
+const RANDOM_OFFICERS: [PieceKind; 8] = [
+    PieceKind::Queen,
+    PieceKind::Bishop,
+    PieceKind::Knight,
+    PieceKind::Rook,
+    PieceKind::Pawn,
+    PieceKind::Pawn,
+    PieceKind::Pawn,
+    PieceKind::Pawn,
+];
+
+/// Attempts at a random position before giving up on leaving the side to
+/// move out of check.
+const RANDOM_POSITION_ATTEMPTS: usize = 1_000;
+
+/// A random position with `pieces` pieces per army, its king among them,
+/// on distinct squares. The rest are drawn from the army's starting pieces;
+/// pawns never start on their promotion zone and the side to move is never
+/// left in check. The same `seed` always gives the same position.
+pub fn random_position(pieces: usize, seed: u64) -> Result<Game, LoadError> {
+    if !(1..=RANDOM_OFFICERS.len() + 1).contains(&pieces) {
+        return Err(LoadError::BadPieceSpec(format!(
+            "Random positions need 1 to {} pieces per army, not {}",
+            RANDOM_OFFICERS.len() + 1,
+            pieces
+        )));
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..RANDOM_POSITION_ATTEMPTS {
+        let mut board = Board::new(&[]);
+        for army in Army::ALL {
+            let mut kinds = RANDOM_OFFICERS;
+            kinds.shuffle(&mut rng);
+            for kind in std::iter::once(PieceKind::King).chain(kinds.into_iter().take(pieces - 1)) {
+                let barred = if kind == PieceKind::Pawn { board.promotion_zones[army.index()] } else { 0 };
+                let open: Vec<Square> = (0..64)
+                    .filter(|&square| board.is_empty(square) && barred & (1u64 << square) == 0)
+                    .collect();
+                let &square = open
+                    .choose(&mut rng)
+                    .ok_or_else(|| LoadError::BadPieceSpec("No room for a random piece".to_string()))?;
+                board.place_piece(army, kind, square);
+            }
+        }
+        let game = finish(board, pieces * ARMY_COUNT)?;
+        if !game.king_in_check(game.current_army()) {
+            debug_assert_eq!(game.validate_invariants(), Ok(()));
+            return Ok(game);
+        }
+    }
+    Err(LoadError::BadPieceSpec(format!(
+        "No random position left the side to move out of check (seed {})",
+        seed
+    )))
+}
+
+/// Builds a game from `Game::to_compact` output: one `army:Ke1,Qd1` line
+/// per army.
+pub fn from_compact(s: &str) -> Result<Game, LoadError> {
+    let mut board = Board::new(&[]);
+    let mut piece_count = 0;
+    for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (army_name, pieces) = line.split_once(':').ok_or_else(|| {
+            LoadError::BadPieceSpec(format!("Invalid compact line '{}'. Use: blue:Ke1,Qd1", line))
+        })?;
+        piece_count += place_pieces(&mut board, army_name, pieces)?;
+    }
+    finish(board, piece_count)
+}
+
+/// A custom array as written in an `--array-file`. Anything left out is
+/// taken from the default array.
+#[derive(Deserialize)]
+struct ArrayFile {
+    name: String,
+    #[serde(default)]
+    description: String,
+    turn_order: Option<[Army; ARMY_COUNT]>,
+    /// Pieces in `Game::to_compact` form, one `army:Ke1,Qd1` line per army.
+    position: String,
+    /// Promotion bitboards by army, e.g. `{"Blue": 18374686479671623680}`.
+    #[serde(default)]
+    promotion_zones: HashMap<Army, u64>,
+}
+
+/// Parses an array file (JSON, see `ArrayFile`) into a spec, rejecting
+/// promotion zones the pawns can't march toward. The spec's strings and
+/// placements are leaked, as built-in arrays are `'static`.
+pub fn array_spec_from_json(json: &str) -> Result<ArraySpec, LoadError> {
+    let file: ArrayFile =
+        serde_json::from_str(json).map_err(|e| LoadError::InvalidArray(e.to_string()))?;
+    let defaults = default_array();
+
+    let mut promotion_zones = defaults.promotion_zones;
+    for (army, zone) in file.promotion_zones {
+        promotion_zones[army.index()] = zone;
+    }
+    let placements: Vec<(Army, PieceKind, u64)> = from_compact(&file.position)?
+        .board
+        .iter_pieces()
+        .map(|(square, army, kind)| (army, kind, 1u64 << square))
+        .collect();
+
+    let spec = ArraySpec {
+        name: Box::leak(file.name.into_boxed_str()),
+        description: Box::leak(file.description.into_boxed_str()),
+        turn_order: file.turn_order.unwrap_or(defaults.turn_order),
+        promotion_zones,
+        placements: Box::leak(placements.into_boxed_slice()),
+        allow_incomplete: true,
+        ..defaults.clone()
+    };
+    spec.validate_promotion_zones().map_err(LoadError::InvalidArray)?;
+    Ok(spec)
+}
+
+/// A fresh game from the array described in the file at `path`.
+pub fn from_array_file(path: &str) -> Result<Game, LoadError> {
+    let spec = array_spec_from_json(&read_source(path)?)?;
+    Ok(Game::from_array_spec(&spec))
+}
+
+/// Reads a file to import, or stdin for `STDIO_PATH`, mapping failures to
+/// `LoadError::Io`.
+pub fn read_source(path: &str) -> Result<String, LoadError> {
+    read_from(open_source(path)?, path)
+}
+
+/// Opens a file to import, or stdin for `STDIO_PATH`.
+pub fn open_source(path: &str) -> Result<Box<dyn Read>, LoadError> {
+    if path == STDIO_PATH {
+        return Ok(Box::new(std::io::stdin()));
+    }
+    std::fs::File::open(path)
+        .map(|file| Box::new(file) as Box<dyn Read>)
+        .map_err(|e| LoadError::Io(format!("Failed to read {}: {}", path, e)))
+}
+
+fn read_from(mut reader: impl Read, path: &str) -> Result<String, LoadError> {
+    let mut contents = String::new();
+    reader
+        .read_to_string(&mut contents)
+        .map_err(|e| LoadError::Io(format!("Failed to read {}: {}", path, e)))?;
+    Ok(contents)
+}
+
+/// A game read by `from_pgn`.
+pub struct PgnImport {
+    pub game: Game,
+    /// Plies replayed, passes included.
+    pub moves: usize,
+    /// One line per move that could not be played and was skipped.
+    pub warnings: Vec<String>,
+}
+
+/// Replays coordinate-dialect PGN (`B:e2-e3`, `Bk:pass`) read from `reader`.
+/// Army tags are read by `Army::from_pgn_tag`, so legacy `K:` files and
+/// full names (`Blue:e2-e3`) load too. The `Array` header picks the
+/// starting position and the other headers become the game's metadata.
+pub fn from_pgn(reader: impl Read) -> Result<PgnImport, LoadError> {
+    let contents = read_from(reader, "PGN")?;
+
+    // Headers first: the Array tag picks the starting position
+    let mut metadata = GameMetadata::default();
+    for line in contents.lines() {
+        if let Some((tag, value)) = parse_pgn_header(line.trim()) {
+            metadata.set_header(tag, &value);
+        }
+    }
+    let spec = match &metadata.array {
+        Some(name) => find_array_by_name(name).ok_or_else(|| LoadError::UnknownArray(name.clone()))?,
+        None => default_array(),
+    };
+    metadata.array = Some(spec.name.to_string());
+
+    let mut game = Game::from_array_spec(spec);
+    game.metadata = metadata;
+    let mut moves = 0;
+    let mut warnings = Vec::new();
+
+    for line in contents.lines().map(str::trim) {
+        // Skip headers and empty lines
+        if line.is_empty() || line.starts_with('[') {
+            continue;
+        }
+
+        // Parse moves (format: B:e2-e3 R:e7-e6), skipping move numbers
+        for token in line.split_whitespace().filter(|token| !token.ends_with('.')) {
+            let Some((code, rest)) = token.split_once(':') else {
+                continue;
+            };
+            let Some(army) = Army::from_pgn_tag(code) else {
+                continue;
+            };
+
+            let (move_str, note) = split_annotation(rest);
+            if move_str == "pass" {
+                game.record_pass(army, PieceKind::King);
+            } else {
+                let Ok((from, to, promotion)) = parse_coordinate_move(move_str) else {
+                    continue;
+                };
+                if let Err(e) = game.apply_move(army, from, to, promotion) {
+                    warnings.push(format!("Failed to apply move {}: {}", token, e));
+                    continue;
+                }
+            }
+            moves += 1;
+            if let Some(symbol) = note {
+                let _ = game.annotate(game.move_history.len(), symbol);
+            }
+        }
+    }
+
+    Ok(PgnImport { game, moves, warnings })
+}
+
+/// Splits a PGN tag pair such as `[Event "Casual"]` into its tag and value,
+/// undoing the `\\` and `\"` escapes.
+fn parse_pgn_header(line: &str) -> Option<(&str, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (tag, value) = inner.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+    Some((tag, unescaped))
+}
+
+/// Parses a single piece such as `"Qd1"`.
+pub fn parse_piece(spec: &str) -> Result<(PieceKind, Square), LoadError> {
+    let spec = spec.trim();
+    let Some(letter) = spec.chars().next().filter(|_| spec.len() >= 2) else {
+        return Err(LoadError::BadPieceSpec(format!("Invalid piece spec: {}", spec)));
+    };
+    let kind = PieceKind::from_letter(letter)
+        .ok_or_else(|| LoadError::BadPieceSpec(format!("Unknown piece: {}", letter)))?;
+    let square_str = &spec[letter.len_utf8()..];
+    let square = parse_square(square_str).map_err(|_| LoadError::BadSquare(square_str.to_string()))?;
+    Ok((kind, square))
+}
+
+fn place_pieces(board: &mut Board, army_name: &str, pieces: &str) -> Result<usize, LoadError> {
+    let army = Army::from_str(army_name.trim())
+        .ok_or_else(|| LoadError::BadPieceSpec(format!("Unknown army: {}", army_name.trim())))?;
+    let mut count = 0;
+    for spec in pieces.split(',') {
+        let (kind, square) = parse_piece(spec)?;
+        board
+            .try_place_piece(army, kind, square)
+            .map_err(|_| LoadError::DuplicateSquare(square))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn finish(board: Board, piece_count: usize) -> Result<Game, LoadError> {
+    if piece_count == 0 {
+        return Err(LoadError::BadPieceSpec("No pieces specified".to_string()));
+    }
+    Ok(Game::new(board))
+}
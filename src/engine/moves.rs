@@ -65,7 +65,7 @@ const fn precompute_king_moves(index: u8) -> u64 {
 }
 
 pub fn compute_king_moves(board: &Board, army: Army) -> u64 {
-    let king = board.by_army_kind[army as usize][PieceKind::King as usize];
+    let king = board.pieces(army, PieceKind::King);
     if king == 0 {
         return 0;
     }
@@ -93,7 +93,7 @@ const fn precompute_knight_moves(index: u8) -> u64 {
 pub fn compute_knights_moves(board: &Board, army: Army) -> u64 {
     let mut moves = 0u64;
     let own_pieces = board.occupancy_by_army[army as usize];
-    let mut knights = board.by_army_kind[army as usize][PieceKind::Knight as usize];
+    let mut knights = board.pieces(army, PieceKind::Knight);
 
     while knights != 0 {
         let index = knights.trailing_zeros();
@@ -241,7 +241,7 @@ pub fn get_sliding_attacks(
 }
 
 pub fn compute_rooks_moves(board: &Board, army: Army) -> u64 {
-    let rooks = board.by_army_kind[army as usize][PieceKind::Rook as usize];
+    let rooks = board.pieces(army, PieceKind::Rook);
     let own_pieces = board.occupancy_by_army[army as usize];
     let occupied = board.all_occupancy;
     get_sliding_moves(rooks, &ROOK_RAYS_DIRECTIONS, own_pieces, occupied)
@@ -296,7 +296,7 @@ const fn precompute_bishop_rays(index: u8) -> [u64; 4] {
 
 pub fn compute_bishops_moves(board: &Board, army: Army) -> u64 {
     let mut moves = 0u64;
-    let mut bishops = board.by_army_kind[army.index()][PieceKind::Bishop.index()];
+    let mut bishops = board.pieces(army, PieceKind::Bishop);
     let own_pieces = board.occupancy_by_army[army.index()];
 
     const VECTORS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
@@ -367,7 +367,7 @@ const fn precompute_queen_rays(index: u8) -> [u64; 8] {
 
 pub fn compute_queens_moves(board: &Board, army: Army) -> u64 {
     let mut moves = 0u64;
-    let mut queens = board.by_army_kind[army.index()][PieceKind::Queen.index()];
+    let mut queens = board.pieces(army, PieceKind::Queen);
     let own_pieces = board.occupancy_by_army[army.index()];
 
     while queens != 0 {
@@ -416,7 +416,7 @@ pub fn compute_pawns_moves(board: &Board, army: Army) -> (u64, u64) {
     let mut moves = 0u64;
     let mut attack_moves = 0u64;
     let own_pieces = board.occupancy_by_army[army.index()];
-    let mut pawns = board.by_army_kind[army.index()][PieceKind::Pawn.index()];
+    let mut pawns = board.pieces(army, PieceKind::Pawn);
 
     while pawns != 0 {
         let index = pawns.trailing_zeros() as usize;
@@ -425,33 +425,17 @@ pub fn compute_pawns_moves(board: &Board, army: Army) -> (u64, u64) {
         let file = (index % 8) as i8;
         let rank = (index / 8) as i8;
 
-        let (forward, diag_left, diag_right) = match army {
-            Army::Blue => (
-                offset_square(file, rank, 0, 1),
-                offset_square(file, rank, -1, 1),
-                offset_square(file, rank, 1, 1),
-            ),
-            Army::Red => (
-                offset_square(file, rank, 0, -1),
-                offset_square(file, rank, -1, -1),
-                offset_square(file, rank, 1, -1),
-            ),
-            Army::Black => (
-                offset_square(file, rank, 1, 0),
-                offset_square(file, rank, 1, 1),
-                offset_square(file, rank, 1, -1),
-            ),
-            Army::Yellow => (
-                offset_square(file, rank, -1, 0),
-                offset_square(file, rank, -1, 1),
-                offset_square(file, rank, -1, -1),
-            ),
-        };
+        // Every army advances along its file, as `Game` generates pawn moves.
+        let step = army.pawn_direction();
+        let (forward, diag_left, diag_right) = (
+            offset_square(file, rank, 0, step),
+            offset_square(file, rank, -1, step),
+            offset_square(file, rank, 1, step),
+        );
 
         if let Some(dest) = forward {
-            let dest_mask = 1u64 << dest;
-            if board.all_occupancy & dest_mask == 0 {
-                moves |= dest_mask;
+            if board.is_empty(dest) {
+                moves |= 1u64 << dest;
             }
         }
 
@@ -468,6 +452,18 @@ pub fn compute_pawns_moves(board: &Board, army: Army) -> (u64, u64) {
     (moves, attack_moves)
 }
 
+/// The two diagonal squares a pawn of `army` on `square` attacks, one rank
+/// ahead along `Army::pawn_direction`.
+pub fn pawn_attacks(army: Army, square: Square) -> u64 {
+    let file = (square % 8) as i8;
+    let rank = (square / 8) as i8;
+    let step = army.pawn_direction();
+    [-1, 1]
+        .iter()
+        .filter_map(|&df| offset_square(file, rank, df, step))
+        .fold(0u64, |acc, dest| acc | (1u64 << dest))
+}
+
 fn offset_square(file: i8, rank: i8, df: i8, dr: i8) -> Option<u8> {
     let nf = file + df;
     let nr = rank + dr;
@@ -0,0 +1,82 @@
+//! Coordinate move notation as typed at every front-end: `--move`,
+//! `--validate`, `--line`, batch files, the REPL and the TUI command line
+//! all read moves through `parse_army_move`, so they accept the same forms
+//! and report the same errors.
+
+use crate::engine::types::{parse_square, Army, PieceKind, Square};
+use std::fmt;
+
+/// Why a typed move could not be read. Legality is checked later, by the
+/// game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    /// Not of the form `army: e2-e4`.
+    BadFormat(String),
+    /// The text before the colon names no army.
+    UnknownArmy(String),
+    /// A square outside a1-h8; holds the reason.
+    BadSquare(String),
+    /// A promotion suffix other than `=Q`, `=R`, `=B` or `=N`.
+    BadPromotion(String),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::BadFormat(reason) => write!(f, "{}", reason),
+            NotationError::UnknownArmy(name) => {
+                write!(f, "Unknown army '{}'. {}", name, Army::suggest_army(name))
+            }
+            NotationError::BadSquare(reason) => write!(f, "{}", reason),
+            NotationError::BadPromotion(piece) => {
+                write!(f, "Invalid promotion piece '{}' (use Q, R, B or N)", piece)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+/// Parses `army: from-to` with an optional promotion: "blue: e2-e4",
+/// "red: d7xd6", "blue: e7-e8=Q". Army names and squares are
+/// case-insensitive and whitespace around each part is ignored.
+pub fn parse_army_move(s: &str) -> Result<(Army, Square, Square, Option<PieceKind>), NotationError> {
+    let (army_name, squares) = s.split_once(':').ok_or_else(|| {
+        NotationError::BadFormat("Move must follow format 'army: e2-e4' (e.g., 'blue: e2-e3')".to_string())
+    })?;
+    let army_name = army_name.trim();
+    let army = Army::from_str(army_name).ok_or_else(|| NotationError::UnknownArmy(army_name.to_string()))?;
+    let (from, to, promotion) = parse_coordinate_move(squares)?;
+    Ok((army, from, to, promotion))
+}
+
+/// The part of a move after the army: "e7-e8", "e7xe8" or "e7e8",
+/// optionally naming a promotion such as "=N".
+pub fn parse_coordinate_move(s: &str) -> Result<(Square, Square, Option<PieceKind>), NotationError> {
+    let (squares, promotion) = match s.trim().split_once('=') {
+        Some((squares, piece)) => (squares, Some(parse_promotion_piece(piece.trim())?)),
+        None => (s.trim(), None),
+    };
+    let squares = squares.trim().to_ascii_lowercase().replace('x', "-");
+    let (from, to) = match squares.split_once('-') {
+        Some(pair) => pair,
+        None if squares.len() == 4 && squares.is_ascii() => squares.split_at(2),
+        None => {
+            return Err(NotationError::BadFormat(
+                "Move must be in format 'e2-e4', 'e2xe4' or 'e2e4'".to_string(),
+            ))
+        }
+    };
+    let square = |name: &str| parse_square(name.trim()).map_err(NotationError::BadSquare);
+    Ok((square(from)?, square(to)?, promotion))
+}
+
+fn parse_promotion_piece(piece: &str) -> Result<PieceKind, NotationError> {
+    match piece.to_uppercase().as_str() {
+        "Q" => Ok(PieceKind::Queen),
+        "R" => Ok(PieceKind::Rook),
+        "B" => Ok(PieceKind::Bishop),
+        "N" => Ok(PieceKind::Knight),
+        _ => Err(NotationError::BadPromotion(piece.to_string())),
+    }
+}
@@ -29,6 +29,8 @@ pub struct ParsedMove {
 /// done on game.rs (this includes promotion logic)
 /// It is only responsible to make sure the string is a correct PGN format
 pub fn parse_move(cmd: &str) -> Result<ParsedMove, ParseError> {
+    // A check or mate marker says nothing about the move itself.
+    let cmd = cmd.trim_end_matches(['+', '#']);
     if cmd.len() <= 1 {
         // invalid
         return Err(ParseError::InvalidLength);
@@ -124,7 +126,6 @@ fn parse_piece(piece: PieceKind, mut chars: Chars) -> Result<ParsedMove, ParseEr
                 file @ 'a'..='h' if piece != PieceKind::King => {
                     source_rank = Some(potential_target_rank);
                     potential_target_file = file;
-                    to = 0; // Replace with a valid bitboard value
                     state = PieceParserState::TargetFileParsed;
                 }
                 _ => {
@@ -144,7 +145,6 @@ fn parse_piece(piece: PieceKind, mut chars: Chars) -> Result<ParsedMove, ParseEr
                     source_file = Some(potential_target_file);
                     source_rank = Some(potential_target_rank);
                     potential_target_file = file;
-                    to = 0; // Replace with a valid bitboard value
                     state = PieceParserState::TargetFileParsed;
                 }
                 _ => {
@@ -164,7 +164,7 @@ fn parse_piece(piece: PieceKind, mut chars: Chars) -> Result<ParsedMove, ParseEr
             PieceParserState::TargetFileParsed => match c {
                 rank @ '0'..='8' => {
                     potential_target_rank = rank.to_digit(10).unwrap() as u64;
-                    to = 0; // Replace with a valid bitboard value
+                    to = target_bit(potential_target_file, potential_target_rank);
                     state = PieceParserState::TargetParsed;
                 }
                 _ => {
@@ -181,7 +181,7 @@ fn parse_piece(piece: PieceKind, mut chars: Chars) -> Result<ParsedMove, ParseEr
 
     // final checks
     if state == PieceParserState::PotentialTargetParsed {
-        to = 0; // Replace with a valid bitboard value
+        to = target_bit(potential_target_file, potential_target_rank);
         state = PieceParserState::TargetParsed;
     }
 
@@ -213,14 +213,13 @@ fn parse_pawn(source: char, mut chars: Chars) -> Result<ParsedMove, ParseError>
     }
 
     let mut state = PawnParserState::Initial;
-    let mut _target_rank: u64 = 0;
 
     while let Some(c) = chars.next() {
         match state {
             PawnParserState::Initial => match c {
                 rank @ '1'..='8' => {
-                    _target_rank = rank.to_digit(10).unwrap() as u64;
-                    to = 0; // Replace with a valid bitboard value
+                    let target_rank = rank.to_digit(10).unwrap() as u64;
+                    to = target_bit(source, target_rank);
                     state = PawnParserState::TargetParsed;
                 }
                 'x' => {
@@ -232,12 +231,12 @@ fn parse_pawn(source: char, mut chars: Chars) -> Result<ParsedMove, ParseError>
                 }
             },
             PawnParserState::Capturing => match c {
-                _file @ 'a'..='h' => {
+                file @ 'a'..='h' => {
                     if let Some(c) = chars.next() {
                         match c {
                             rank @ '1'..='8' => {
-                                _target_rank = rank.to_digit(10).unwrap() as u64;
-                                to = 0; // Replace with a valid bitboard value
+                                let target_rank = rank.to_digit(10).unwrap() as u64;
+                                to = target_bit(file, target_rank);
                                 state = PawnParserState::TargetParsed;
                             }
                             _ => {
@@ -293,6 +292,15 @@ fn parse_pawn(source: char, mut chars: Chars) -> Result<ParsedMove, ParseError>
     })
 }
 
+/// Bitboard of the square at `file` (a-h) and `rank` (1-8). Rank 0 yields an
+/// empty bitboard so the caller reports an invalid target.
+fn target_bit(file: char, rank: u64) -> u64 {
+    if !('a'..='h').contains(&file) || !(1..=8).contains(&rank) {
+        return 0;
+    }
+    1u64 << ((rank - 1) * 8 + (file as u64 - 'a' as u64))
+}
+
 fn parse_source(c: char) -> Result<PieceKind, ParseError> {
     match c {
         'a'..='h' => Ok(PieceKind::Pawn),
@@ -0,0 +1,175 @@
+//! Newline-delimited JSON request/response protocol used by `--serve`, so a
+//! front end in another process can drive one in-memory game.
+
+use crate::engine::game::{Game, MoveOutcome};
+use crate::engine::types::{parse_square, square_name, Army, Move, PieceKind, Square};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+pub enum Request {
+    Move {
+        army: String,
+        from: String,
+        to: String,
+        /// Piece letter to promote to (Q, R, B or N).
+        #[serde(default)]
+        promotion: Option<String>,
+    },
+    /// A move packed with `Move::encode`, as listed by `legal`.
+    Packed {
+        army: String,
+        mv: u16,
+    },
+    Legal {
+        army: String,
+    },
+    State,
+    Undo {
+        #[serde(default = "one")]
+        n: usize,
+    },
+}
+
+fn one() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Response {
+    Moved { message: String, outcome: MoveOutcome },
+    Legal { army: &'static str, moves: Vec<MoveView> },
+    State(StateView),
+    Undone { count: usize },
+    Error { message: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MoveView {
+    pub piece: &'static str,
+    pub from: String,
+    pub to: String,
+    /// The move as `Move::encode` packs it.
+    pub packed: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PieceView {
+    pub square: String,
+    pub army: &'static str,
+    pub piece: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StateView {
+    pub turn: &'static str,
+    pub plies: usize,
+    /// "ongoing", "win" or "draw".
+    pub result: &'static str,
+    pub winner: Option<&'static str>,
+    pub pieces: Vec<PieceView>,
+    /// The board as printed by `--show`, rank 8 first.
+    pub board: Vec<String>,
+}
+
+impl StateView {
+    pub fn of(game: &Game) -> StateView {
+        let winner = game.winning_team();
+        let result = if winner.is_some() {
+            "win"
+        } else if game.draw_condition() {
+            "draw"
+        } else {
+            "ongoing"
+        };
+        StateView {
+            turn: game.current_army().display_name(),
+            plies: game.move_history.len(),
+            result,
+            winner: winner.map(|team| team.name()),
+            pieces: game
+                .board
+                .iter_pieces()
+                .map(|(square, army, kind)| PieceView {
+                    square: square_name(square),
+                    army: army.display_name(),
+                    piece: kind.name(),
+                })
+                .collect(),
+            board: game.board.ascii_rows(),
+        }
+    }
+}
+
+fn parse_army(name: &str) -> Result<Army, String> {
+    Army::from_str(name).ok_or_else(|| format!("Unknown army '{}'", name))
+}
+
+/// Parses one request line and applies it to `game`. Malformed input is
+/// answered with `Response::Error` rather than failing.
+pub fn handle_line(game: &mut Game, line: &str) -> Response {
+    match serde_json::from_str::<Request>(line) {
+        Ok(request) => handle(game, request),
+        Err(e) => Response::Error {
+            message: format!("Invalid request: {}", e),
+        },
+    }
+}
+
+pub fn handle(game: &mut Game, request: Request) -> Response {
+    let result = match request {
+        Request::Move {
+            army,
+            from,
+            to,
+            promotion,
+        } => apply_move(game, &army, &from, &to, promotion.as_deref()),
+        Request::Packed { army, mv } => parse_army(&army).and_then(|army| {
+            let mv = Move::decode(mv)?;
+            let outcome = game.apply_move(army, mv.from, mv.to, mv.promotion)?;
+            Ok(Response::Moved { message: outcome.to_string(), outcome })
+        }),
+        Request::Legal { army } => parse_army(&army).map(|army| Response::Legal {
+            army: army.display_name(),
+            moves: game
+                .legal_moves(army)
+                .iter()
+                .map(|mv| MoveView {
+                    piece: mv.kind.name(),
+                    from: square_name(mv.from),
+                    to: square_name(mv.to),
+                    packed: mv.encode(),
+                })
+                .collect(),
+        }),
+        Request::State => Ok(Response::State(StateView::of(game))),
+        Request::Undo { n } => game.undo(n).map(|count| Response::Undone { count }),
+    };
+    result.unwrap_or_else(|message| Response::Error { message })
+}
+
+fn apply_move(
+    game: &mut Game,
+    army: &str,
+    from: &str,
+    to: &str,
+    promotion: Option<&str>,
+) -> Result<Response, String> {
+    let army = parse_army(army)?;
+    let from = parse_square(from)?;
+    let to = parse_square(to)?;
+    let promotion = match promotion {
+        Some(letter) => Some(
+            letter
+                .chars()
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .and_then(PieceKind::from_letter)
+                .ok_or_else(|| format!("Unknown promotion piece '{}'", letter))?,
+        ),
+        None => None,
+    };
+    let outcome = game.apply_move(army, from, to, promotion)?;
+    Ok(Response::Moved { message: outcome.to_string(), outcome })
+}
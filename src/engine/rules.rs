@@ -0,0 +1,294 @@
+//! Rules reference used by `--query` and anything else that needs to explain
+//! a rule in prose. Queries are classified into a `Topic` by keyword matching
+//! so the lookup can be tested without touching stdout.
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Topic {
+    QueenCaptureQueen,
+    BishopCaptureBishop,
+    QueenBishopCapture,
+    Check,
+    Promotion,
+    Frozen,
+    Throne,
+    Victory,
+    QueenMovement,
+    PawnMovement,
+    Stalemate,
+}
+
+/// Canonical keywords a query is reduced to before matching.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Keyword {
+    Queen,
+    Bishop,
+    Pawn,
+    Capture,
+    Move,
+    Check,
+    Promotion,
+    Frozen,
+    Throne,
+    Victory,
+    Stalemate,
+}
+
+/// Word stems recognised for each keyword. A query word matches when it
+/// starts with one of the stems, or is within one edit of it.
+const KEYWORD_STEMS: &[(Keyword, &[&str])] = &[
+    (Keyword::Queen, &["queen"]),
+    (Keyword::Bishop, &["bishop"]),
+    (Keyword::Pawn, &["pawn"]),
+    (
+        Keyword::Capture,
+        &["captur", "take", "taking", "took", "eat", "kill", "attack"],
+    ),
+    (Keyword::Move, &["move", "moving", "movement", "leap", "jump", "go"]),
+    (Keyword::Check, &["check", "mate"]),
+    (Keyword::Promotion, &["promot", "queening"]),
+    (Keyword::Frozen, &["frozen", "freez", "froze", "revive", "reviv"]),
+    (Keyword::Throne, &["throne"]),
+    (Keyword::Victory, &["win", "won", "victor", "team", "goal"]),
+    (Keyword::Stalemate, &["stalemat", "stuck", "skip"]),
+];
+
+impl Topic {
+    pub const ALL: [Topic; 11] = [
+        Topic::QueenCaptureQueen,
+        Topic::BishopCaptureBishop,
+        Topic::QueenBishopCapture,
+        Topic::Check,
+        Topic::Promotion,
+        Topic::Frozen,
+        Topic::Throne,
+        Topic::Victory,
+        Topic::QueenMovement,
+        Topic::PawnMovement,
+        Topic::Stalemate,
+    ];
+
+    /// Classifies a free-form question such as "can a queen take a queen?".
+    /// Returns `None` when no topic matches.
+    pub fn from_query(query: &str) -> Option<Topic> {
+        let words: Vec<String> = query
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_ascii_lowercase())
+            .collect();
+
+        let mut queens = 0;
+        let mut bishops = 0;
+        let mut found = Vec::new();
+        for word in &words {
+            if let Some(keyword) = classify_word(word) {
+                match keyword {
+                    Keyword::Queen => queens += 1,
+                    Keyword::Bishop => bishops += 1,
+                    _ => {}
+                }
+                found.push(keyword);
+            }
+        }
+        let has = |keyword: Keyword| found.contains(&keyword);
+
+        // Most specific topics first: piece interactions before general rules.
+        if has(Keyword::Queen) && has(Keyword::Bishop) {
+            return Some(Topic::QueenBishopCapture);
+        }
+        if queens >= 2 || (has(Keyword::Queen) && has(Keyword::Capture)) {
+            return Some(Topic::QueenCaptureQueen);
+        }
+        if bishops >= 2 || (has(Keyword::Bishop) && has(Keyword::Capture)) {
+            return Some(Topic::BishopCaptureBishop);
+        }
+        if has(Keyword::Promotion) {
+            return Some(Topic::Promotion);
+        }
+        if has(Keyword::Stalemate) {
+            return Some(Topic::Stalemate);
+        }
+        if has(Keyword::Check) {
+            return Some(Topic::Check);
+        }
+        if has(Keyword::Frozen) {
+            return Some(Topic::Frozen);
+        }
+        if has(Keyword::Throne) {
+            return Some(Topic::Throne);
+        }
+        if has(Keyword::Victory) {
+            return Some(Topic::Victory);
+        }
+        if has(Keyword::Queen) {
+            return Some(Topic::QueenMovement);
+        }
+        if has(Keyword::Pawn) {
+            return Some(Topic::PawnMovement);
+        }
+        None
+    }
+
+    /// A short example query that maps to this topic.
+    pub const fn example_query(self) -> &'static str {
+        match self {
+            Topic::QueenCaptureQueen => "queen capture queen",
+            Topic::BishopCaptureBishop => "bishop capture bishop",
+            Topic::QueenBishopCapture => "queen bishop",
+            Topic::Check => "check",
+            Topic::Promotion => "promotion",
+            Topic::Frozen => "frozen",
+            Topic::Throne => "throne",
+            Topic::Victory => "victory",
+            Topic::QueenMovement => "queen move",
+            Topic::PawnMovement => "pawn move",
+            Topic::Stalemate => "stalemate",
+        }
+    }
+
+    pub const fn explanation(self) -> &'static str {
+        match self {
+            Topic::QueenCaptureQueen => {
+                "Can queens capture queens?\n\
+                 ❌ No - Queens cannot capture other queens"
+            }
+            Topic::BishopCaptureBishop => {
+                "Can bishops capture bishops?\n\
+                 ❌ No - Bishops cannot capture other bishops"
+            }
+            Topic::QueenBishopCapture => {
+                "Can queens and bishops capture each other?\n\
+                 ✓ Yes - Queens can capture bishops, and bishops can capture queens"
+            }
+            Topic::Check => {
+                "Check rules:\n\
+                 • No checkmate - kings are captured like other pieces\n\
+                 • If in check with legal king moves, you MUST move the king\n\
+                 • If in check with no legal king moves, you may move any piece\n\
+                 • Capturing an enemy king is always allowed, even in check"
+            }
+            Topic::Promotion => {
+                "Promotion rules:\n\
+                 • Blue pawns promote on rank 8 (north edge)\n\
+                 • Red pawns promote on rank 1 (south edge)\n\
+                 • Black pawns promote on rank 1 (south edge, as Red's do)\n\
+                 • Yellow pawns promote on rank 8 (north edge, as Blue's do)\n\
+                 • Privileged pawn: With only K+Q+P, K+B+P, or K+P remaining,\n  \
+                 the pawn can promote to any piece type"
+            }
+            Topic::Frozen => {
+                "Frozen army rules:\n\
+                 • When a king is captured, that army becomes frozen\n\
+                 • Frozen pieces cannot move or attack\n\
+                 • Frozen pieces act as blocking terrain\n\
+                 • An army can be revived by controlling its throne square"
+            }
+            Topic::Throne => {
+                "Throne square rules:\n\
+                 • Each army has a throne (king's starting position)\n\
+                 • Moving your king onto an ally's throne = gain control\n\
+                 • Controlling a throne revives that frozen army\n\
+                 • Your own throne, or an enemy's, gives no control\n\
+                 • An enemy king on a throne blocks that army's prisoner exchange"
+            }
+            Topic::Victory => {
+                "Victory conditions:\n\
+                 • Teams: Air (Blue + Black) vs Earth (Red + Yellow)\n\
+                 • Win by capturing both enemy kings\n\
+                 • Frozen armies can be revived via throne control"
+            }
+            Topic::QueenMovement => {
+                "Queen movement:\n\
+                 • Leaps exactly 2 squares (orthogonal or diagonal)\n\
+                 • Ignores intervening pieces (like a knight)\n\
+                 • Cannot move 1 square or 3+ squares"
+            }
+            Topic::PawnMovement => {
+                "Pawn movement:\n\
+                 • Moves 1 square forward\n\
+                 • Captures 1 square diagonally\n\
+                 • No double-step initial move\n\
+                 • No en passant (both allowed by the optional double-step variant)"
+            }
+            Topic::Stalemate => {
+                "Stalemate rules:\n\
+                 • If an army has no legal moves, that turn is skipped\n\
+                 • Play continues with the next army\n\
+                 • Under the optional draw variant, the stalemate draws the game instead"
+            }
+        }
+    }
+}
+
+fn classify_word(word: &str) -> Option<Keyword> {
+    // Exact stem matches win over typo-tolerant ones so that e.g. "stalemate"
+    // is never read as a misspelt "mate".
+    for &(keyword, stems) in KEYWORD_STEMS {
+        if stems.iter().any(|stem| stem_matches(word, stem)) {
+            return Some(keyword);
+        }
+    }
+    if word.len() < 5 {
+        return None;
+    }
+    for &(keyword, stems) in KEYWORD_STEMS {
+        if stems
+            .iter()
+            .any(|stem| stem.len() >= 5 && within_one_edit(word, stem))
+        {
+            return Some(keyword);
+        }
+    }
+    None
+}
+
+fn stem_matches(word: &str, stem: &str) -> bool {
+    // Short stems ("go", "eat", "win") must match whole words (plus a plural
+    // or verb suffix) to avoid hits like "good" or "window".
+    if stem.len() <= 3 {
+        return word == stem || word.strip_prefix(stem).is_some_and(|rest| rest == "s");
+    }
+    word.starts_with(stem)
+}
+
+/// True when `word` equals `target` after at most one insertion, deletion or
+/// substitution, comparing only as many characters as `target` has (so
+/// "promtion" still matches the stem "promot").
+fn within_one_edit(word: &str, target: &str) -> bool {
+    let word = word.as_bytes();
+    let target = target.as_bytes();
+    let candidates = [
+        &word[..word.len().min(target.len())],
+        &word[..word.len().min(target.len() + 1)],
+        &word[..word.len().min(target.len().saturating_sub(1))],
+    ];
+    candidates
+        .iter()
+        .any(|candidate| edit_distance_at_most_one(candidate, target))
+}
+
+fn edit_distance_at_most_one(a: &[u8], b: &[u8]) -> bool {
+    if a.len().abs_diff(b.len()) > 1 {
+        return false;
+    }
+    let (mut i, mut j, mut edits) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        edits += 1;
+        if edits > 1 {
+            return false;
+        }
+        match a.len().cmp(&b.len()) {
+            std::cmp::Ordering::Greater => i += 1,
+            std::cmp::Ordering::Less => j += 1,
+            std::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    edits + (a.len() - i) + (b.len() - j) <= 1
+}
@@ -0,0 +1,90 @@
+//! Per-army activity statistics derived from a game's move history, shared by
+//! the text and JSON forms of `--stats`.
+
+use crate::engine::game::{is_pass, Game};
+use crate::engine::types::{Army, PieceKind, Team};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PieceStats {
+    pub kind: PieceKind,
+    pub moves: usize,
+    pub captures: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArmyStats {
+    pub army: Army,
+    pub frozen: bool,
+    pub moves: usize,
+    pub captures: usize,
+    pub pieces_lost: usize,
+    /// One entry per piece kind, in `PieceKind::ALL` order.
+    pub by_kind: Vec<PieceStats>,
+}
+
+impl ArmyStats {
+    pub fn piece(&self, kind: PieceKind) -> &PieceStats {
+        &self.by_kind[kind.index()]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GameStats {
+    pub moves_played: usize,
+    /// One entry per army, in `Army::ALL` order.
+    pub armies: Vec<ArmyStats>,
+    pub winner: Option<Team>,
+}
+
+impl GameStats {
+    pub fn from_game(game: &Game) -> GameStats {
+        let mut armies: Vec<ArmyStats> = Army::ALL
+            .iter()
+            .map(|&army| ArmyStats {
+                army,
+                frozen: game.army_is_frozen(army),
+                moves: 0,
+                captures: 0,
+                pieces_lost: 0,
+                by_kind: PieceKind::ALL
+                    .iter()
+                    .map(|&kind| PieceStats {
+                        kind,
+                        moves: 0,
+                        captures: 0,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        for (entry, detail) in game.move_history.iter().zip(game.aligned_move_details().iter()) {
+            if is_pass(entry) {
+                continue;
+            }
+            let army = entry.0;
+            let stats = &mut armies[army.index()];
+            stats.moves += 1;
+            stats.by_kind[detail.kind.index()].moves += 1;
+            if let Some((victim, _)) = detail.captured {
+                stats.captures += 1;
+                stats.by_kind[detail.kind.index()].captures += 1;
+                armies[victim.index()].pieces_lost += 1;
+            }
+        }
+
+        GameStats {
+            moves_played: game.move_history.iter().filter(|entry| !is_pass(entry)).count(),
+            armies,
+            winner: game.winning_team(),
+        }
+    }
+
+    pub fn army(&self, army: Army) -> &ArmyStats {
+        &self.armies[army.index()]
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
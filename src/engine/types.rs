@@ -52,6 +52,29 @@ impl Army {
             "Valid armies: blue, red, black, yellow".to_string()
         }
     }
+
+    /// The army's tag in coordinate PGN movetext (`Bk:a8-b8`). Black is
+    /// `Bk` rather than the older `K`, which read as a king.
+    pub fn pgn_tag(self) -> &'static str {
+        match self {
+            Army::Blue => "B",
+            Army::Black => "Bk",
+            Army::Red => "R",
+            Army::Yellow => "Y",
+        }
+    }
+
+    /// Reads a PGN army tag: `B`, `Bk`, `R` or `Y`, the legacy `K` for
+    /// Black, or a full army name, all case-insensitive.
+    pub fn from_pgn_tag(tag: &str) -> Option<Army> {
+        match tag.to_lowercase().as_str() {
+            "b" => Some(Army::Blue),
+            "bk" | "k" => Some(Army::Black),
+            "r" => Some(Army::Red),
+            "y" => Some(Army::Yellow),
+            _ => Army::from_str(tag),
+        }
+    }
 }
 
 impl Army {
@@ -77,6 +100,20 @@ impl Army {
             Army::Yellow => 1, // moves right (but in rank terms)
         }
     }
+
+    /// The board square `relative` names when read from this army's side:
+    /// its rank 1 is the back rank its pawns advance away from along
+    /// `pawn_direction`, as in the move generator, and its a-file is on the
+    /// player's left. Blue and Yellow advance up the board, so for them
+    /// this is `relative` itself; Red and Black see the board turned half
+    /// round.
+    pub fn relative_to_absolute(self, relative: Square) -> Square {
+        if self.pawn_direction() > 0 {
+            relative
+        } else {
+            63 - relative
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Serialize, Deserialize)]
@@ -88,6 +125,14 @@ pub enum Team {
 impl Team {
     pub const ALL: [Team; TEAM_COUNT] = [Team::Air, Team::Earth];
 
+    pub fn from_name(name: &str) -> Option<Team> {
+        match name.to_lowercase().as_str() {
+            "air" => Some(Team::Air),
+            "earth" => Some(Team::Earth),
+            _ => None,
+        }
+    }
+
     #[inline]
     pub const fn index(self) -> usize {
         self as usize
@@ -150,6 +195,43 @@ impl PieceKind {
             PieceKind::Pawn => "Pawn",
         }
     }
+
+    /// Upper-case letter used in compact and generated positions.
+    pub const fn letter(self) -> char {
+        match self {
+            PieceKind::King => 'K',
+            PieceKind::Queen => 'Q',
+            PieceKind::Bishop => 'B',
+            PieceKind::Knight => 'N',
+            PieceKind::Rook => 'R',
+            PieceKind::Pawn => 'P',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<PieceKind> {
+        PieceKind::ALL.into_iter().find(|kind| kind.letter() == letter)
+    }
+
+    /// One line on how the piece moves and what it may not capture, for
+    /// showing beside a selected piece. `--query` has the longer versions.
+    pub const fn rules_summary(self) -> &'static str {
+        match self {
+            PieceKind::King => "steps 1 square any way; captured, not mated, freezing its army",
+            PieceKind::Queen => {
+                "leaps exactly 2 squares, straight or diagonal; cannot capture queens, \
+                 nor bishops on the other diagonal system"
+            }
+            PieceKind::Bishop => {
+                "slides diagonally; cannot capture bishops, nor queens on the other \
+                 diagonal system"
+            }
+            PieceKind::Knight => "leaps in an L, over any piece",
+            PieceKind::Rook => "slides along ranks and files",
+            PieceKind::Pawn => {
+                "steps 1 square toward its promotion edge, captures 1 diagonally forward"
+            }
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Serialize, Deserialize)]
@@ -158,10 +240,26 @@ pub struct PlayerId(pub u8);
 impl PlayerId {
     pub const PLAYER_ONE: PlayerId = PlayerId(0);
     pub const PLAYER_TWO: PlayerId = PlayerId(1);
+    pub const PLAYER_THREE: PlayerId = PlayerId(2);
+    pub const PLAYER_FOUR: PlayerId = PlayerId(3);
+
+    /// One controller per army is the most a game can have.
+    pub const MAX_PLAYERS: u8 = ARMY_COUNT as u8;
 
     pub const fn new(id: u8) -> PlayerId {
         PlayerId(id)
     }
+
+    /// Short display label, "P1" through "P4".
+    pub const fn label(self) -> &'static str {
+        match self.0 {
+            0 => "P1",
+            1 => "P2",
+            2 => "P3",
+            3 => "P4",
+            _ => "P?",
+        }
+    }
 }
 
 impl Default for PlayerId {
@@ -174,7 +272,7 @@ impl Default for PlayerId {
 pub struct Piece {
     pub army: Army,
     pub kind: PieceKind,
-    pub pawn_type: Option<PieceKind>, // for “pawn of X” if you want to distinguish
+    pub pawn_type: Option<PieceKind>, // officer a pawn belongs to (“pawn of X”), decides its promotion
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Serialize, Deserialize)]
@@ -183,6 +281,44 @@ pub struct Move {
     pub to: Square,
     pub kind: PieceKind,
     pub promotion: Option<PieceKind>,
+    /// The destination holds an enemy king. Capturing it freezes that army,
+    /// so the AI treats these moves as decisive.
+    #[serde(default)]
+    pub captures_king: bool,
+}
+
+const PROMOTION_FLAG: u16 = 1 << 15;
+
+impl Move {
+    /// Packs the move into 16 bits: `from` in bits 0-5, `to` in bits 6-11
+    /// and a piece index in bits 12-14. Bit 15 marks a promotion, in which
+    /// case the piece is the promotion target and the mover a pawn.
+    /// `captures_king` is not kept.
+    pub fn encode(&self) -> u16 {
+        let (flag, kind) = match self.promotion {
+            Some(target) => (PROMOTION_FLAG, target),
+            None => (0, self.kind),
+        };
+        flag | (kind.index() as u16) << 12 | (self.to as u16 & 0x3F) << 6 | (self.from as u16 & 0x3F)
+    }
+
+    pub fn decode(packed: u16) -> Result<Move, String> {
+        let piece = *PieceKind::ALL
+            .get(((packed >> 12) & 0x7) as usize)
+            .ok_or_else(|| format!("Invalid packed move {:#06x}", packed))?;
+        let (kind, promotion) = if packed & PROMOTION_FLAG != 0 {
+            (PieceKind::Pawn, Some(piece))
+        } else {
+            (piece, None)
+        };
+        Ok(Move {
+            from: (packed & 0x3F) as Square,
+            to: ((packed >> 6) & 0x3F) as Square,
+            kind,
+            promotion,
+            captures_king: false,
+        })
+    }
 }
 
 impl std::fmt::Display for Move {
@@ -205,3 +341,27 @@ pub fn file_char(square: Square) -> char {
 pub fn rank_char(square: Square) -> char {
     ((square / 8) + b'1') as char
 }
+
+/// The name of `square`, such as "e2"; the reverse of `parse_square`.
+pub fn square_name(square: Square) -> String {
+    format!("{}{}", file_char(square), rank_char(square))
+}
+
+/// Parses a square name such as "e2".
+pub fn parse_square(s: &str) -> Result<Square, String> {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() != 2 {
+        return Err(format!("Invalid square '{}'. Expected format: a1-h8 (e.g., e2, d4)", s));
+    }
+    let file = chars[0].to_ascii_lowercase() as u8;
+    let rank = chars[1] as u8;
+
+    if !(b'a'..=b'h').contains(&file) {
+        return Err(format!("Invalid file '{}'. Must be a-h", chars[0]));
+    }
+    if !(b'1'..=b'8').contains(&rank) {
+        return Err(format!("Invalid rank '{}'. Must be 1-8", chars[1]));
+    }
+
+    Ok((rank - b'1') * 8 + (file - b'a'))
+}
@@ -0,0 +1,66 @@
+//! Self-contained HTML viewer for `--export-html`: the serialized game is
+//! embedded next to a small script that steps through its plies in the
+//! browser, so a finished game can be shared as a single file.
+
+use crate::engine::game::Game;
+
+const TEMPLATE: &str = include_str!("viewer.html");
+
+/// An HTML page replaying `game`. The embedded JSON carries the game as
+/// `Game::to_json` saves it, packed move history included, plus the
+/// position before the first ply and after each one in `to_compact`
+/// notation, worked out by replaying the history through the engine so
+/// the script only has to draw them. Fails with the first bad ply when the
+/// history doesn't replay.
+pub fn to_html(game: &Game) -> Result<String, String> {
+    let positions: Vec<String> = game
+        .replay_boards()
+        .map_err(|(ply, reason)| format!("ply {} does not replay: {}", ply + 1, reason))?
+        .into_iter()
+        .map(|board| Game::new(board).to_compact())
+        .collect();
+    let data = serde_json::json!({
+        "game": game,
+        "positions": positions,
+    });
+    // `</` can't appear inside a script element; JSON reads `<\/` the same.
+    let data = serde_json::to_string(&data)
+        .map_err(|e| e.to_string())?
+        .replace("</", "<\\/");
+    let title = escape(game.metadata.event.as_deref().unwrap_or("Enochian Chess Game"));
+    let plies = game.move_history.len().to_string();
+    Ok(fill(TEMPLATE, &[("TITLE", &title), ("PLIES", &plies), ("DATA", &data)]))
+}
+
+/// Replaces each `{{KEY}}` in `template` in a single pass, so text put in
+/// for one placeholder is never searched for another.
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        let value = after.find("}}").and_then(|close| {
+            values
+                .iter()
+                .find(|(key, _)| *key == &after[..close])
+                .map(|(_, value)| (close, value))
+        });
+        match value {
+            Some((close, value)) => {
+                out.push_str(value);
+                rest = &after[close + 2..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
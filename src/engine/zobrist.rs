@@ -0,0 +1,63 @@
+//! Zobrist keys for identifying positions. The keys are fixed at compile time
+//! so a hash printed today still names the same position tomorrow.
+
+use crate::engine::types::{ARMY_COUNT, PIECE_KIND_COUNT};
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+pub struct ZobristKeys {
+    /// Indexed by `[army][kind][square]`.
+    pub pieces: [[[u64; 64]; PIECE_KIND_COUNT]; ARMY_COUNT],
+    /// The army to move.
+    pub turn: [u64; ARMY_COUNT],
+    pub frozen: [u64; ARMY_COUNT],
+    /// Indexed by `[army][controller]`.
+    pub controllers: [[u64; ARMY_COUNT]; ARMY_COUNT],
+}
+
+const fn generate() -> ZobristKeys {
+    let mut keys = ZobristKeys {
+        pieces: [[[0; 64]; PIECE_KIND_COUNT]; ARMY_COUNT],
+        turn: [0; ARMY_COUNT],
+        frozen: [0; ARMY_COUNT],
+        controllers: [[0; ARMY_COUNT]; ARMY_COUNT],
+    };
+    let mut state = 0x454E_4F43_4821_u64;
+    let mut army = 0;
+    while army < ARMY_COUNT {
+        let mut kind = 0;
+        while kind < PIECE_KIND_COUNT {
+            let mut square = 0;
+            while square < 64 {
+                let (next, key) = splitmix64(state);
+                state = next;
+                keys.pieces[army][kind][square] = key;
+                square += 1;
+            }
+            kind += 1;
+        }
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys.turn[army] = key;
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys.frozen[army] = key;
+        let mut controller = 0;
+        while controller < ARMY_COUNT {
+            let (next, key) = splitmix64(state);
+            state = next;
+            keys.controllers[army][controller] = key;
+            controller += 1;
+        }
+        army += 1;
+    }
+    keys
+}
+
+pub static KEYS: ZobristKeys = generate();
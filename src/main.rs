@@ -3,12 +3,15 @@
 mod engine;
 mod ui;
 
-use crate::engine::game::Game;
+use crate::engine::game::{is_pass, split_annotation, Game, Status};
 use crate::engine::arrays::{default_array, find_array_by_name};
 use crate::engine::ai;
-use crate::engine::types::Army;
+use crate::engine::load::{self, LoadError};
+use crate::engine::notation::parse_army_move;
+use crate::engine::types::{parse_square, square_name, Army, Team};
 use crate::ui::app::{App, CurrentScreen};
-use crate::ui::ui::{render, render_size_error};
+use crate::ui::theme::Theme;
+use crate::ui::ui::{render, render_size_error, MIN_HEIGHT, MIN_WIDTH};
 use clap::Parser;
 use crossterm::event::{self, DisableMouseCapture, Event, KeyCode, KeyEventKind};
 use crossterm::terminal::{
@@ -59,12 +62,29 @@ struct Args {
     #[arg(long, value_name = "FILE")]
     state: Option<String>,
     
+    /// Start the game over from the position it began at, keeping its rule
+    /// settings (saved back to --state when given)
+    #[arg(long)]
+    new: bool,
+    
+    /// Replay the loaded game's move history and refuse it if any ply was
+    /// illegal or the saved position doesn't match
+    #[arg(long)]
+    verify: bool,
+    
     // === Move Operations ===
     
-    /// Make a move (format: "army: from-to")
-    #[arg(long, value_name = "MOVE")]
+    /// Make a move (format: "army: from-to"). Exits 2 if it is another
+    /// army's turn, 3 if the move is illegal, 4 if the army is frozen
+    #[arg(long, visible_alias = "move", value_name = "MOVE")]
     move_cmd: Option<String>,
     
+    /// Play a comma-separated line of moves ("blue: e2-e3, red: e7-e6")
+    /// and report the resulting position. Stops at the first illegal move,
+    /// exiting as --move would
+    #[arg(long, value_name = "MOVES")]
+    line: Option<String>,
+    
     /// Validate a move without applying it
     #[arg(long, value_name = "MOVE")]
     validate: Option<String>,
@@ -73,12 +93,34 @@ struct Args {
     #[arg(long, value_name = "N")]
     undo: Option<usize>,
     
+    /// Ask to take back ARMY's last move; applied once an opponent grants it
+    #[arg(long, value_name = "ARMY")]
+    request_takeback: Option<String>,
+    
+    /// Grant the pending takeback request as ARMY, which must be an
+    /// opponent of the army that asked
+    #[arg(long, value_name = "ARMY")]
+    grant_takeback: Option<String>,
+    
     // === Analysis Tools ===
     
     /// Analyze a square (show piece info and legal moves)
     #[arg(long, value_name = "SQUARE")]
     analyze: Option<String>,
     
+    /// Show where the piece on a square can move, before and after king
+    /// safety filtering
+    #[arg(long, value_name = "SQUARE")]
+    reach: Option<String>,
+    
+    /// Show how many of a team's pieces cover each square (air or earth)
+    #[arg(long, value_name = "TEAM")]
+    heatmap: Option<String>,
+    
+    /// Show every square an army can move to or attacks
+    #[arg(long, value_name = "ARMY")]
+    coverage: Option<String>,
+    
     /// Query rules (e.g., "queen capture queen", "promotion")
     #[arg(long, value_name = "QUERY")]
     query: Option<String>,
@@ -91,6 +133,16 @@ struct Args {
     #[arg(long)]
     stats: bool,
     
+    /// Print each army's legal move count on one line
+    #[arg(long)]
+    mobility: bool,
+    
+    /// Output format for --stats, --mobility, --tree and --list-arrays
+    /// (text, json) and --auto-play (text, ndjson); checked against every
+    /// one requested before any runs
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    format: String,
+    
     /// Show legal moves for army
     #[arg(long, value_name = "ARMY")]
     legal_moves: Option<String>,
@@ -101,6 +153,22 @@ struct Args {
     #[arg(long, value_name = "POSITION")]
     generate: Option<String>,
     
+    /// Generate a random position (see --pieces and --seed)
+    #[arg(long)]
+    generate_random: bool,
+    
+    /// With --generate-random: pieces per army, king included (1-9)
+    #[arg(long, value_name = "N", default_value_t = 4)]
+    pieces: usize,
+    
+    /// With --generate-random: RNG seed, so a position can be generated again
+    #[arg(long, value_name = "SEED")]
+    seed: Option<u64>,
+    
+    /// Army to move first in the loaded or generated position
+    #[arg(long, value_name = "ARMY")]
+    to_move: Option<String>,
+    
     /// List all available starting arrays
     #[arg(long)]
     list_arrays: bool,
@@ -109,26 +177,66 @@ struct Args {
     #[arg(long, value_name = "NAME")]
     array: Option<String>,
     
+    /// Start from a custom array described in a JSON file (name, position,
+    /// and optionally turn_order and promotion_zones)
+    #[arg(long, value_name = "FILE", conflicts_with = "array")]
+    array_file: Option<String>,
+    
+    /// Number of human players (1, 2 or 4); 4 gives each army its own player
+    #[arg(long, value_name = "N")]
+    players: Option<u8>,
+    
+    /// Let an army in check answer with any legal move instead of being
+    /// made to move its king
+    #[arg(long)]
+    no_forced_king: bool,
+    
     // === Game I/O ===
     
-    /// Export game in PGN-like format
+    /// Export game in PGN-like format (- for stdout)
     #[arg(long, value_name = "FILE")]
     export_pgn: Option<String>,
     
-    /// Import game from PGN format
+    /// With --export-pgn: "coordinate" (B:e2-e3) or "san" (standard SAN)
+    #[arg(long, value_name = "DIALECT", default_value = "coordinate")]
+    pgn_dialect: String,
+    
+    /// Export the game as a single-file HTML page that steps through its
+    /// plies in the browser
+    #[arg(long, value_name = "FILE")]
+    export_html: Option<String>,
+    
+    /// Import game from PGN format (- for stdin)
     #[arg(long, value_name = "FILE")]
     import_pgn: Option<String>,
     
+    /// Import a position in compact notation (see --convert compact)
+    #[arg(long, value_name = "FILE")]
+    import_compact: Option<String>,
+    
     /// Convert format (json, ascii, compact)
     #[arg(long, value_name = "FORMAT")]
     convert: Option<String>,
     
+    /// Poll a saved game and print NDJSON analysis each time it changes
+    #[arg(long, value_name = "FILE")]
+    watch: Option<String>,
+    
+    /// Compare two saved games and report where they diverge
+    #[arg(long, num_args = 2, value_names = ["A", "B"])]
+    compare: Option<Vec<String>>,
+    
     // === Modes ===
     
     /// Interactive REPL mode
     #[arg(long)]
     interactive: bool,
     
+    /// Serve newline-delimited JSON requests on stdin, one response per line
+    /// on stdout (see engine::protocol)
+    #[arg(long)]
+    serve: bool,
+    
     /// Execute commands from file
     #[arg(long, value_name = "FILE")]
     batch: Option<String>,
@@ -139,6 +247,20 @@ struct Args {
     #[arg(long, value_name = "ARMIES")]
     ai: Option<String>,
     
+    /// Search depth for AI moves (alpha-beta)
+    #[arg(long, value_name = "DEPTH")]
+    ai_depth: Option<u8>,
+
+    /// Make depth-searching AIs play for their team: king safety counts and
+    /// captures that expose the allied king are avoided. Needs --ai-depth;
+    /// the timed search has no team-aware mode
+    #[arg(long, requires = "ai_depth", conflicts_with = "ai_time")]
+    ai_team: bool,
+    
+    /// Time budget per AI move in milliseconds (iterative deepening)
+    #[arg(long, value_name = "MS", conflicts_with = "ai_depth")]
+    ai_time: Option<u64>,
+    
     /// Auto-play until game ends
     #[arg(long)]
     auto_play: bool,
@@ -147,12 +269,25 @@ struct Args {
     #[arg(long, value_name = "DEPTH")]
     perft: Option<u8>,
     
+    /// Print every legal line DEPTH plies deep (at most 3), as an indented
+    /// list or with --format json as nested {move, children} nodes
+    #[arg(long, value_name = "DEPTH")]
+    tree: Option<u8>,
+    
+    /// Quick speed check: perft(4) from the starting position, with NPS
+    #[arg(long)]
+    bench: bool,
+    
     // === Display ===
     
     /// Show board
     #[arg(long)]
     show: bool,
     
+    /// With --show, draw the board from this army's side
+    #[arg(long, value_name = "ARMY")]
+    orient: Option<String>,
+    
     /// Show move history
     #[arg(long)]
     history: bool,
@@ -161,14 +296,36 @@ struct Args {
     #[arg(long)]
     status: bool,
     
+    /// Show the next armies to move, with frozen and stalemated armies
+    /// shown where they are skipped
+    #[arg(long)]
+    timeline: bool,
+    
+    /// Print the position hash, a short id for the current position
+    #[arg(long)]
+    hash: bool,
+    
+    /// Print every army/piece bitboard of the loaded position
+    #[arg(long, hide = true)]
+    debug_bitboards: bool,
+    
+    /// Time move generation per piece kind over a perft walk to DEPTH
+    #[arg(long, value_name = "DEPTH", hide = true)]
+    profile_movegen: Option<u8>,
+    
     /// Suppress non-essential output
     #[arg(long, short)]
     quiet: bool,
+    
+    /// Board color theme for the TUI (classic, high-contrast, monochrome)
+    #[arg(long, value_name = "NAME", default_value = "classic")]
+    theme: String,
+    
+    /// Keep a text capture of every TUI frame, for debugging the renderer
+    #[arg(long, hide = true)]
+    capture: bool,
 }
 
-pub const MIN_WIDTH: u16 = 80;
-pub const MIN_HEIGHT: u16 = 24;
-
 fn check_size(terminal: &mut DefaultTerminal) -> Result<(), io::Error> {
     let size = terminal.size()?;
     if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
@@ -203,30 +360,71 @@ fn check_size(terminal: &mut DefaultTerminal) -> Result<(), io::Error> {
 fn main() -> Result<(), io::Error> {
     let args = Args::parse();
     
-    if args.headless {
+    if args.headless || args.serve {
         run_headless(args);
         Ok(())
     } else {
         let use_halfblocks = env::args().any(|arg| arg == "--halfblocks");
-        run_tui(use_halfblocks)
+        let theme = Theme::from_name(&args.theme).unwrap_or_else(|| {
+            eprintln!("❌ Unknown theme: {} (use {})", args.theme, Theme::names().join(", "));
+            process::exit(1);
+        });
+        // Load before taking over the terminal, so errors print normally
+        let start = match &args.array_file {
+            Some(path) => Some(or_exit(load::from_array_file(path))),
+            None => args.array.as_deref().map(|name| or_exit(load::from_array(Some(name)))),
+        };
+        run_tui(use_halfblocks, theme, !args.no_forced_king, args.capture, start)
     }
 }
 
-fn run_tui(use_halfblocks: bool) -> Result<(), io::Error> {
+fn run_tui(
+    use_halfblocks: bool,
+    theme: Theme,
+    forced_king: bool,
+    capture: bool,
+    start: Option<Game>,
+) -> Result<(), io::Error> {
     let mut terminal = ratatui::init();
     let mut app = App::new(use_halfblocks);
+    if let Some(game) = start {
+        app.start_game(game);
+    }
+    app.theme = theme;
+    app.capture_frames = capture;
+    app.game.set_force_king_move_in_check(forced_king);
     run(&mut terminal, &mut app)?;
     ratatui::restore();
     Ok(())
 }
 
+/// How often the TUI redraws while waiting for input.
+const CLOCK_REFRESH: std::time::Duration = std::time::Duration::from_secs(1);
+
 fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<bool> {
     loop {
         check_size(terminal)?;
         terminal.hide_cursor()?;
+        app.tick_clock();
         terminal.draw(|frame| render(frame, app))?;
+        if app.save_pending_screenshot() {
+            // Redraw to show whether it was saved
+            continue;
+        }
         
-        if let Event::Key(key) = event::read()? {
+        // Wake once a second so the header clock keeps counting
+        if !event::poll(CLOCK_REFRESH)? {
+            continue;
+        }
+        let event = event::read()?;
+        // Time up to the key press belongs to the army that was to move
+        app.tick_clock();
+        if let Event::Resize(_, height) = event {
+            // Redraw at the new size on the next pass
+            app.handle_resize(height);
+            continue;
+        }
+        if let Event::Key(key) = event {
             if key.kind == KeyEventKind::Press {
                 // Handle Ctrl-C for immediate exit
                 if key.code == KeyCode::Char('c')
@@ -279,6 +477,14 @@ fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<bool> {
                             app.add_char(to_insert);
                         }
                         KeyCode::Backspace => app.delete_char(),
+                        KeyCode::Left => app.move_cursor(-1, 0),
+                        KeyCode::Right => app.move_cursor(1, 0),
+                        KeyCode::Up => app.move_cursor(0, 1),
+                        KeyCode::Down => app.move_cursor(0, -1),
+                        KeyCode::Enter if app.input.trim().is_empty() => {
+                            app.activate_cursor();
+                            app.input.clear();
+                        }
                         KeyCode::Enter => {
                             let input = app.input.trim().to_string();
                             if !app.try_select_square(&input) {
@@ -306,6 +512,20 @@ fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<bool> {
                         }
                         _ => {}
                     },
+                    CurrentScreen::GameOver => match key.code {
+                        KeyCode::Char('n') => app.new_game(),
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            app.scroll_game_over(-1);
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            app.scroll_game_over(1);
+                        }
+                        KeyCode::Char('u') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                            app.undo();
+                        }
+                        _ => {}
+                    },
                     CurrentScreen::Exiting => match key.code {
                         KeyCode::Char('y') => return Ok(true),
                         KeyCode::Char('n') => {
@@ -319,76 +539,168 @@ fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<bool> {
     }
 }
 
+/// Checks `--format` against every requested command that reads it before
+/// anything runs, so a format one command takes can't abort another
+/// half-way through.
+fn check_format(args: &Args) -> Result<(), String> {
+    const TEXT_OR_JSON: &[&str] = &["text", "json"];
+    let readers: [(&str, bool, &[&str]); 5] = [
+        ("--list-arrays", args.list_arrays, TEXT_OR_JSON),
+        ("--tree", args.tree.is_some(), TEXT_OR_JSON),
+        ("--mobility", args.mobility, TEXT_OR_JSON),
+        ("--stats", args.stats, TEXT_OR_JSON),
+        ("--auto-play", args.auto_play, &["text", "ndjson"]),
+    ];
+    let format = args.format.to_lowercase();
+    for (flag, requested, accepted) in readers {
+        if requested && !accepted.contains(&format.as_str()) {
+            return Err(format!(
+                "--format {} does not apply to {} (use {})",
+                args.format,
+                flag,
+                accepted.join(" or ")
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn run_headless(args: Args) {
     use crate::engine::game::Game;
     use crate::engine::arrays::{default_array, find_array_by_name, available_arrays};
     use crate::engine::ai;
     use std::fs;
     
+    if let Err(e) = check_format(&args) {
+        eprintln!("❌ {}", e);
+        process::exit(1);
+    }
+
     // Handle list-arrays command first (doesn't need game state)
     if args.list_arrays {
-        list_arrays();
+        match args.format.to_lowercase().as_str() {
+            "text" => list_arrays(),
+            "json" => list_arrays_json(),
+            _ => {
+                eprintln!("❌ Unknown array list format: {} (use text or json)", args.format);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    // Handle compare command first (works on its own pair of files)
+    if let Some(files) = &args.compare {
+        compare_games(&files[0], &files[1]);
         return;
     }
     
+    // Watch mode runs until killed
+    if let Some(file) = &args.watch {
+        watch_state_file(file);
+    }
+    
     // Handle generate command first (doesn't need existing game)
     if let Some(gen_str) = &args.generate {
         generate_position(gen_str, &args);
         return;
     }
+    if args.generate_random {
+        generate_random_position(&args);
+        return;
+    }
     
     // Load or create game
-    let mut game = if let Some(state_file) = &args.state {
-        if let Ok(json) = fs::read_to_string(state_file) {
-            Game::from_json(&json).unwrap_or_else(|_| {
-                let array = if let Some(array_name) = &args.array {
-                    find_array_by_name(array_name).unwrap_or_else(|| {
-                        eprintln!("❌ Unknown array: {}", array_name);
-                        eprintln!("Use --list-arrays to see available options");
-                        process::exit(1);
-                    })
-                } else {
-                    default_array()
-                };
-                Game::from_array_spec(array)
-            })
+    let saved = args
+        .state
+        .as_ref()
+        .and_then(|state_file| fs::read_to_string(state_file).ok())
+        .and_then(|json| Game::from_json(&json).ok());
+    let mut game = match saved {
+        Some(game) => game,
+        None => match &args.array_file {
+            Some(path) => or_exit(load::from_array_file(path)),
+            None => or_exit(load::from_array(args.array.as_deref())),
+        },
+    };
+    
+    // With the PGN going to stdout, progress notes go to stderr.
+    let note = |message: String| {
+        if args.export_pgn.as_deref() == Some(load::STDIO_PATH) {
+            eprintln!("{}", message);
         } else {
-            let array = if let Some(array_name) = &args.array {
-                find_array_by_name(array_name).unwrap_or_else(|| {
-                    eprintln!("❌ Unknown array: {}", array_name);
-                    eprintln!("Use --list-arrays to see available options");
-                    process::exit(1);
-                })
-            } else {
-                default_array()
-            };
-            Game::from_array_spec(array)
+            println!("{}", message);
         }
-    } else {
-        let array = if let Some(array_name) = &args.array {
-            find_array_by_name(array_name).unwrap_or_else(|| {
-                eprintln!("❌ Unknown array: {}", array_name);
-                eprintln!("Use --list-arrays to see available options");
-                process::exit(1);
-            })
-        } else {
-            default_array()
-        };
-        Game::from_array_spec(array)
     };
     
     // Import PGN if provided
     if let Some(pgn_file) = &args.import_pgn {
-        game = import_pgn(pgn_file);
+        let import = or_exit(load::open_source(pgn_file).and_then(load::from_pgn));
+        for warning in &import.warnings {
+            eprintln!("Warning: {}", warning);
+        }
+        let source = if pgn_file == load::STDIO_PATH { "stdin" } else { pgn_file.as_str() };
+        note(format!("Imported {} moves from {}", import.moves, source));
+        game = import.game;
         // Save to state file if provided
         if let Some(save_file) = &args.state {
             if let Ok(json) = game.to_json() {
                 fs::write(save_file, json).ok();
-                println!("Imported and saved to {}", save_file);
+                note(format!("Imported and saved to {}", save_file));
             }
         }
     }
     
+    // Import compact position if provided
+    if let Some(compact_file) = &args.import_compact {
+        game = or_exit(load::read_source(compact_file).and_then(|s| load::from_compact(&s)));
+        if let Some(save_file) = &args.state {
+            if let Ok(json) = game.to_json() {
+                fs::write(save_file, json).ok();
+                note(format!("Imported and saved to {}", save_file));
+            }
+        }
+    }
+    
+    if args.new {
+        game.reset_to_start();
+        if let Some(save_file) = &args.state {
+            let written = game
+                .to_json()
+                .map_err(|e| e.to_string())
+                .and_then(|json| fs::write(save_file, json).map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                eprintln!("❌ Could not save the new game to {}: {}", save_file, e);
+                process::exit(1);
+            }
+            note(format!("New game saved to {}", save_file));
+        }
+    }
+    
+    if args.verify {
+        if let Err((ply, reason)) = game.verify_history() {
+            eprintln!("❌ History check failed at ply {}: {}", ply + 1, reason);
+            process::exit(1);
+        }
+        if !args.quiet {
+            println!("✓ History verified ({} plies)", game.move_history.len());
+        }
+    }
+    
+    // Assign controllers if a player count was given
+    if let Some(players) = args.players {
+        if let Err(e) = game.assign_players(players) {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    }
+    
+    if args.no_forced_king {
+        game.set_force_king_move_in_check(false);
+    }
+    
+    apply_to_move(&mut game, &args);
+    
     // Parse AI armies
     let ai_armies: Vec<Army> = if let Some(ai_str) = &args.ai {
         ai_str.split(',')
@@ -398,6 +710,12 @@ fn run_headless(args: Args) {
         Vec::new()
     };
     
+    // JSON protocol mode
+    if args.serve {
+        serve(&mut game);
+        return;
+    }
+    
     // Interactive mode
     if args.interactive {
         run_interactive(&mut game, &ai_armies, &args);
@@ -422,18 +740,63 @@ fn run_headless(args: Args) {
         return;
     }
     
+    if let Some(team_name) = &args.heatmap {
+        let Some(team) = Team::from_name(team_name) else {
+            eprintln!("❌ Unknown team: {} (use air or earth)", team_name);
+            process::exit(1);
+        };
+        println!("{} coverage", team.name());
+        println!("{}", crate::engine::board::print_heatmap(&game.coverage_counts(team)));
+        return;
+    }
+    
+    if let Some(army_name) = &args.coverage {
+        let Some(army) = Army::from_str(army_name) else {
+            eprintln!("❌ Unknown army: {}. {}", army_name, Army::suggest_army(army_name));
+            process::exit(1);
+        };
+        let covered = game.army_coverage(army);
+        println!("{} coverage ({} squares)", army.display_name(), covered.count_ones());
+        println!("{}", crate::engine::board::print_bitboard(covered));
+        return;
+    }
+    
+    if let Some(square_str) = &args.reach {
+        if let Err(e) = show_reach(&game, square_str) {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+    
     // Query rules if provided
     if let Some(query_str) = &args.query {
         query_rules(query_str);
         return;
     }
     
+    // Benchmark ignores any loaded game so runs are comparable
+    if args.bench {
+        run_perft(&mut Game::from_array_spec(default_array()), 4);
+        return;
+    }
+    
+    if let Some(depth) = args.profile_movegen {
+        profile_movegen(&mut game, depth);
+        return;
+    }
+    
     // Perft if provided
     if let Some(depth) = args.perft {
         run_perft(&mut game, depth);
         return;
     }
     
+    if let Some(depth) = args.tree {
+        show_move_tree(&game, depth, &args.format);
+        return;
+    }
+    
     // Convert format if provided
     if let Some(format) = &args.convert {
         convert_format(&game, format);
@@ -442,15 +805,19 @@ fn run_headless(args: Args) {
     
     // Execute move if provided
     if let Some(move_cmd) = &args.move_cmd {
-        if let Err(e) = execute_headless_move(&mut game, move_cmd, &args) {
+        if let Err((code, e)) = execute_headless_move(&mut game, move_cmd, &args) {
             eprintln!("Error: {}", e);
-            process::exit(1);
+            process::exit(code);
         }
         
         // AI moves after player move
         make_ai_moves(&mut game, &ai_armies, &args);
     }
     
+    if let Some(line) = &args.line {
+        play_line(&mut game, line, &args);
+    }
+    
     // Undo moves if requested
     if let Some(count) = args.undo {
         match game.undo(count) {
@@ -472,6 +839,48 @@ fn run_headless(args: Args) {
         }
     }
     
+    if let Some(army_str) = &args.request_takeback {
+        let army = Army::from_str(army_str.trim()).unwrap_or_else(|| {
+            eprintln!("❌ Unknown army '{}'. {}", army_str, Army::suggest_army(army_str));
+            process::exit(1);
+        });
+        if let Err(e) = game.request_takeback(army) {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+        if !args.quiet {
+            println!("{} requests a takeback; waiting for an opponent to grant it", army.display_name());
+        }
+        if let Some(save_file) = &args.state {
+            if let Ok(json) = game.to_json() {
+                std::fs::write(save_file, json).ok();
+            }
+        }
+    }
+    
+    if let Some(army_str) = &args.grant_takeback {
+        let granter = Army::from_str(army_str.trim()).unwrap_or_else(|| {
+            eprintln!("❌ Unknown army '{}'. {}", army_str, Army::suggest_army(army_str));
+            process::exit(1);
+        });
+        match game.grant_takeback(granter) {
+            Ok(plies) => {
+                if !args.quiet {
+                    println!("Takeback granted: undid {} ply(s)", plies);
+                }
+                if let Some(save_file) = &args.state {
+                    if let Ok(json) = game.to_json() {
+                        std::fs::write(save_file, json).ok();
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                process::exit(1);
+            }
+        }
+    }
+    
     // Auto-play mode
     if args.auto_play {
         auto_play(&mut game, &ai_armies, &args);
@@ -492,21 +901,50 @@ fn run_headless(args: Args) {
         evaluate_position(&mut game);
     }
     
+    if args.mobility {
+        show_mobility(&game, &args.format);
+    }
+    
     if args.stats {
-        show_stats(&game);
+        show_stats(&game, &args.format);
     }
     
     if let Some(output_file) = &args.export_pgn {
-        export_pgn(&game, output_file);
+        export_pgn(&game, output_file, &args.pgn_dialect);
+    }
+    
+    if let Some(output_file) = &args.export_html {
+        export_html(&game, output_file);
     }
     
     if args.status {
         show_status(&game);
     }
     
+    if args.timeline {
+        println!("{}", game.timeline_text(crate::engine::game::TIMELINE_MOVERS));
+    }
+    
+    if args.hash {
+        println!("{:016x}", game.position_hash());
+    }
+    
     // Show board
     if args.show {
-        show_board(&game);
+        match args.orient.as_deref() {
+            Some(name) => match Army::from_str(name) {
+                Some(army) => show_board_oriented(&game, army),
+                None => {
+                    eprintln!("❌ Unknown army: {}. {}", name, Army::suggest_army(name));
+                    process::exit(1);
+                }
+            },
+            None => show_board(&game),
+        }
+    }
+    
+    if args.debug_bitboards {
+        println!("{}", game.board.debug_layers());
     }
     
     // Save state
@@ -517,77 +955,73 @@ fn run_headless(args: Args) {
     }
 }
 
-fn execute_headless_move(game: &mut Game, move_cmd: &str, args: &Args) -> Result<(), String> {
-    // Parse move command (format: "blue: e2-e4" or "blue: e2e4")
-    let parts: Vec<&str> = move_cmd.split(':').collect();
-    if parts.len() != 2 {
-        return Err("Move must follow format 'army: e2-e4' (e.g., 'blue: e2-e3')".to_string());
-    }
-    
-    let army_str = parts[0].trim();
-    let army = Army::from_str(army_str)
-        .ok_or_else(|| format!("Unknown army '{}'. {}", army_str, Army::suggest_army(army_str)))?;
-    
-    let move_part = parts[1].trim().replace('x', "-");
+/// Exit codes for a rejected `--move`, so scripts can branch on the cause.
+/// Malformed input exits with 1.
+const EXIT_WRONG_TURN: i32 = 2;
+const EXIT_ILLEGAL_MOVE: i32 = 3;
+const EXIT_FROZEN: i32 = 4;
+
+fn execute_headless_move(game: &mut Game, move_cmd: &str, args: &Args) -> Result<(), (i32, String)> {
+    let (army, from, to, promotion) = parse_army_move(move_cmd).map_err(|e| (1, e.to_string()))?;
     
-    // Parse coordinates - support both "e2-e4" and "e2e4" formats
-    let coords: Vec<&str> = if move_part.contains('-') {
-        move_part.split('-').collect()
-    } else if move_part.len() == 4 {
-        // Compact notation: e2e4
-        vec![&move_part[0..2], &move_part[2..4]]
+    let code = if game.army_is_frozen(army) {
+        EXIT_FROZEN
+    } else if army != game.current_army() {
+        EXIT_WRONG_TURN
     } else {
-        return Err("Move must be in format 'e2-e4' or 'e2e4'".to_string());
+        EXIT_ILLEGAL_MOVE
     };
-    
-    if coords.len() != 2 {
-        return Err("Move must contain source and destination (e.g., e2-e4 or e2e4)".to_string());
-    }
-    
-    let from = parse_square_headless(coords[0].trim())?;
-    let to = parse_square_headless(coords[1].trim())?;
-    
-    game.apply_move(army, from, to, None)?;
+    let outcome = game.apply_move(army, from, to, promotion).map_err(|e| (code, e.to_string()))?;
     
     if !args.quiet {
-        println!("✓ {} moved from {} to {}", army.display_name(), coords[0], coords[1]);
+        let promoted = outcome
+            .promoted
+            .map(|kind| format!(", promoting to {}", kind.name()))
+            .unwrap_or_default();
+        println!(
+            "✓ {} moved from {} to {}{}",
+            army.display_name(),
+            square_name(from),
+            square_name(to),
+            promoted
+        );
     }
     
     Ok(())
 }
 
-fn parse_square_headless(s: &str) -> Result<u8, String> {
-    let chars: Vec<char> = s.chars().collect();
-    if chars.len() != 2 {
-        return Err(format!("Invalid square '{}'. Expected format: a1-h8 (e.g., e2, d4)", s));
-    }
-    let file = chars[0].to_ascii_lowercase() as u8;
-    let rank = chars[1] as u8;
-    
-    if file < b'a' || file > b'h' {
-        return Err(format!("Invalid file '{}'. Must be a-h", chars[0]));
-    }
-    if rank < b'1' || rank > b'8' {
-        return Err(format!("Invalid rank '{}'. Must be 1-8", chars[1]));
+/// Applies each move of a `--line` in order, then shows the board and
+/// `evaluate_position`. The first illegal move ends the run with its place
+/// in the line and --move's exit code.
+fn play_line(game: &mut Game, line: &str, args: &Args) {
+    let moves: Vec<&str> = line.split(',').map(str::trim).filter(|m| !m.is_empty()).collect();
+    for (index, move_cmd) in moves.iter().enumerate() {
+        if let Err((code, e)) = execute_headless_move(game, move_cmd, args) {
+            eprintln!("Error: move {} of {} ({}): {}", index + 1, moves.len(), move_cmd, e);
+            process::exit(code);
+        }
     }
     
-    Ok((rank - b'1') * 8 + (file - b'a'))
+    println!("\nAfter {} moves, {} to move\n", moves.len(), game.current_army().display_name());
+    show_board(game);
+    println!();
+    evaluate_position(game);
 }
 
 fn make_ai_moves(game: &mut Game, ai_armies: &[Army], args: &Args) {
-    loop {
+    while game.skip_stuck_armies() {
         let current = game.current_army();
         if !ai_armies.contains(&current) {
             break;
         }
         
-        if let Some(mv) = ai::capture_preferring_move(game, current) {
+        if let Some(mv) = choose_ai_move(game, current, args) {
             let from_file = (b'a' + (mv.from % 8)) as char;
             let from_rank = (b'1' + (mv.from / 8)) as char;
             let to_file = (b'a' + (mv.to % 8)) as char;
             let to_rank = (b'1' + (mv.to / 8)) as char;
             
-            game.apply_move(current, mv.from, mv.to, None).ok();
+            game.apply_move(current, mv.from, mv.to, mv.promotion).ok();
             
             if !args.quiet {
                 println!("🤖 {} AI: {}{} -> {}{}", 
@@ -596,56 +1030,214 @@ fn make_ai_moves(game: &mut Game, ai_armies: &[Army], args: &Args) {
         } else {
             break;
         }
-        
-        if game.winning_team().is_some() {
-            break;
+    }
+}
+
+/// Picks an AI move using the search settings from the command line, falling
+/// back to the capture-preferring heuristic when no search was requested.
+fn choose_ai_move(game: &mut Game, army: Army, args: &Args) -> Option<crate::engine::types::Move> {
+    if let Some(millis) = args.ai_time {
+        ai::search_timed(game, army, millis)
+    } else if let Some(depth) = args.ai_depth {
+        if args.ai_team {
+            ai::team_aware_move(game, army, depth)
+        } else {
+            ai::search(game, army, depth)
         }
+    } else {
+        ai::capture_preferring_move(game, army)
     }
 }
 
+/// Backstop for auto-play; repetition and the fifty-move rule normally end
+/// a game long before this.
+const AUTO_PLAY_PLY_LIMIT: usize = 5_000;
+
 fn auto_play(game: &mut Game, ai_armies: &[Army], args: &Args) {
+    let ndjson = match args.format.to_lowercase().as_str() {
+        "text" => false,
+        "ndjson" => true,
+        _ => {
+            eprintln!("❌ Unknown auto-play format: {} (use text or ndjson)", args.format);
+            process::exit(1);
+        }
+    };
     let mut move_count = 0;
+    let mut draw_reason = None;
+    let mut aborted = false;
     
-    while game.winning_team().is_none() && move_count < 500 {
+    while game.skip_stuck_armies() {
+        if let Some(reason) = game.claimable_draw() {
+            draw_reason = Some(reason);
+            break;
+        }
+        if move_count >= AUTO_PLAY_PLY_LIMIT {
+            aborted = true;
+            break;
+        }
         let current = game.current_army();
         
-        if let Some(mv) = ai::capture_preferring_move(game, current) {
+        if let Some(mv) = choose_ai_move(game, current, args) {
             let from_file = (b'a' + (mv.from % 8)) as char;
             let from_rank = (b'1' + (mv.from / 8)) as char;
             let to_file = (b'a' + (mv.to % 8)) as char;
             let to_rank = (b'1' + (mv.to / 8)) as char;
             
-            game.apply_move(current, mv.from, mv.to, None).ok();
+            if game.apply_move(current, mv.from, mv.to, mv.promotion).is_err() {
+                break;
+            }
             move_count += 1;
             
-            println!("{}. {}: {}{} -> {}{}", 
-                move_count, current.display_name(), 
-                from_file, from_rank, to_file, to_rank);
+            if ndjson {
+                let capture = game.move_details.last().and_then(|detail| detail.captured);
+                emit_ndjson(&PlyRecord {
+                    ply: move_count,
+                    army: current.display_name(),
+                    from: square_name(mv.from),
+                    to: square_name(mv.to),
+                    capture: capture.map(|(army, kind)| CaptureRecord {
+                        army: army.display_name(),
+                        piece: kind.name(),
+                    }),
+                });
+            } else {
+                println!("{}. {}: {}{} -> {}{}", 
+                    move_count, current.display_name(), 
+                    from_file, from_rank, to_file, to_rank);
+            }
         } else {
             break;
         }
     }
     
-    if let Some(team) = game.winning_team() {
+    let winner = game.winning_team();
+    let (result, reason) = if winner.is_some() {
+        ("win", None)
+    } else if draw_reason.is_some() || game.draw_condition() {
+        ("draw", draw_reason.or_else(|| game.is_dead_position().then_some("dead position")))
+    } else if aborted {
+        ("aborted", Some("move limit"))
+    } else {
+        ("unfinished", None)
+    };
+    
+    if ndjson {
+        emit_ndjson(&SummaryRecord {
+            result,
+            reason,
+            winner: winner.map(|team| team.name()),
+            plies: move_count,
+        });
+    } else if let Some(team) = winner {
         println!("\n🏆 {} TEAM WINS after {} moves!", team.name().to_uppercase(), move_count);
     } else {
-        println!("\nGame ended after {} moves", move_count);
+        let outcome = match (result, reason) {
+            ("draw", Some(reason)) => format!("drawn by {}", reason),
+            ("draw", None) => "drawn".to_string(),
+            ("aborted", _) => "aborted (move limit)".to_string(),
+            _ => "ended".to_string(),
+        };
+        println!("\nGame {} after {} moves", outcome, move_count);
     }
 }
 
-fn show_legal_moves(game: &mut Game, army: Army) {
-    let moves = game.legal_moves(army).to_vec();
-    println!("Legal moves for {}:", army.display_name());
-    for mv in moves {
-        let from_file = (b'a' + (mv.from % 8)) as char;
-        let from_rank = (b'1' + (mv.from / 8)) as char;
-        let to_file = (b'a' + (mv.to % 8)) as char;
-        let to_rank = (b'1' + (mv.to / 8)) as char;
-        println!("  {}{} -> {}{}", from_file, from_rank, to_file, to_rank);
-    }
+#[derive(serde::Serialize)]
+struct PlyRecord {
+    ply: usize,
+    army: &'static str,
+    from: String,
+    to: String,
+    capture: Option<CaptureRecord>,
 }
 
-fn run_batch(game: &mut Game, batch_file: &str, args: &Args) {
+#[derive(serde::Serialize)]
+struct CaptureRecord {
+    army: &'static str,
+    piece: &'static str,
+}
+
+#[derive(serde::Serialize)]
+struct SummaryRecord {
+    /// "win", "draw", "aborted" (the move limit was hit) or "unfinished".
+    result: &'static str,
+    reason: Option<&'static str>,
+    winner: Option<&'static str>,
+    plies: usize,
+}
+
+#[derive(serde::Serialize)]
+struct WatchRecord {
+    turn: &'static str,
+    legal_moves: usize,
+    plies: usize,
+    result: &'static str,
+    winner: Option<&'static str>,
+}
+
+/// Polls `file` and emits a `WatchRecord` whenever its modification time or
+/// size changes. A missing or half-written file is retried on the next poll,
+/// so atomic replace-by-rename writes are picked up once they land.
+fn watch_state_file(file: &str) -> ! {
+    use std::fs;
+    use std::time::Duration;
+    
+    let mut last_seen = None;
+    loop {
+        if let Ok(meta) = fs::metadata(file) {
+            let stamp = (meta.modified().ok(), meta.len());
+            if last_seen != Some(stamp) {
+                let game = fs::read_to_string(file)
+                    .ok()
+                    .and_then(|json| Game::from_json(&json).ok());
+                if let Some(mut game) = game {
+                    last_seen = Some(stamp);
+                    let army = game.current_army();
+                    let winner = game.winning_team();
+                    let result = if winner.is_some() {
+                        "win"
+                    } else if game.draw_condition() {
+                        "draw"
+                    } else {
+                        "ongoing"
+                    };
+                    emit_ndjson(&WatchRecord {
+                        turn: army.display_name(),
+                        legal_moves: game.legal_moves(army).len(),
+                        plies: game.move_history.len(),
+                        result,
+                        winner: winner.map(|team| team.name()),
+                    });
+                }
+            }
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Writes one NDJSON record and flushes so a reading process sees it at once.
+fn emit_ndjson<T: serde::Serialize>(record: &T) {
+    use std::io::Write;
+    
+    let mut stdout = io::stdout().lock();
+    if let Ok(line) = serde_json::to_string(record) {
+        writeln!(stdout, "{}", line).ok();
+    }
+    stdout.flush().ok();
+}
+
+fn show_legal_moves(game: &mut Game, army: Army) {
+    let moves = game.legal_moves(army).to_vec();
+    println!("Legal moves for {}:", army.display_name());
+    for mv in moves {
+        let from_file = (b'a' + (mv.from % 8)) as char;
+        let from_rank = (b'1' + (mv.from / 8)) as char;
+        let to_file = (b'a' + (mv.to % 8)) as char;
+        let to_rank = (b'1' + (mv.to / 8)) as char;
+        println!("  {}{} -> {}{}", from_file, from_rank, to_file, to_rank);
+    }
+}
+
+fn run_batch(game: &mut Game, batch_file: &str, args: &Args) {
     use std::fs;
     
     let contents = match fs::read_to_string(batch_file) {
@@ -656,6 +1248,8 @@ fn run_batch(game: &mut Game, batch_file: &str, args: &Args) {
         }
     };
     
+    let mut array_index = initial_array_index(game);
+    
     for (line_num, line) in contents.lines().enumerate() {
         let line = line.trim();
         
@@ -687,24 +1281,12 @@ fn run_batch(game: &mut Game, batch_file: &str, args: &Args) {
                     eprintln!("Error: move requires argument");
                     continue;
                 }
-                let move_str = parts[1..].join(" ");
-                let move_parts: Vec<&str> = move_str.split(':').collect();
-                if move_parts.len() == 2 {
-                    if let Some(army) = Army::from_str(move_parts[0].trim()) {
-                        let coords = move_parts[1].trim().replace('x', "-");
-                        let coord_parts: Vec<&str> = coords.split('-').collect();
-                        if coord_parts.len() == 2 {
-                            if let (Ok(from), Ok(to)) = (
-                                parse_square_headless(coord_parts[0].trim()),
-                                parse_square_headless(coord_parts[1].trim())
-                            ) {
-                                match game.apply_move(army, from, to, None) {
-                                    Ok(msg) => println!("  ✓ {}", msg),
-                                    Err(e) => eprintln!("  ❌ {}", e),
-                                }
-                            }
-                        }
-                    }
+                match parse_army_move(&parts[1..].join(" ")) {
+                    Ok((army, from, to, promotion)) => match game.apply_move(army, from, to, promotion) {
+                        Ok(msg) => println!("  ✓ {}", msg),
+                        Err(e) => eprintln!("  ❌ {}", e),
+                    },
+                    Err(e) => eprintln!("  ❌ {}", e),
                 }
             }
             "legal" => {
@@ -714,6 +1296,27 @@ fn run_batch(game: &mut Game, batch_file: &str, args: &Args) {
                     show_legal_moves(game, army);
                 }
             }
+            "reach" => {
+                if parts.len() < 2 {
+                    eprintln!("Error: reach requires square argument");
+                } else if let Err(e) = show_reach(game, parts[1]) {
+                    eprintln!("  ❌ {}", e);
+                }
+            }
+            "arrays" => list_arrays(),
+            "array" => {
+                if parts.len() < 2 {
+                    eprintln!("Error: array requires <name|next|prev>");
+                    continue;
+                }
+                match resolve_array_command(&parts[1..].join(" "), array_index) {
+                    Ok(index) => {
+                        array_index = index;
+                        load_array_into(game, index);
+                    }
+                    Err(e) => eprintln!("  ❌ {}", e),
+                }
+            }
             _ => eprintln!("Unknown command: {}", cmd),
         }
     }
@@ -727,12 +1330,28 @@ fn run_batch(game: &mut Game, batch_file: &str, args: &Args) {
     }
 }
 
+/// Answers protocol requests from stdin until it closes.
+fn serve(game: &mut Game) {
+    use crate::engine::protocol::handle_line;
+    use std::io::BufRead;
+    
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        emit_ndjson(&handle_line(game, &line));
+    }
+}
+
 fn run_interactive(game: &mut Game, ai_armies: &[Army], args: &Args) {
     use std::io::{self, Write};
     
     println!("Enochian Chess Interactive Mode");
     println!("Type 'help' for commands, 'quit' to exit\n");
     
+    let mut array_index = initial_array_index(game);
+    
     loop {
         print!("> ");
         io::stdout().flush().unwrap();
@@ -762,7 +1381,11 @@ fn run_interactive(game: &mut Game, ai_armies: &[Army], args: &Args) {
                 println!("  validate <move>   - Validate a move");
                 println!("  move <move>       - Make a move (e.g., 'move blue: e2-e3')");
                 println!("  undo [N]          - Undo last N moves (default 1)");
+                println!("  annotate <ply> <symbol> - Tag a move (!!, !, !?, ?!, ?, ??)");
                 println!("  legal <army>      - Show legal moves for army");
+                println!("  reach <square>    - Show a piece's reach before and after king safety");
+                println!("  arrays            - List starting arrays");
+                println!("  array <name|next|prev> - Restart from another array");
                 println!("  quit              - Exit interactive mode");
             }
             "show" | "board" => {
@@ -778,7 +1401,7 @@ fn run_interactive(game: &mut Game, ai_armies: &[Army], args: &Args) {
                     println!("Usage: analyze <square>");
                 } else {
                     let square_str = parts[1];
-                    if let Ok(square) = parse_square_headless(square_str) {
+                    if let Ok(square) = parse_square(square_str) {
                         // Inline analyze logic
                         if let Some((piece_army, piece_kind)) = game.board.piece_at(square) {
                             println!("Square {}: {} {}", square_str, piece_army.display_name(), piece_kind.name());
@@ -806,34 +1429,15 @@ fn run_interactive(game: &mut Game, ai_armies: &[Army], args: &Args) {
                 if parts.len() < 2 {
                     println!("Usage: validate <move>");
                 } else {
-                    let move_str = parts[1..].join(" ");
-                    // Parse and validate
-                    let move_parts: Vec<&str> = move_str.split(':').collect();
-                    if move_parts.len() == 2 {
-                        if let Some(army) = Army::from_str(move_parts[0].trim()) {
-                            let coords = move_parts[1].trim().replace('x', "-");
-                            let coord_parts: Vec<&str> = coords.split('-').collect();
-                            if coord_parts.len() == 2 {
-                                if let (Ok(from), Ok(to)) = (
-                                    parse_square_headless(coord_parts[0].trim()),
-                                    parse_square_headless(coord_parts[1].trim())
-                                ) {
-                                    if game.is_legal_move(army, from, to) {
-                                        println!("✓ Valid move");
-                                    } else {
-                                        println!("❌ Invalid move");
-                                    }
-                                } else {
-                                    println!("Invalid square notation");
-                                }
+                    match parse_army_move(&parts[1..].join(" ")) {
+                        Ok((army, from, to, _)) => {
+                            if game.is_legal_move(army, from, to) {
+                                println!("✓ Valid move");
                             } else {
-                                println!("Invalid move format");
+                                println!("❌ {}", game.explain_illegal(army, from, to));
                             }
-                        } else {
-                            println!("Unknown army");
                         }
-                    } else {
-                        println!("Format: army: from-to");
+                        Err(e) => println!("{}", e),
                     }
                 }
             }
@@ -841,32 +1445,12 @@ fn run_interactive(game: &mut Game, ai_armies: &[Army], args: &Args) {
                 if parts.len() < 2 {
                     println!("Usage: move <army: from-to>");
                 } else {
-                    let move_str = parts[1..].join(" ");
-                    let move_parts: Vec<&str> = move_str.split(':').collect();
-                    if move_parts.len() == 2 {
-                        if let Some(army) = Army::from_str(move_parts[0].trim()) {
-                            let coords = move_parts[1].trim().replace('x', "-");
-                            let coord_parts: Vec<&str> = coords.split('-').collect();
-                            if coord_parts.len() == 2 {
-                                if let (Ok(from), Ok(to)) = (
-                                    parse_square_headless(coord_parts[0].trim()),
-                                    parse_square_headless(coord_parts[1].trim())
-                                ) {
-                                    match game.apply_move(army, from, to, None) {
-                                        Ok(msg) => println!("✓ {}", msg),
-                                        Err(e) => println!("❌ {}", e),
-                                    }
-                                } else {
-                                    println!("Invalid square notation");
-                                }
-                            } else {
-                                println!("Invalid move format");
-                            }
-                        } else {
-                            println!("Unknown army");
-                        }
-                    } else {
-                        println!("Format: army: from-to");
+                    match parse_army_move(&parts[1..].join(" ")) {
+                        Ok((army, from, to, promotion)) => match game.apply_move(army, from, to, promotion) {
+                            Ok(msg) => println!("✓ {}", msg),
+                            Err(e) => println!("❌ {}", e),
+                        },
+                        Err(e) => println!("{}", e),
                     }
                 }
             }
@@ -879,6 +1463,27 @@ fn run_interactive(game: &mut Game, ai_armies: &[Army], args: &Args) {
                     println!("Unknown army");
                 }
             }
+            "reach" => {
+                if parts.len() < 2 {
+                    println!("Usage: reach <square>");
+                } else if let Err(e) = show_reach(game, parts[1]) {
+                    println!("❌ {}", e);
+                }
+            }
+            "annotate" => {
+                if parts.len() < 3 {
+                    println!("Usage: annotate <ply> <symbol>");
+                } else {
+                    let result = parts[1]
+                        .parse()
+                        .map_err(|_| format!("Invalid ply: {}", parts[1]))
+                        .and_then(|ply| game.annotate(ply, parts[2]).map(|_| ply));
+                    match result {
+                        Ok(ply) => println!("✓ Annotated ply {} with {}", ply, parts[2]),
+                        Err(e) => println!("❌ {}", e),
+                    }
+                }
+            }
             "undo" | "u" => {
                 let count = if parts.len() > 1 {
                     parts[1].parse().unwrap_or(1)
@@ -890,6 +1495,32 @@ fn run_interactive(game: &mut Game, ai_armies: &[Army], args: &Args) {
                     Err(e) => println!("Error: {}", e),
                 }
             }
+            "arrays" => list_arrays(),
+            "array" => {
+                if parts.len() < 2 {
+                    println!("Usage: array <name|next|prev>");
+                    continue;
+                }
+                let index = match resolve_array_command(&parts[1..].join(" "), array_index) {
+                    Ok(index) => index,
+                    Err(e) => {
+                        println!("❌ {}", e);
+                        continue;
+                    }
+                };
+                print!("This discards the current game. Continue? [y/N] ");
+                io::stdout().flush().unwrap();
+                let mut answer = String::new();
+                if io::stdin().read_line(&mut answer).is_err() {
+                    break;
+                }
+                if answer.trim().eq_ignore_ascii_case("y") {
+                    array_index = index;
+                    load_array_into(game, index);
+                } else {
+                    println!("Cancelled");
+                }
+            }
             _ => println!("Unknown command. Type 'help' for commands."),
         }
     }
@@ -903,98 +1534,210 @@ fn run_interactive(game: &mut Game, ai_armies: &[Army], args: &Args) {
     }
 }
 
-fn import_pgn(pgn_file: &str) -> Game {
+/// Index into `available_arrays()` of the array `game` started from (the
+/// one chosen with `--array`, or recorded in a saved game), or of the
+/// default array if it isn't one of them.
+fn initial_array_index(game: &Game) -> usize {
+    use crate::engine::arrays::{available_arrays, find_array_by_name};
+    
+    game.array_name()
+        .and_then(find_array_by_name)
+        .and_then(|spec| available_arrays().iter().position(|s| s.name == spec.name))
+        .unwrap_or(0)
+}
+
+/// Resolves the argument of an `array` command (a name, `next` or `prev`)
+/// to an index into `available_arrays()`.
+fn resolve_array_command(arg: &str, current: usize) -> Result<usize, String> {
+    use crate::engine::arrays::{available_arrays, find_array_by_name};
+    
+    let specs = available_arrays();
+    match arg.to_lowercase().as_str() {
+        "next" => Ok((current + 1) % specs.len()),
+        "prev" => Ok((current + specs.len() - 1) % specs.len()),
+        _ => find_array_by_name(arg)
+            .and_then(|spec| specs.iter().position(|s| s.name == spec.name))
+            .ok_or_else(|| format!("Unknown array: {} (use 'arrays' to list them)", arg)),
+    }
+}
+
+/// Replaces `game` with a fresh game from the array at `index` and prints
+/// its turn order.
+fn load_array_into(game: &mut Game, index: usize) {
+    use crate::engine::arrays::available_arrays;
+    
+    let spec = available_arrays()[index];
+    *game = Game::from_array_spec(spec);
+    let order: Vec<&str> = game.config.turn_order.iter().map(|a| a.display_name()).collect();
+    println!("✓ Loaded array: {}", spec.name);
+    println!("Turn order: {}", order.join(" → "));
+}
+
+/// Prints a load failure and exits; the CLI's single exit point for
+/// `LoadError`s.
+fn or_exit<T>(result: Result<T, LoadError>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        if let LoadError::UnknownArray(_) = e {
+            eprintln!("Use --list-arrays to see available options");
+        }
+        process::exit(1);
+    })
+}
+
+fn compare_games(left_file: &str, right_file: &str) {
+    use crate::engine::compare::{compare_games, Comparison};
     use std::fs;
-    use crate::engine::arrays::default_array;
     
-    let contents = match fs::read_to_string(pgn_file) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Error reading PGN file: {}", e);
+    let load = |file: &str| -> Game {
+        let json = fs::read_to_string(file).unwrap_or_else(|e| {
+            eprintln!("❌ Failed to read {}: {}", file, e);
             process::exit(1);
-        }
+        });
+        Game::from_json(&json).unwrap_or_else(|e| {
+            eprintln!("❌ Failed to parse {}: {}", file, e);
+            process::exit(1);
+        })
     };
+    let left = load(left_file);
+    let right = load(right_file);
     
-    let mut game = Game::from_array_spec(default_array());
-    let mut move_count = 0;
+    let start = left.replay_start();
     
-    for line in contents.lines() {
-        let line = line.trim();
-        
-        // Skip headers and empty lines
-        if line.is_empty() || line.starts_with('[') {
-            continue;
+    let describe = |entry: (Army, u8, u8, Option<crate::engine::types::PieceKind>)| {
+        let (army, from, to, _) = entry;
+        if is_pass(&entry) {
+            return format!("{} pass", army.display_name());
         }
-        
-        // Parse moves (format: B:e2-e3 R:e7-e6)
-        for token in line.split_whitespace() {
-            // Skip move numbers (e.g., "1.")
-            if token.ends_with('.') {
-                continue;
-            }
-            
-            // Parse move (format: B:e2-e3)
-            let parts: Vec<&str> = token.split(':').collect();
-            if parts.len() != 2 {
-                continue;
-            }
-            
-            let army = match parts[0] {
-                "B" => Army::Blue,
-                "R" => Army::Red,
-                "K" => Army::Black,
-                "Y" => Army::Yellow,
-                _ => continue,
+        format!("{} {}-{}", army.display_name(), square_name(from), square_name(to))
+    };
+    let piece = |contents: Option<(Army, crate::engine::types::PieceKind)>| match contents {
+        Some((army, kind)) => format!("{} {}", army.display_name(), kind.letter()),
+        None => "empty".to_string(),
+    };
+    
+    match compare_games(&start, &left, &right) {
+        Comparison::Identical => {
+            println!("Games are identical ({} moves)", left.move_history.len());
+        }
+        Comparison::Prefix { left_is_shorter, plies } => {
+            let (short, long) = if left_is_shorter {
+                (left_file, right_file)
+            } else {
+                (right_file, left_file)
             };
-            
-            let move_str = parts[1];
-            let coords: Vec<&str> = move_str.split('-').collect();
-            if coords.len() != 2 {
-                continue;
-            }
-            
-            if let (Ok(from), Ok(to)) = (
-                parse_square_headless(coords[0]),
-                parse_square_headless(coords[1])
-            ) {
-                if let Err(e) = game.apply_move(army, from, to, None) {
-                    eprintln!("Warning: Failed to apply move {}: {}", token, e);
-                } else {
-                    move_count += 1;
-                }
+            println!("{} is a prefix of {} (common moves: {})", short, long, plies);
+        }
+        Comparison::Diverged { ply, left: left_move, right: right_move, board_diff } => {
+            println!("First difference at ply {}:", ply);
+            println!("  {}: {}", left_file, describe(left_move));
+            println!("  {}: {}", right_file, describe(right_move));
+            println!("\nBoard differences after ply {}:", ply);
+            for diff in board_diff {
+                println!("  {}: {} vs {}", square_name(diff.square), piece(diff.left), piece(diff.right));
             }
         }
     }
-    
-    println!("Imported {} moves from {}", move_count, pgn_file);
-    game
 }
 
-fn export_pgn(game: &Game, output_file: &str) {
+fn export_pgn(game: &Game, output_file: &str, dialect: &str) {
     use std::fs;
     
+    let san = match dialect.to_lowercase().as_str() {
+        "coordinate" => false,
+        "san" => true,
+        _ => {
+            eprintln!("❌ Unknown PGN dialect: {} (use coordinate or san)", dialect);
+            process::exit(1);
+        }
+    };
+    let movetext = if san { san_movetext(game) } else { coordinate_movetext(game) };
+    
     let mut pgn = String::new();
     
     // Header
-    pgn.push_str("[Event \"Enochian Chess Game\"]\n");
-    pgn.push_str(&format!("[Date \"{}\"]\n", chrono::Local::now().format("%Y.%m.%d")));
-    pgn.push_str("[Variant \"Enochian\"]\n");
-    pgn.push_str("[Players \"4\"]\n");
+    let metadata = &game.metadata;
+    let date = metadata
+        .date
+        .clone()
+        .unwrap_or_else(|| chrono::Local::now().format("%Y.%m.%d").to_string());
+    let mut header = |tag: &str, value: &str| pgn.push_str(&pgn_header(tag, value));
+    header("Event", metadata.event.as_deref().unwrap_or("Enochian Chess Game"));
+    header("Date", &date);
+    header("Variant", if san { "Enochian" } else { COORDINATE_VARIANT });
+    header("Players", metadata.players.as_deref().unwrap_or("4"));
+    if let Some(array) = &metadata.array {
+        header("Array", array);
+    }
+    if san {
+        // Standard PGN has no tag for more than two sides: Armies lists the
+        // turn order, and replaying through the rules tells whose move each
+        // token is, as frozen armies are skipped.
+        let armies: Vec<&str> = game.config.turn_order.iter().map(|army| army.display_name()).collect();
+        header("Dialect", "SAN");
+        header("Armies", &armies.join(" "));
+    }
+    
+    let result = match game.status {
+        Status::Won(team) => format!("{} team wins", team.name()),
+        Status::Draw => "Draw".to_string(),
+        Status::Ongoing => metadata.result.clone().unwrap_or_else(|| "*".to_string()),
+    };
+    header("Result", &result);
     
-    if let Some(team) = game.winning_team() {
-        pgn.push_str(&format!("[Result \"{} team wins\"]\n", team.name()));
-    } else {
-        pgn.push_str("[Result \"*\"]\n");
+    pgn.push_str("\n");
+    pgn.push_str(&movetext);
+    
+    if output_file == load::STDIO_PATH {
+        print!("{}", pgn);
+        return;
+    }
+    if let Err(e) = fs::write(output_file, pgn) {
+        eprintln!("Error writing PGN: {}", e);
+        process::exit(1);
     }
     
-    pgn.push_str("\n");
+    println!("Exported to {}", output_file);
+}
+
+fn export_html(game: &Game, output_file: &str) {
+    let written = crate::engine::viewer::to_html(game)
+        .and_then(|html| std::fs::write(output_file, html).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        eprintln!("Error writing HTML: {}", e);
+        process::exit(1);
+    }
+    println!("Exported {} plies to {}", game.move_history.len(), output_file);
+}
+
+/// A PGN tag pair line, with `\\` and `"` in the value escaped.
+fn pgn_header(tag: &str, value: &str) -> String {
+    format!("[{} \"{}\"]\n", tag, value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `[Variant]` value for coordinate movetext, naming the army tags.
+const COORDINATE_VARIANT: &str = "Enochian; armies B=Blue Bk=Black R=Red Y=Yellow";
+
+/// Movetext in the coordinate dialect: `B:e2-e3`, four plies per line.
+fn coordinate_movetext(game: &Game) -> String {
+    let mut pgn = String::new();
     
-    // Moves
-    for (i, (army, from, to, promotion)) in game.move_history.iter().enumerate() {
+    for (i, entry) in game.move_history.iter().enumerate() {
+        let (army, from, to, promotion) = entry;
         if i % 4 == 0 {
             pgn.push_str(&format!("{}. ", i / 4 + 1));
         }
         
+        let army_code = army.pgn_tag();
+        let note = game.annotation(i + 1).unwrap_or("");
+        if is_pass(entry) {
+            pgn.push_str(&format!("{}:pass{} ", army_code, note));
+            if (i + 1) % 4 == 0 {
+                pgn.push('\n');
+            }
+            continue;
+        }
+        
         let from_file = (b'a' + (from % 8)) as char;
         let from_rank = (b'1' + (from / 8)) as char;
         let to_file = (b'a' + (to % 8)) as char;
@@ -1012,14 +1755,9 @@ fn export_pgn(game: &Game, output_file: &str) {
             String::new()
         };
         
-        pgn.push_str(&format!("{}:{}{}-{}{}{} ", 
-            match army {
-                crate::engine::types::Army::Blue => "B",
-                crate::engine::types::Army::Red => "R",
-                crate::engine::types::Army::Black => "K",
-                crate::engine::types::Army::Yellow => "Y",
-            },
-            from_file, from_rank, to_file, to_rank, promo_str
+        pgn.push_str(&format!("{}:{}{}-{}{}{}{} ", 
+            army_code,
+            from_file, from_rank, to_file, to_rank, promo_str, note
         ));
         
         if (i + 1) % 4 == 0 {
@@ -1031,17 +1769,102 @@ fn export_pgn(game: &Game, output_file: &str) {
         pgn.push('\n');
     }
     
-    if let Err(e) = fs::write(output_file, pgn) {
-        eprintln!("Error writing PGN: {}", e);
-        process::exit(1);
+    pgn
+}
+
+/// Movetext in SAN, numbered by round of the turn order, with `--` for a
+/// divination pass. Replays the history from its starting array.
+fn san_movetext(game: &Game) -> String {
+    let mut replay = game.replay_start();
+    let round_of = |army: Army| game.config.turn_order.iter().position(|&a| a == army).unwrap_or(0);
+    let mut tokens = Vec::new();
+    let mut round = 0;
+    let mut last_position = None;
+    for (i, entry) in game.move_history.iter().enumerate() {
+        let &(army, from, to, promotion) = entry;
+        let position = round_of(army);
+        if last_position.map_or(true, |last| position <= last) {
+            round += 1;
+            tokens.push(format!("{}.", round));
+        }
+        last_position = Some(position);
+        let note = game.annotation(i + 1).unwrap_or("");
+        if is_pass(entry) {
+            replay.record_pass(army, crate::engine::types::PieceKind::King);
+            tokens.push(format!("--{}", note));
+            continue;
+        }
+        match replay.san(army, from, to, promotion) {
+            Ok(san) => {
+                tokens.push(format!("{}{}", san, note));
+                let _ = replay.apply_move(army, from, to, promotion);
+            }
+            Err(e) => {
+                eprintln!("❌ Cannot write ply {} as SAN: {}", i + 1, e);
+                process::exit(1);
+            }
+        }
+    }
+    let mut text = String::new();
+    for token in tokens {
+        if token.ends_with('.') && !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&token);
+        text.push(' ');
+    }
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text
+}
+
+/// One-line mobility snapshot in turn order, e.g. `Blue:20 Red:20 ...`.
+fn show_mobility(game: &Game, format: &str) {
+    let counts = game.mobility_counts();
+    match format.to_lowercase().as_str() {
+        "text" => {
+            let line: Vec<String> = game.config.turn_order.iter()
+                .map(|army| format!("{}:{}", army.display_name(), counts[army.index()]))
+                .collect();
+            println!("{}", line.join(" "));
+        }
+        "json" => {
+            let map: serde_json::Map<String, serde_json::Value> = game.config.turn_order.iter()
+                .map(|army| (army.display_name().to_string(), counts[army.index()].into()))
+                .collect();
+            println!("{}", serde_json::Value::Object(map));
+        }
+        _ => {
+            eprintln!("❌ Unknown mobility format: {} (use text or json)", format);
+            process::exit(1);
+        }
     }
-    
-    println!("Exported to {}", output_file);
 }
 
-fn show_stats(game: &Game) {
+fn show_stats(game: &Game, format: &str) {
+    use crate::engine::stats::GameStats;
     use crate::engine::types::{Army, PieceKind};
     
+    let stats = GameStats::from_game(game);
+    match format.to_lowercase().as_str() {
+        "text" => {}
+        "json" => {
+            match stats.to_json() {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("❌ Failed to convert stats to JSON: {}", e);
+                    process::exit(1);
+                }
+            }
+            return;
+        }
+        _ => {
+            eprintln!("❌ Unknown stats format: {} (use text or json)", format);
+            process::exit(1);
+        }
+    }
+    
     println!("Game Statistics\n");
     
     // Move count
@@ -1079,6 +1902,22 @@ fn show_stats(game: &Game) {
         }
     }
     
+    // Piece activity
+    println!("\nPiece Activity (moves/captures):");
+    for army_stats in &stats.armies {
+        let active: Vec<String> = army_stats
+            .by_kind
+            .iter()
+            .filter(|piece| piece.moves > 0)
+            .map(|piece| format!("{} {}/{}", piece.kind.name(), piece.moves, piece.captures))
+            .collect();
+        if active.is_empty() {
+            println!("  {}: none", army_stats.army.display_name());
+        } else {
+            println!("  {}: {}", army_stats.army.display_name(), active.join(", "));
+        }
+    }
+    
     // Army status
     println!("\nArmy Status:");
     for &army in Army::ALL.iter() {
@@ -1093,7 +1932,7 @@ fn show_stats(game: &Game) {
     }
     
     // Winner
-    if let Some(team) = game.winning_team() {
+    if let Some(team) = stats.winner {
         println!("\n🏆 Winner: {} team", team.name());
     }
 }
@@ -1167,7 +2006,13 @@ fn show_history(game: &Game) {
     }
     
     println!("Move history ({} moves):\n", game.move_history.len());
-    for (i, (army, from, to, promotion)) in game.move_history.iter().enumerate() {
+    for (i, entry) in game.move_history.iter().enumerate() {
+        let (army, from, to, promotion) = entry;
+        let note = game.annotation(i + 1).unwrap_or("");
+        if is_pass(entry) {
+            println!("{}. {}: pass{}", i + 1, army.display_name(), note);
+            continue;
+        }
         let from_file = (b'a' + (from % 8)) as char;
         let from_rank = (b'1' + (from / 8)) as char;
         let to_file = (b'a' + (to % 8)) as char;
@@ -1185,18 +2030,20 @@ fn show_history(game: &Game) {
             String::new()
         };
         
-        println!("{}. {}: {}{}-{}{}{}", 
+        println!("{}. {}: {}{}-{}{}{}{}", 
             i + 1, 
             army.display_name(), 
             from_file, from_rank, 
             to_file, to_rank,
-            promo_str
+            promo_str,
+            note
         );
     }
 }
 
 fn show_status(game: &Game) {
     println!("Current turn: {}", game.current_army().display_name());
+    println!("Position: {:016x}", game.position_hash());
     
     for &army in Army::ALL.iter() {
         let status = if game.army_is_frozen(army) {
@@ -1206,20 +2053,63 @@ fn show_status(game: &Game) {
         } else {
             "Active"
         };
-        println!("  {}: {}", army.display_name(), status);
+        let controller = game.board.controller_for(army).label();
+        println!("  {}: {} ({})", army.display_name(), status, controller);
     }
     
-    if let Some(team) = game.winning_team() {
-        println!("\n🏆 Winner: {} team", team.name());
+    for (army, revivable, [first, second]) in game.revival_status() {
+        println!(
+            "  {} throne {}/{}: {}",
+            army.display_name(),
+            square_name(first),
+            square_name(second),
+            if revivable { "an allied king can revive it" } else { "no revival in reach" }
+        );
+    }
+    
+    match game.status {
+        Status::Won(team) => println!("\n🏆 Winner: {} team", team.name()),
+        Status::Draw => println!("\n⚖ Draw"),
+        Status::Ongoing => {}
     }
 }
 
+/// Prints the pseudo-legal and legal destinations of the piece on
+/// `square_str` side by side.
+fn show_reach(game: &Game, square_str: &str) -> Result<(), String> {
+    use crate::engine::board::print_bitboards_side_by_side;
+    
+    let square = parse_square(square_str)?;
+    let (pseudo_legal, legal) = game
+        .reach(square)
+        .ok_or_else(|| format!("No piece at {}", square_str))?;
+    if let Some((army, kind)) = game.board.piece_at(square) {
+        println!("{} {} on {}", army.display_name(), kind.name(), square_name(square));
+    }
+    println!(
+        "{}",
+        print_bitboards_side_by_side(
+            &format!("Pseudo-legal ({})", pseudo_legal.count_ones()),
+            pseudo_legal,
+            &format!("Legal ({})", legal.count_ones()),
+            legal,
+        )
+    );
+    Ok(())
+}
+
 fn show_board(game: &Game) {
     for row in game.board.ascii_rows() {
         println!("{}", row);
     }
 }
 
+fn show_board_oriented(game: &Game, army: Army) {
+    for row in game.board.ascii_rows_oriented(army) {
+        println!("{}", row);
+    }
+}
+
 fn list_arrays() {
     use crate::engine::arrays::available_arrays;
     
@@ -1231,6 +2121,20 @@ fn list_arrays() {
     }
 }
 
+/// Every array's `ArraySummary` as one JSON array, for front-end pickers.
+fn list_arrays_json() {
+    use crate::engine::arrays::{available_arrays, ArraySummary};
+    
+    let summaries: Vec<ArraySummary> = available_arrays().iter().map(|spec| spec.summary()).collect();
+    match serde_json::to_string_pretty(&summaries) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("❌ Failed to convert arrays to JSON: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
 fn convert_format(game: &Game, format: &str) {
     match format.to_lowercase().as_str() {
         "json" => {
@@ -1246,35 +2150,50 @@ fn convert_format(game: &Game, format: &str) {
                 println!("{}", row);
             }
         }
-        "compact" => {
-            // Compact notation: piece positions per army
-            for &army in crate::engine::types::Army::ALL.iter() {
-                let mut pieces = Vec::new();
-                for square in 0..64 {
-                    if let Some((piece_army, kind)) = game.board.piece_at(square) {
-                        if piece_army == army {
-                            let file = (b'a' + (square % 8)) as char;
-                            let rank = (b'1' + (square / 8)) as char;
-                            let piece_char = match kind {
-                                crate::engine::types::PieceKind::King => 'K',
-                                crate::engine::types::PieceKind::Queen => 'Q',
-                                crate::engine::types::PieceKind::Bishop => 'B',
-                                crate::engine::types::PieceKind::Knight => 'N',
-                                crate::engine::types::PieceKind::Rook => 'R',
-                                crate::engine::types::PieceKind::Pawn => 'P',
-                            };
-                            pieces.push(format!("{}{}{}", piece_char, file, rank));
-                        }
+        "compact" => print!("{}", game.to_compact()),
+        _ => {
+            eprintln!("❌ Unknown format: {}", format);
+            eprintln!("Available formats: json, ascii, compact");
+            process::exit(1);
+        }
+    }
+}
+
+fn show_move_tree(game: &Game, depth: u8, format: &str) {
+    use crate::engine::game::{MoveNode, MAX_TREE_DEPTH};
+    
+    if depth > MAX_TREE_DEPTH {
+        eprintln!("❌ Tree depth {} is too deep (at most {}); the tree grows about twentyfold per ply", depth, MAX_TREE_DEPTH);
+        process::exit(1);
+    }
+    let tree = game.move_tree(depth);
+    match format.to_lowercase().as_str() {
+        "text" => {
+            fn print_nodes(nodes: &[MoveNode], indent: usize) {
+                for node in nodes {
+                    if let (Some(army), Some(mv)) = (node.army, node.mv) {
+                        println!(
+                            "{}{} {}-{}",
+                            "  ".repeat(indent),
+                            army.display_name(),
+                            square_name(mv.from),
+                            square_name(mv.to)
+                        );
                     }
-                }
-                if !pieces.is_empty() {
-                    println!("{}:{}", army.display_name().to_lowercase(), pieces.join(","));
+                    print_nodes(&node.children, indent + 1);
                 }
             }
+            print_nodes(&tree.children, 0);
         }
+        "json" => match serde_json::to_string(&tree) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("❌ Failed to convert tree to JSON: {}", e);
+                process::exit(1);
+            }
+        },
         _ => {
-            eprintln!("❌ Unknown format: {}", format);
-            eprintln!("Available formats: json, ascii, compact");
+            eprintln!("❌ Unknown tree format: {} (use text or json)", format);
             process::exit(1);
         }
     }
@@ -1285,7 +2204,7 @@ fn run_perft(game: &mut Game, depth: u8) {
     
     println!("Running perft({})", depth);
     let start = Instant::now();
-    let nodes = perft(game, depth);
+    let nodes = game.perft(depth);
     let elapsed = start.elapsed();
     
     println!("Nodes: {}", nodes);
@@ -1293,97 +2212,67 @@ fn run_perft(game: &mut Game, depth: u8) {
     println!("NPS: {:.0}", nodes as f64 / elapsed.as_secs_f64());
 }
 
-fn perft(game: &mut Game, depth: u8) -> u64 {
-    if depth == 0 {
-        return 1;
-    }
-    
-    let army = game.current_army();
-    let moves = game.legal_moves(army).to_vec();
-    
-    if depth == 1 {
-        return moves.len() as u64;
-    }
-    
-    let mut nodes = 0u64;
-    for mv in moves {
-        let saved = game.clone();
-        if game.apply_move(army, mv.from, mv.to, None).is_ok() {
-            nodes += perft(game, depth - 1);
-        }
-        *game = saved;
+fn profile_movegen(game: &mut Game, depth: u8) {
+    use crate::engine::types::PieceKind;
+    
+    let profile = game.profile_movegen(depth);
+    let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+    println!("Move generation profile, depth {} ({} positions)", depth, profile.positions);
+    println!("{:<18} {:>10} {:>12}", "Step", "Count", "Time (ms)");
+    for kind in PieceKind::ALL {
+        println!(
+            "{:<18} {:>10} {:>12.3}",
+            kind.name(),
+            profile.pieces[kind.index()],
+            ms(profile.pseudo_legal[kind.index()])
+        );
     }
-    
-    nodes
+    println!(
+        "{:<18} {:>10} {:>12.3}",
+        "Self-check filter",
+        profile.candidates,
+        ms(profile.self_check_filter)
+    );
 }
 
 fn generate_position(gen_str: &str, args: &Args) {
-    use crate::engine::board::Board;
-    use crate::engine::game::Game;
-    use crate::engine::types::{Army, PieceKind, Piece};
-    use std::fs;
+    let mut game = or_exit(load::from_generate(gen_str));
+    let piece_count = game.board.iter_pieces().count();
     
-    let mut placements = Vec::new();
-    
-    // Parse format: "Kb1,Qc2:blue Ke8:red"
-    for army_spec in gen_str.split_whitespace() {
-        let parts: Vec<&str> = army_spec.split(':').collect();
-        if parts.len() != 2 {
-            eprintln!("❌ Invalid format. Use: 'Kb1,Qc2:blue Ke8:red'");
-            process::exit(1);
-        }
-        
-        let army = match Army::from_str(parts[1].trim()) {
-            Some(a) => a,
-            None => {
-                eprintln!("❌ Unknown army: {}", parts[1]);
-                process::exit(1);
-            }
-        };
-        
-        for piece_spec in parts[0].split(',') {
-            let piece_spec = piece_spec.trim();
-            if piece_spec.len() < 2 {
-                eprintln!("❌ Invalid piece spec: {}", piece_spec);
-                process::exit(1);
-            }
-            
-            let kind = match piece_spec.chars().next().unwrap() {
-                'K' => PieceKind::King,
-                'Q' => PieceKind::Queen,
-                'B' => PieceKind::Bishop,
-                'N' => PieceKind::Knight,
-                'R' => PieceKind::Rook,
-                'P' => PieceKind::Pawn,
-                c => {
-                    eprintln!("❌ Unknown piece: {}", c);
-                    process::exit(1);
-                }
-            };
-            
-            let square_str = &piece_spec[1..];
-            let square = match parse_square_headless(square_str) {
-                Ok(sq) => sq,
-                Err(e) => {
-                    eprintln!("❌ Invalid square {}: {}", square_str, e);
-                    process::exit(1);
-                }
-            };
-            
-            placements.push((army, Piece { army, kind, pawn_type: None }, 1u64 << square));
-        }
-    }
-    
-    if placements.is_empty() {
-        eprintln!("❌ No pieces specified");
+    println!("✓ Generated position with {} pieces", piece_count);
+    show_and_save_generated(&mut game, args);
+}
+
+fn generate_random_position(args: &Args) {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut game = or_exit(load::random_position(args.pieces, seed));
+    
+    println!(
+        "✓ Generated random position with {} pieces per army (seed {})",
+        args.pieces, seed
+    );
+    show_and_save_generated(&mut game, args);
+}
+
+/// Hands the move to the army named by --to-move, if any.
+fn apply_to_move(game: &mut Game, args: &Args) {
+    let Some(name) = &args.to_move else {
+        return;
+    };
+    let army = Army::from_str(name.trim()).unwrap_or_else(|| {
+        eprintln!("❌ Unknown army '{}'. {}", name, Army::suggest_army(name));
+        process::exit(1);
+    });
+    if let Err(e) = game.set_to_move(army) {
+        eprintln!("❌ {}", e);
         process::exit(1);
     }
+}
+
+fn show_and_save_generated(game: &mut Game, args: &Args) {
+    use std::fs;
     
-    let board = Board::new(&placements);
-    let game = Game::new(board);
-    
-    println!("✓ Generated position with {} pieces", placements.len());
-    
+    apply_to_move(game, args);
     if args.show {
         println!();
         for row in game.board.ascii_rows() {
@@ -1400,78 +2289,21 @@ fn generate_position(gen_str: &str, args: &Args) {
 }
 
 fn query_rules(query: &str) {
-    let q = query.to_lowercase();
-    
-    if q.contains("queen") && q.contains("capture") && q.contains("queen") {
-        println!("Can queens capture queens?");
-        println!("❌ No - Queens cannot capture other queens");
-    } else if q.contains("bishop") && q.contains("capture") && q.contains("bishop") {
-        println!("Can bishops capture bishops?");
-        println!("❌ No - Bishops cannot capture other bishops");
-    } else if q.contains("queen") && q.contains("bishop") {
-        println!("Can queens and bishops capture each other?");
-        println!("✓ Yes - Queens can capture bishops, and bishops can capture queens");
-    } else if q.contains("check") {
-        println!("Check rules:");
-        println!("• No checkmate - kings are captured like other pieces");
-        println!("• If in check with legal king moves, you MUST move the king");
-        println!("• If in check with no legal king moves, you may move any piece");
-    } else if q.contains("promotion") || q.contains("promote") {
-        println!("Promotion rules:");
-        println!("• Blue pawns promote on rank 8 (north edge)");
-        println!("• Red pawns promote on rank 1 (south edge)");
-        println!("• Black pawns promote on file h (east edge)");
-        println!("• Yellow pawns promote on file a (west edge)");
-        println!("• Privileged pawn: With only K+Q+P, K+B+P, or K+P remaining,");
-        println!("  the pawn can promote to any piece type");
-    } else if q.contains("frozen") || q.contains("freeze") {
-        println!("Frozen army rules:");
-        println!("• When a king is captured, that army becomes frozen");
-        println!("• Frozen pieces cannot move or attack");
-        println!("• Frozen pieces act as blocking terrain");
-        println!("• An army can be revived by controlling its throne square");
-    } else if q.contains("throne") {
-        println!("Throne square rules:");
-        println!("• Each army has a throne (king's starting position)");
-        println!("• Moving your king onto an ally's throne = gain control");
-        println!("• Controlling a throne revives that frozen army");
-    } else if q.contains("team") || q.contains("victory") || q.contains("win") {
-        println!("Victory conditions:");
-        println!("• Teams: Air (Blue + Black) vs Earth (Red + Yellow)");
-        println!("• Win by capturing both enemy kings");
-        println!("• Frozen armies can be revived via throne control");
-    } else if q.contains("queen") && q.contains("move") {
-        println!("Queen movement:");
-        println!("• Leaps exactly 2 squares (orthogonal or diagonal)");
-        println!("• Ignores intervening pieces (like a knight)");
-        println!("• Cannot move 1 square or 3+ squares");
-    } else if q.contains("pawn") && (q.contains("move") || q.contains("capture")) {
-        println!("Pawn movement:");
-        println!("• Moves 1 square forward");
-        println!("• Captures 1 square diagonally");
-        println!("• No double-step initial move");
-        println!("• No en passant");
-    } else if q.contains("stalemate") {
-        println!("Stalemate rules:");
-        println!("• If an army has no legal moves, that turn is skipped");
-        println!("• Play continues with the next army");
-    } else {
-        println!("Unknown query. Try:");
-        println!("  --query 'queen capture queen'");
-        println!("  --query 'bishop capture bishop'");
-        println!("  --query 'check'");
-        println!("  --query 'promotion'");
-        println!("  --query 'frozen'");
-        println!("  --query 'throne'");
-        println!("  --query 'victory'");
-        println!("  --query 'queen move'");
-        println!("  --query 'pawn move'");
-        println!("  --query 'stalemate'");
+    use crate::engine::rules::Topic;
+
+    match Topic::from_query(query) {
+        Some(topic) => println!("{}", topic.explanation()),
+        None => {
+            println!("Unknown query. Try:");
+            for topic in Topic::ALL {
+                println!("  --query '{}'", topic.example_query());
+            }
+        }
     }
 }
 
 fn analyze_square(game: &mut Game, square_str: &str) {
-    let square = match parse_square_headless(square_str.trim()) {
+    let square = match parse_square(square_str.trim()) {
         Ok(sq) => sq,
         Err(e) => {
             println!("❌ Invalid square: {}", e);
@@ -1497,6 +2329,15 @@ fn analyze_square(game: &mut Game, square_str: &str) {
             println!("Status: Active");
         }
         
+        if kind == crate::engine::types::PieceKind::Pawn {
+            let promotion_squares: Vec<String> = game
+                .promotion_squares(army)
+                .into_iter()
+                .map(|sq| format!("{}{}", (b'a' + sq % 8) as char, (b'1' + sq / 8) as char))
+                .collect();
+            println!("Promotes at: {}", promotion_squares.join(", "));
+        }
+        
         // Show legal moves from this square
         let all_moves = game.legal_moves(army).to_vec();
         let moves: Vec<_> = all_moves.iter()
@@ -1524,39 +2365,10 @@ fn analyze_square(game: &mut Game, square_str: &str) {
 }
 
 fn validate_move(game: &mut Game, move_cmd: &str) {
-    let parts: Vec<&str> = move_cmd.split(':').collect();
-    if parts.len() != 2 {
-        println!("❌ Invalid format. Use: army: e2-e4");
-        process::exit(1);
-    }
-    
-    let army = match Army::from_str(parts[0].trim()) {
-        Some(a) => a,
-        None => {
-            println!("❌ Unknown army: {}", parts[0].trim());
-            process::exit(1);
-        }
-    };
-    
-    let move_part = parts[1].trim().replace('x', "-");
-    let coords: Vec<&str> = move_part.split('-').collect();
-    if coords.len() != 2 {
-        println!("❌ Invalid move format. Use: e2-e4");
-        process::exit(1);
-    }
-    
-    let from = match parse_square_headless(coords[0].trim()) {
-        Ok(sq) => sq,
+    let (army, from, to, _) = match parse_army_move(move_cmd) {
+        Ok(parsed) => parsed,
         Err(e) => {
-            println!("❌ Invalid source square: {}", e);
-            process::exit(1);
-        }
-    };
-    
-    let to = match parse_square_headless(coords[1].trim()) {
-        Ok(sq) => sq,
-        Err(e) => {
-            println!("❌ Invalid destination square: {}", e);
+            println!("❌ {}", e);
             process::exit(1);
         }
     };
@@ -1577,7 +2389,7 @@ fn validate_move(game: &mut Game, move_cmd: &str) {
     // Check if move is legal
     if game.is_legal_move(army, from, to) {
         println!("✓ Valid move: {} {} → {}", 
-            army.display_name(), coords[0], coords[1]);
+            army.display_name(), square_name(from), square_name(to));
         
         // Show what piece is moving
         if let Some((piece_army, piece_kind)) = game.board.piece_at(from) {
@@ -1590,18 +2402,9 @@ fn validate_move(game: &mut Game, move_cmd: &str) {
         }
     } else {
         println!("❌ Illegal move: {} {} → {}", 
-            army.display_name(), coords[0], coords[1]);
+            army.display_name(), square_name(from), square_name(to));
         
-        // Provide helpful context
-        if let Some((piece_army, piece_kind)) = game.board.piece_at(from) {
-            if piece_army != army {
-                println!("  Reason: That piece belongs to {}", piece_army.display_name());
-            } else {
-                println!("  Reason: {} cannot move there", piece_kind.name());
-            }
-        } else {
-            println!("  Reason: No piece at {}", coords[0]);
-        }
+        println!("  Reason: {}", game.explain_illegal(army, from, to));
         
         process::exit(1);
     }
@@ -1,10 +1,14 @@
 use crate::engine::arrays::{available_arrays, default_array, find_array_by_name};
-use crate::engine::game::Game;
-use crate::engine::types::{Army, PieceKind, Square};
+use crate::engine::game::{DivinationResult, Game, Status, TIMELINE_MOVERS};
+use crate::engine::notation::parse_army_move;
+use crate::engine::types::{square_name, Army, PieceKind, Square, Team, ARMY_COUNT};
 use crate::engine::ai;
+use crate::ui::theme::Theme;
+use ratatui::text::Text;
 use std::fmt;
 use std::fs;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 pub struct App {
     pub game: Game,
@@ -16,26 +20,84 @@ pub struct App {
     pub selected_array: String,
     pub array_index: usize,
     pub help_scroll: usize,
+    pub game_over_scroll: usize,
+    /// Text capture of the last frame drawn while a screenshot was pending
+    /// or `capture_frames` was on.
     pub last_frame: Option<String>,
+    /// Capture every frame into `last_frame`, for tests and `--capture`.
+    /// Off in normal play, where building the capture is wasted work.
+    pub capture_frames: bool,
+    /// File `/screenshot` asked for; the next frame is captured into
+    /// `last_frame` and saved there.
+    pub pending_screenshot: Option<String>,
+    /// The board as last drawn, rebuilt only when its key changes.
+    pub board_cache: Option<(BoardRenderKey, Text<'static>)>,
     pub selected_army: Option<Army>,
     pub selected_square: Option<u8>,
+    /// Square under the keyboard cursor.
+    pub cursor: Square,
+    /// Empty throne last picked with no piece selected; the status panel
+    /// explains it.
+    pub inspected_throne: Option<Square>,
+    /// Squares typed for a move are read from the moving army's side of the
+    /// board, as `Army::relative_to_absolute`, rather than as absolute names.
+    pub relative_coords: bool,
     pub move_history: Vec<String>,
     pub undo_stack: Vec<Game>,
     pub redo_stack: Vec<Game>,
     pub captured_pieces: HashMap<Army, Vec<PieceKind>>,
     pub last_move: Option<(Army, Square, Square)>,
     pub colorblind_mode: bool,
+    pub theme: Theme,
     pub ai_armies: Vec<Army>,
+    /// Team whose square coverage is drawn over the board, if any.
+    pub heatmap: Option<Team>,
+    /// Shade the squares the army to move can reach or attack.
+    pub coverage: bool,
+    /// Play time of the current game. Only runs on the main screen while
+    /// the game is on, so reading help doesn't count.
+    pub game_time: Duration,
+    /// Time each army has spent with the move, by army index.
+    pub think_time: [Duration; ARMY_COUNT],
+    /// Show `game_time` in the header (`/clock`).
+    pub show_clock: bool,
+    /// When `tick_clock` last ran.
+    clock_tick: Instant,
+}
+
+/// Everything the drawn board depends on: the position, with whose move it
+/// is and which moves are legal, the selection and cursor, the square size
+/// and the display options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardRenderKey {
+    pub position: u64,
+    /// Thrones aren't part of the position hash, and two empty arrays
+    /// differ only in them.
+    pub thrones: [[Square; 2]; ARMY_COUNT],
+    pub en_passant: Option<Square>,
+    pub forced_king: bool,
+    pub selected_army: Option<Army>,
+    pub selected_square: Option<Square>,
+    pub cursor: Square,
+    pub square_size: u16,
+    pub heatmap: Option<Team>,
+    pub coverage: bool,
+    pub theme: Theme,
+    pub colorblind_mode: bool,
 }
 
 pub enum CurrentScreen {
     Main,
     Help,
+    GameOver,
     Exiting,
 }
 
 const MAX_INPUT_LENGTH: usize = 64;
 
+/// Rows of the help screen taken by its border and padding rather than text.
+pub const HELP_CHROME_HEIGHT: u16 = 4;
+
 pub enum UiCommand {
     Move {
         army: Army,
@@ -57,7 +119,16 @@ pub enum UiCommand {
     Undo,
     Redo,
     ToggleColorblind,
+    SetTheme(Theme),
+    Annotate(usize, String),
     ToggleAI(Army),
+    ToggleHeatmap(Team),
+    ToggleCoverage,
+    ToggleClock,
+    Rules,
+    ToggleForcedKing,
+    ToggleRelativeCoords,
+    Timeline,
 }
 
 #[derive(Debug)]
@@ -83,19 +154,74 @@ impl App {
             selected_array: spec.name.to_string(),
             array_index: 0,
             help_scroll: 0,
+            game_over_scroll: 0,
             last_frame: None,
+            capture_frames: false,
+            pending_screenshot: None,
+            board_cache: None,
             selected_army: Some(current_army),
             selected_square: None,
+            cursor: 0,
+            inspected_throne: None,
+            relative_coords: false,
             move_history: Vec::new(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             captured_pieces: HashMap::new(),
             last_move: None,
             colorblind_mode: false,
+            theme: Theme::default(),
             ai_armies: Vec::new(),
+            heatmap: None,
+            coverage: false,
+            game_time: Duration::ZERO,
+            think_time: [Duration::ZERO; ARMY_COUNT],
+            show_clock: true,
+            clock_tick: Instant::now(),
         }
     }
 
+    /// Charges the time since the last tick to the game clock and to the
+    /// army with the move. Call it before anything that can change whose
+    /// move it is, so each army pays only for its own thinking.
+    pub fn tick_clock(&mut self) {
+        let now = Instant::now();
+        let elapsed = now - self.clock_tick;
+        self.clock_tick = now;
+        if matches!(self.current_screen, CurrentScreen::Main) && !self.is_game_over() {
+            self.game_time += elapsed;
+            self.think_time[self.game.current_army().index()] += elapsed;
+        }
+    }
+
+    /// Starts play from `game`, such as one built from `--array` or
+    /// `--array-file`. The array selection follows the game's array when it
+    /// has one.
+    pub fn start_game(&mut self, game: Game) {
+        if let Some(name) = game.array_name() {
+            self.selected_array = name.to_string();
+            if let Some(index) = available_arrays().iter().position(|spec| spec.name == name) {
+                self.array_index = index;
+            }
+        }
+        self.selected_army = Some(game.current_army());
+        self.game = game;
+        self.reset_clock();
+    }
+
+    /// Zeroes the clocks for a new game.
+    pub fn reset_clock(&mut self) {
+        self.game_time = Duration::ZERO;
+        self.think_time = [Duration::ZERO; ARMY_COUNT];
+        self.clock_tick = Instant::now();
+    }
+
+    /// Round of play, counting a round as one ply per army in the turn
+    /// order, as PGN export numbers them.
+    pub fn round(&self) -> usize {
+        self.game.move_history.len() / self.game.config.turn_order.len() + 1
+    }
+
     pub fn add_char(&mut self, ch: char) {
         if self.input.chars().count() < MAX_INPUT_LENGTH {
             self.input.push(ch);
@@ -136,6 +262,7 @@ impl App {
             self.selected_army = Some(self.game.current_army());
             self.status_message = Some("Undone".to_string());
             self.error_message = None;
+            self.sync_game_over_screen();
         } else {
             self.error_message = Some("Nothing to undo".to_string());
         }
@@ -149,89 +276,193 @@ impl App {
             self.selected_army = Some(self.game.current_army());
             self.status_message = Some("Redone".to_string());
             self.error_message = None;
+            self.sync_game_over_screen();
         } else {
             self.error_message = Some("Nothing to redo".to_string());
         }
     }
 
+    /// Moves the cursor by `files` and `ranks`, stopping at the board edge.
+    /// The board is drawn with rank 8 at the top, so up is `ranks = 1`.
+    pub fn move_cursor(&mut self, files: i8, ranks: i8) {
+        let file = ((self.cursor % 8) as i8 + files).clamp(0, 7);
+        let rank = ((self.cursor / 8) as i8 + ranks).clamp(0, 7);
+        self.cursor = (rank * 8 + file) as Square;
+    }
+
+    /// Selects the piece under the cursor, or moves the selected piece
+    /// there, exactly as typing the square's name would.
+    pub fn activate_cursor(&mut self) -> bool {
+        self.select_square(self.cursor)
+    }
+
+    /// Selects the piece on the square named by `input`, or moves the
+    /// selected piece there. With relative coordinates on, the name is read
+    /// from the selected army's side of the board.
     pub fn try_select_square(&mut self, input: &str) -> bool {
-        if let Some(square) = parse_square(input) {
-            if let Some(selected_sq) = self.selected_square {
-                // Second square - try to move
-                if let Some(army) = self.selected_army {
-                    // Save state for undo
-                    self.undo_stack.push(self.game.clone());
-                    self.redo_stack.clear();
-                    
-                    // Check if there's a piece to capture
-                    if let Some((captured_army, captured_kind)) = self.game.board.piece_at(square) {
-                        if captured_army != army && captured_kind != PieceKind::King {
-                            self.captured_pieces.entry(captured_army)
-                                .or_insert_with(Vec::new)
-                                .push(captured_kind);
-                        }
+        match parse_square(input) {
+            Some(square) => self.select_square(self.absolute_square(square)),
+            None => false,
+        }
+    }
+
+    /// The board square meant by `square` as typed: itself, or with
+    /// relative coordinates on, the square it names from the selected
+    /// army's side.
+    pub fn absolute_square(&self, square: Square) -> Square {
+        match self.selected_army {
+            Some(army) if self.relative_coords => army.relative_to_absolute(square),
+            _ => square,
+        }
+    }
+
+    fn select_square(&mut self, square: Square) -> bool {
+        if self.is_game_over() {
+            return false;
+        }
+        if let Some(selected_sq) = self.selected_square {
+            // Second square - try to move
+            if let Some(army) = self.selected_army {
+                // Save state for undo
+                self.undo_stack.push(self.game.clone());
+                self.redo_stack.clear();
+                
+                // Check if there's a piece to capture
+                if let Some((captured_army, captured_kind)) = self.game.board.piece_at(square) {
+                    if captured_army != army && captured_kind != PieceKind::King {
+                        self.captured_pieces.entry(captured_army)
+                            .or_insert_with(Vec::new)
+                            .push(captured_kind);
                     }
-                    
-                    match self.game.apply_move(army, selected_sq, square, None) {
-                        Ok(msg) => {
-                            self.last_move = Some((army, selected_sq, square));
-                            self.move_history.push(format!("{}: {}->{}", 
-                                army.display_name(), 
-                                square_name(selected_sq), 
-                                square_name(square)));
-                            self.status_message = Some(msg);
-                            self.error_message = None;
-                            self.selected_square = None;
-                            self.selected_army = Some(self.game.current_army());
-                            
-                            // Check if AI should move next
-                            self.try_ai_move();
-                            
-                            return true;
-                        }
-                        Err(err) => {
-                            // Restore state on error - we just pushed it
-                            if let Some(prev) = self.undo_stack.pop() {
-                                self.game = prev;
-                            }
-                            self.error_message = Some(format!("{} (from {} to {})", 
-                                err, square_name(selected_sq), square_name(square)));
-                            self.selected_square = None;
-                            return false;
+                }
+                
+                match self.game.apply_move(army, selected_sq, square, None) {
+                    Ok(outcome) => {
+                        self.last_move = Some((army, selected_sq, square));
+                        self.move_history.push(format!("{}: {}->{}", 
+                            army.display_name(), 
+                            square_name(selected_sq), 
+                            square_name(square)));
+                        self.status_message = Some(outcome.to_string());
+                        self.error_message = None;
+                        self.selected_square = None;
+                        self.selected_army = Some(self.game.current_army());
+                        
+                        // Check if AI should move next
+                        self.try_ai_move();
+                        self.sync_game_over_screen();
+                        
+                        return true;
+                    }
+                    Err(err) => {
+                        // Restore state on error - we just pushed it
+                        if let Some(prev) = self.undo_stack.pop() {
+                            self.game = prev;
                         }
+                        self.error_message = Some(format!("{} (from {} to {})", 
+                            err, square_name(selected_sq), square_name(square)));
+                        self.selected_square = None;
+                        return false;
                     }
                 }
-            } else {
-                // First square - select piece
-                if let Some(army) = self.selected_army {
-                    if let Some((piece_army, kind)) = self.game.board.piece_at(square) {
-                        if piece_army == army {
-                            let legal_moves = self.game.generate_legal_moves(army);
-                            let can_move = legal_moves.iter().any(|m| m.from == square);
-                            if can_move {
-                                self.selected_square = Some(square);
-                                self.status_message = Some(format!("Selected {} {} at {}", 
-                                    army.display_name(), kind.name(), square_name(square)));
-                                return true;
-                            } else {
-                                self.error_message = Some(format!("{} at {} has no legal moves", 
-                                    kind.name(), square_name(square)));
-                                return false;
-                            }
+            }
+        } else {
+            // First square - select piece
+            self.inspected_throne = None;
+            if let Some(army) = self.selected_army {
+                if let Some((piece_army, kind)) = self.game.board.piece_at(square) {
+                    if piece_army == army {
+                        let legal_moves = self.game.generate_legal_moves(army);
+                        let can_move = legal_moves.iter().any(|m| m.from == square);
+                        if can_move {
+                            self.selected_square = Some(square);
+                            self.status_message = Some(format!("Selected {} {} at {}", 
+                                army.display_name(), kind.name(), square_name(square)));
+                            return true;
                         } else {
-                            self.error_message = Some(format!("That's {}'s piece", piece_army.display_name()));
+                            self.error_message = Some(format!("{} at {} has no legal moves", 
+                                kind.name(), square_name(square)));
                             return false;
                         }
                     } else {
-                        self.error_message = Some(format!("No piece at {}", square_name(square)));
+                        self.error_message = Some(format!("That's {}'s piece", piece_army.display_name()));
                         return false;
                     }
+                } else if let Some(owner) = self.game.board.throne_owner(square) {
+                    self.inspected_throne = Some(square);
+                    self.status_message = Some(format!("Empty {} throne at {}", 
+                        owner.display_name(), square_name(square)));
+                    return false;
+                } else {
+                    self.error_message = Some(format!("No piece at {}", square_name(square)));
+                    return false;
                 }
             }
         }
         false
     }
 
+    /// Key for the board drawn with squares `square_size` rows high.
+    pub fn board_render_key(&self, square_size: u16) -> BoardRenderKey {
+        BoardRenderKey {
+            position: self.game.position_hash(),
+            thrones: self.game.board.armies.map(|army| army.throne_squares),
+            en_passant: self.game.state.en_passant,
+            forced_king: self.game.config.force_king_move_in_check,
+            selected_army: self.selected_army,
+            selected_square: self.selected_square,
+            cursor: self.cursor,
+            square_size,
+            heatmap: self.heatmap,
+            coverage: self.coverage,
+            theme: self.theme,
+            colorblind_mode: self.colorblind_mode,
+        }
+    }
+
+    /// Text of the last captured frame, if any was.
+    pub fn last_frame(&self) -> Option<&str> {
+        self.last_frame.as_deref()
+    }
+
+    /// Writes the frame captured for a pending `/screenshot`. Call after
+    /// each draw; returns whether a screenshot was pending.
+    pub fn save_pending_screenshot(&mut self) -> bool {
+        let Some(filename) = self.pending_screenshot.take() else {
+            return false;
+        };
+        let Some(frame) = &self.last_frame else {
+            self.error_message = Some("No frame captured yet".into());
+            return true;
+        };
+        match fs::write(&filename, frame) {
+            Ok(_) => {
+                self.status_message = Some(format!("Screenshot saved to {}", filename));
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to save screenshot: {}", e));
+            }
+        }
+        true
+    }
+
+    /// What the status panel says about the selection: how the selected
+    /// piece moves and captures, or how an inspected empty throne works.
+    pub fn selection_info(&self) -> Option<String> {
+        if let Some(square) = self.selected_square {
+            let (_, kind) = self.game.board.piece_at(square)?;
+            return Some(format!("{}: {}", kind.name(), kind.rules_summary()));
+        }
+        let owner = self.game.board.throne_owner(self.inspected_throne?)?;
+        Some(format!(
+            "{} throne: an allied king here takes control of {} and revives it if frozen; \
+             an enemy king here blocks its prisoner exchange",
+            owner.display_name(),
+            owner.display_name()
+        ))
+    }
+
     pub fn submit_command(&mut self) {
         let trimmed = self.input.trim();
         if trimmed.is_empty() {
@@ -250,13 +481,60 @@ impl App {
         
         // Check if AI should move
         self.try_ai_move();
+        self.sync_game_over_screen();
+    }
+    
+    /// True once a team has won or the game is drawn; no further moves are
+    /// accepted.
+    pub fn is_game_over(&self) -> bool {
+        self.game.status != Status::Ongoing || self.game.draw_condition()
+    }
+    
+    /// Switches to the game-over screen when the game has ended, and back to
+    /// the board when it no longer has (after an undo, restart or load).
+    pub fn sync_game_over_screen(&mut self) {
+        let over = self.is_game_over();
+        match self.current_screen {
+            CurrentScreen::Main if over => {
+                self.current_screen = CurrentScreen::GameOver;
+                self.game_over_scroll = 0;
+                self.selected_square = None;
+            }
+            CurrentScreen::GameOver if !over => {
+                self.current_screen = CurrentScreen::Main;
+            }
+            _ => {}
+        }
+    }
+    
+    pub fn scroll_game_over(&mut self, delta: isize) {
+        let max_scroll = self.move_history.len().saturating_sub(1);
+        if delta > 0 {
+            self.game_over_scroll = self.game_over_scroll.saturating_add(delta as usize).min(max_scroll);
+        } else {
+            self.game_over_scroll = self.game_over_scroll.saturating_sub((-delta) as usize);
+        }
+    }
+    
+    /// Starts a fresh game from the current array.
+    pub fn new_game(&mut self) {
+        self.execute_command(UiCommand::Restart);
+        self.sync_game_over_screen();
     }
     
     pub fn try_ai_move(&mut self) {
+        let before = self.game.current_army();
+        if self.is_game_over() || !self.game.skip_stuck_armies() {
+            self.sync_game_over_screen();
+            return;
+        }
         let current = self.game.current_army();
+        if current != before {
+            self.selected_army = Some(current);
+        }
         if self.ai_armies.contains(&current) {
             if let Some(mv) = ai::capture_preferring_move(&mut self.game, current) {
-                let _ = self.game.apply_move(current, mv.from, mv.to, None);
+                let _ = self.game.apply_move(current, mv.from, mv.to, mv.promotion);
                 self.last_move = Some((current, mv.from, mv.to));
                 self.move_history.push(format!("{}: {}->{} (AI)", 
                     current.display_name(),
@@ -272,20 +550,31 @@ impl App {
 
     fn execute_command(&mut self, command: UiCommand) {
         match command {
+            UiCommand::Move { .. } if self.is_game_over() => {
+                self.status_message = None;
+                self.error_message = Some("The game is over - press n for a new game".to_string());
+            }
             UiCommand::Move {
                 army,
                 from,
                 to,
                 promotion,
-            } => match self.game.apply_move(army, from, to, promotion) {
-                Ok(msg) => {
-                    self.status_message = Some(msg);
-                    self.error_message = None;
-                }
-                Err(err) => {
-                    self.error_message = Some(err);
+            } => {
+                let (from, to) = if self.relative_coords {
+                    (army.relative_to_absolute(from), army.relative_to_absolute(to))
+                } else {
+                    (from, to)
+                };
+                match self.game.apply_move(army, from, to, promotion) {
+                    Ok(outcome) => {
+                        self.status_message = Some(outcome.to_string());
+                        self.error_message = None;
+                    }
+                    Err(err) => {
+                        self.error_message = Some(err.to_string());
+                    }
                 }
-            },
+            }
             UiCommand::ArraysList => {
                 let names: Vec<&str> = available_arrays().iter().map(|spec| spec.name).collect();
                 self.status_message = Some(format!("Arrays: {}", names.join(", ")));
@@ -332,7 +621,7 @@ impl App {
                     self.error_message = None;
                 } else {
                     self.error_message =
-                        Some("Exchange failed: both kings must be captured and frozen".into());
+                        Some("Exchange failed: both kings must be captured and neither throne held by an enemy king".into());
                 }
             }
             UiCommand::Save(filename) => match self.game.to_json() {
@@ -352,6 +641,14 @@ impl App {
             UiCommand::Load(filename) => match fs::read_to_string(&filename) {
                 Ok(json) => match Game::from_json(&json) {
                     Ok(game) => {
+                        // Keep the current selection if the saved array is unknown.
+                        let saved_array = game
+                            .array_name()
+                            .and_then(|name| available_arrays().iter().position(|s| s.name == name));
+                        if let Some(index) = saved_array {
+                            self.array_index = index;
+                            self.selected_array = available_arrays()[index].name.to_string();
+                        }
                         self.game = game;
                         self.status_message = Some(format!("Game loaded from {}", filename));
                         self.error_message = None;
@@ -382,49 +679,65 @@ impl App {
                 let roll = Game::roll_die();
                 let kinds = Game::die_to_piece_kind(roll);
                 let kind_names: Vec<&str> = kinds.iter().map(|k| k.name()).collect();
+                let rolled = format!("🎲 Rolled {}: {}", roll, kind_names.join(" or "));
                 let army = self.game.current_army();
-                let moves = self.game.generate_divination_moves(army, roll);
+                let before = self.game.clone();
                 
-                if moves.is_empty() {
-                    self.status_message = Some(format!(
-                        "🎲 Rolled {}: {} - No moves available (recorded as 'No Move')",
-                        roll,
-                        kind_names.join(" or ")
-                    ));
-                } else {
-                    self.status_message = Some(format!(
-                        "🎲 Rolled {}: {} - {} legal moves available",
-                        roll,
-                        kind_names.join(" or "),
-                        moves.len()
-                    ));
+                match self.game.apply_divination_roll(army, roll) {
+                    Ok(DivinationResult::Moved(mv)) => {
+                        self.undo_stack.push(before);
+                        self.redo_stack.clear();
+                        self.last_move = Some((army, mv.from, mv.to));
+                        self.move_history.push(format!("{}: {}->{}",
+                            army.display_name(),
+                            square_name(mv.from),
+                            square_name(mv.to)));
+                        self.status_message = Some(format!(
+                            "{} - only move: {} {}->{}",
+                            rolled,
+                            mv.kind.name(),
+                            square_name(mv.from),
+                            square_name(mv.to)
+                        ));
+                    }
+                    Ok(DivinationResult::Choose(moves)) => {
+                        self.status_message = Some(format!(
+                            "{} - {} legal moves available",
+                            rolled,
+                            moves.len()
+                        ));
+                    }
+                    Ok(result) => {
+                        self.undo_stack.push(before);
+                        self.redo_stack.clear();
+                        self.move_history.push(format!("{}: pass", army.display_name()));
+                        let outcome = if result == DivinationResult::Drawn {
+                            "draw: no army can move"
+                        } else {
+                            "recorded as 'No Move'"
+                        };
+                        self.status_message = Some(format!(
+                            "{} - No moves available ({})",
+                            rolled, outcome
+                        ));
+                    }
+                    Err(err) => {
+                        self.error_message = Some(err);
+                        return;
+                    }
                 }
+                self.selected_square = None;
+                self.selected_army = Some(self.game.current_army());
                 self.error_message = None;
+                self.try_ai_move();
+                self.sync_game_over_screen();
             }
             UiCommand::Screenshot(filename) => {
-                if let Some(ref frame) = self.last_frame {
-                    match fs::write(&filename, frame) {
-                        Ok(_) => {
-                            self.status_message = Some(format!("Screenshot saved to {}", filename));
-                            self.error_message = None;
-                        }
-                        Err(e) => {
-                            self.error_message = Some(format!("Failed to save screenshot: {}", e));
-                        }
-                    }
-                } else {
-                    self.error_message = Some("No frame captured yet".into());
-                }
+                self.pending_screenshot = Some(filename);
             }
             UiCommand::Restart => {
-                let arrays = available_arrays();
-                if arrays.is_empty() {
-                    self.error_message = Some("No arrays available".to_string());
-                    return;
-                }
-                let spec = find_array_by_name(&self.selected_array)
-                    .unwrap_or_else(|| arrays.first().unwrap());
-                self.game = Game::from_array_spec(spec);
+                self.game.reset_to_start();
+                self.reset_clock();
                 self.move_history.clear();
                 self.undo_stack.clear();
                 self.redo_stack.clear();
@@ -447,6 +760,44 @@ impl App {
                 self.status_message = Some(format!("Colorblind mode {}", mode));
                 self.error_message = None;
             }
+            UiCommand::Rules => {
+                let forced = if self.game.config.force_king_move_in_check { "on" } else { "off" };
+                self.status_message = Some(format!("Rules: forced-king {} (/rules forced-king to toggle)", forced));
+                self.error_message = None;
+            }
+            UiCommand::ToggleForcedKing => {
+                let forced = !self.game.config.force_king_move_in_check;
+                self.game.set_force_king_move_in_check(forced);
+                let mode = if forced { "enabled" } else { "disabled" };
+                self.status_message = Some(format!("Forced king move in check {}", mode));
+                self.error_message = None;
+            }
+            UiCommand::Timeline => {
+                self.status_message = Some(format!("Next: {}", self.game.timeline_text(TIMELINE_MOVERS)));
+                self.error_message = None;
+            }
+            UiCommand::ToggleRelativeCoords => {
+                self.relative_coords = !self.relative_coords;
+                let mode = if self.relative_coords {
+                    "on: each army types squares from its own side, rank 1 at its back"
+                } else {
+                    "off"
+                };
+                self.status_message = Some(format!("Relative coordinates {}", mode));
+                self.error_message = None;
+            }
+            UiCommand::SetTheme(theme) => {
+                self.theme = theme;
+                self.status_message = Some(format!("Theme set to {}", theme.name));
+                self.error_message = None;
+            }
+            UiCommand::Annotate(ply, symbol) => match self.game.annotate(ply, &symbol) {
+                Ok(()) => {
+                    self.status_message = Some(format!("Annotated ply {} with {}", ply, symbol));
+                    self.error_message = None;
+                }
+                Err(err) => self.error_message = Some(err),
+            },
             UiCommand::ToggleAI(army) => {
                 if self.ai_armies.contains(&army) {
                     self.ai_armies.retain(|&a| a != army);
@@ -457,6 +808,28 @@ impl App {
                 }
                 self.error_message = None;
             }
+            UiCommand::ToggleHeatmap(team) => {
+                if self.heatmap == Some(team) {
+                    self.heatmap = None;
+                    self.status_message = Some("Heatmap hidden".to_string());
+                } else {
+                    self.heatmap = Some(team);
+                    self.status_message = Some(format!("Showing {} coverage", team.name()));
+                }
+                self.error_message = None;
+            }
+            UiCommand::ToggleCoverage => {
+                self.coverage = !self.coverage;
+                self.status_message = Some(if self.coverage {
+                    "Shading the squares the army to move covers".to_string()
+                } else {
+                    "Coverage hidden".to_string()
+                });
+            }
+            UiCommand::ToggleClock => {
+                self.show_clock = !self.show_clock;
+                self.status_message = Some(format!("Clock {}", if self.show_clock { "shown" } else { "hidden" }));
+            }
         }
         if self.status_message.is_some() {
             self.error_message = None;
@@ -487,7 +860,7 @@ impl App {
         if !stalemated.is_empty() {
             parts.push(format!("Stalemated: {}", stalemated.join(", ")));
         }
-        if let Some(team) = self.game.winning_team() {
+        if let Status::Won(team) = self.game.status {
             parts.push(format!("Winner: {} team", team.name()));
         } else if self.game.draw_condition() {
             parts.push("Draw condition met".into());
@@ -506,10 +879,12 @@ impl App {
     fn load_array(&mut self, index: usize) {
         if let Some(spec) = available_arrays().get(index) {
             self.game = Game::from_array_spec(spec);
+            self.reset_clock();
             self.array_index = index;
             self.selected_array = spec.name.to_string();
             self.status_message = Some(format!("Loaded array: {}", spec.name));
             self.error_message = None;
+            self.sync_game_over_screen();
         }
     }
 
@@ -530,6 +905,27 @@ impl App {
         self.cycle_array(direction);
     }
 
+    /// Keeps view state consistent after the terminal is resized to
+    /// `height` rows: the help scroll offset is clamped to the new viewport
+    /// and a selection that no longer holds one of the selected army's pieces
+    /// is dropped.
+    pub fn handle_resize(&mut self, height: u16) {
+        let visible = height.saturating_sub(HELP_CHROME_HEIGHT) as usize;
+        let max_scroll = Self::get_help_text().len().saturating_sub(visible);
+        self.help_scroll = self.help_scroll.min(max_scroll);
+
+        if let Some(square) = self.selected_square {
+            let still_valid = square < 64
+                && matches!(
+                    (self.game.board.piece_at(square), self.selected_army),
+                    (Some((piece_army, _)), Some(army)) if piece_army == army
+                );
+            if !still_valid {
+                self.selected_square = None;
+            }
+        }
+    }
+
     pub fn scroll_help(&mut self, delta: isize) {
         if delta > 0 {
             self.help_scroll = self.help_scroll.saturating_add(delta as usize);
@@ -584,14 +980,15 @@ impl App {
             "──────────".to_string(),
             "• Blue pawns promote on rank 8 (north)".to_string(),
             "• Red pawns promote on rank 1 (south)".to_string(),
-            "• Black pawns promote on file h (east)".to_string(),
-            "• Yellow pawns promote on file a (west)".to_string(),
+            "• Black pawns promote on rank 1 (south, as Red's do)".to_string(),
+            "• Yellow pawns promote on rank 8 (north, as Blue's do)".to_string(),
             "• Privileged pawn: with only K+Q+P or K+B+P, pawn can promote to any piece".to_string(),
             "".to_string(),
             "STALEMATE & DRAWS".to_string(),
             "─────────────────".to_string(),
             "• Stalemate: King not in check but no legal moves → skip turns".to_string(),
             "• Draw: Both allied kings bare, or four bare kings".to_string(),
+            "• Dead position: no team can ever capture a king → draw".to_string(),
             "".to_string(),
             "DIVINATION MODE".to_string(),
             "───────────────".to_string(),
@@ -610,17 +1007,26 @@ impl App {
             "• /array next - Cycle to next array".to_string(),
             "• /array prev - Cycle to previous array".to_string(),
             "• /status - Show game status".to_string(),
+            "• /timeline - Show the next eight movers, with skipped armies in brackets".to_string(),
             "• /divination - Toggle divination mode (dice-based play)".to_string(),
             "• /roll - Roll die for divination mode".to_string(),
             "• /exchange <army> - Exchange prisoners with army".to_string(),
             "• /save <file> - Save game to file".to_string(),
             "• /load <file> - Load game from file".to_string(),
             "• /screenshot <file> - Capture terminal state to text file".to_string(),
-            "• /restart - Start a new game".to_string(),
+            "• /new - Start a new game with the same rules".to_string(),
             "• /undo or Ctrl-U - Undo last move".to_string(),
             "• /redo or Ctrl-R - Redo move".to_string(),
             "• /colorblind - Toggle colorblind mode (adds symbols)".to_string(),
+            "• /theme <name> - Switch colors (classic, high-contrast, monochrome)".to_string(),
+            "• /annotate <ply> <symbol> - Tag a move for review (!!, !, !?, ?!, ?, ??)".to_string(),
             "• /ai <army> - Toggle AI for army (blue/red/black/yellow)".to_string(),
+            "• /rules - Show rule toggles; /rules forced-king - Toggle forcing the king to move in check".to_string(),
+            "• /heatmap <air|earth> - Toggle a count of each team's pieces covering each square".to_string(),
+            "• /coverage - Toggle shading of every square the army to move can reach or attack".to_string(),
+            "• /clock - Toggle the game clock in the header".to_string(),
+            "• /relcoords - Toggle typing squares from your own army's side (rank 1 = your back rank)".to_string(),
+            "• Arrow keys + Enter - Move the cursor and select/move without typing".to_string(),
             "• [ ] - Cycle arrays with bracket keys".to_string(),
             "• ? or F1 - Toggle this help screen".to_string(),
             "• ESC - Exit help or quit game".to_string(),
@@ -645,6 +1051,7 @@ fn parse_ui_command(input: &str) -> Result<UiCommand, CommandParseError> {
             match cmd.to_lowercase().as_str() {
                 "arrays" => Ok(UiCommand::ArraysList),
                 "status" => Ok(UiCommand::Status),
+                "timeline" => Ok(UiCommand::Timeline),
                 "divination" | "div" => Ok(UiCommand::ToggleDivination),
                 "roll" | "dice" => Ok(UiCommand::RollDie),
                 "array" => {
@@ -693,6 +1100,37 @@ fn parse_ui_command(input: &str) -> Result<UiCommand, CommandParseError> {
                 "undo" | "u" => Ok(UiCommand::Undo),
                 "redo" | "r" => Ok(UiCommand::Redo),
                 "colorblind" | "cb" => Ok(UiCommand::ToggleColorblind),
+                "relcoords" => Ok(UiCommand::ToggleRelativeCoords),
+                "theme" => {
+                    let themes = Theme::names().join(", ");
+                    if let Some(name) = parts.next() {
+                        match Theme::from_name(name) {
+                            Some(theme) => Ok(UiCommand::SetTheme(theme)),
+                            None => Err(CommandParseError(format!("Unknown theme (use {})", themes))),
+                        }
+                    } else {
+                        Err(CommandParseError(format!("Missing theme name ({})", themes)))
+                    }
+                }
+                "annotate" => match (parts.next(), parts.next()) {
+                    (Some(ply), Some(symbol)) => match ply.parse() {
+                        Ok(ply) => Ok(UiCommand::Annotate(ply, symbol.to_string())),
+                        Err(_) => Err(CommandParseError("Invalid ply number".into())),
+                    },
+                    _ => Err(CommandParseError("Usage: /annotate <ply> <symbol>".into())),
+                },
+                "rules" => match parts.next().map(str::to_lowercase).as_deref() {
+                    None => Ok(UiCommand::Rules),
+                    Some("forced-king") => Ok(UiCommand::ToggleForcedKing),
+                    Some(_) => Err(CommandParseError("Unknown rule (use forced-king)".into())),
+                },
+                "coverage" => Ok(UiCommand::ToggleCoverage),
+                "clock" => Ok(UiCommand::ToggleClock),
+                "heatmap" => match parts.next().map(Team::from_name) {
+                    Some(Some(team)) => Ok(UiCommand::ToggleHeatmap(team)),
+                    Some(None) => Err(CommandParseError("Unknown team (use air or earth)".into())),
+                    None => Err(CommandParseError("Usage: /heatmap <air|earth>".into())),
+                },
                 "ai" => {
                     if let Some(name) = parts.next() {
                         match Army::from_str(name) {
@@ -714,51 +1152,13 @@ fn parse_ui_command(input: &str) -> Result<UiCommand, CommandParseError> {
 }
 
 fn parse_move_command(input: &str) -> Result<UiCommand, CommandParseError> {
-    let parts: Vec<&str> = input.split(':').collect();
-    if parts.len() != 2 {
-        return Err(CommandParseError(
-            "Move must follow format `army: e2-e4`".into(),
-        ));
-    }
-    let army_name = parts[0].trim();
-    let army = Army::from_str(army_name).ok_or_else(|| CommandParseError("Unknown army".into()))?;
-    let move_part = parts[1].trim();
-    let promo_split: Vec<&str> = move_part.split('=').collect();
-    let (move_segment, promotion) = if promo_split.len() == 2 {
-        (promo_split[0], Some(promo_split[1]))
-    } else {
-        (move_part, None)
-    };
-    let move_segment = move_segment.replace('x', "-");
-    let coords: Vec<&str> = move_segment.split('-').collect();
-    if coords.len() != 2 {
-        return Err(CommandParseError(
-            "Move must contain source and destination".into(),
-        ));
-    }
-    let from = parse_square(coords[0].trim())
-        .ok_or_else(|| CommandParseError("Invalid source square".into()))?;
-    let to = parse_square(coords[1].trim())
-        .ok_or_else(|| CommandParseError("Invalid destination square".into()))?;
-    let promotion_kind = promotion
-        .map(|code| match code.to_uppercase().as_str() {
-            "Q" => Some(PieceKind::Queen),
-            "R" => Some(PieceKind::Rook),
-            "B" => Some(PieceKind::Bishop),
-            "N" => Some(PieceKind::Knight),
-            _ => None,
-        })
-        .flatten();
-
-    if promotion.is_some() && promotion_kind.is_none() {
-        return Err(CommandParseError("Invalid promotion piece".into()));
-    }
-
+    let (army, from, to, promotion) =
+        parse_army_move(input).map_err(|e| CommandParseError(e.to_string()))?;
     Ok(UiCommand::Move {
         army,
         from,
         to,
-        promotion: promotion_kind,
+        promotion,
     })
 }
 
@@ -777,8 +1177,3 @@ fn parse_square(token: &str) -> Option<Square> {
     Some(rank as Square * 8 + file as Square)
 }
 
-fn square_name(square: Square) -> String {
-    let file = (b'a' + (square % 8)) as char;
-    let rank = (b'1' + (square / 8)) as char;
-    format!("{}{}", file, rank)
-}
@@ -0,0 +1,118 @@
+use crate::engine::types::Army;
+use ratatui::style::Color;
+
+/// Colors used to draw the board and pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub light_square: Color,
+    pub dark_square: Color,
+    pub throne: Color,
+    pub throne_marker: Color,
+    pub empty_marker: Color,
+    pub selection: Color,
+    pub cursor: Color,
+    pub legal_move: Color,
+    /// Background of squares under the `/coverage` overlay.
+    pub coverage: Color,
+    /// Piece colors on light, selected, cursor and legal-move squares, by
+    /// army index.
+    pub army_on_light: [Color; 4],
+    /// Piece colors on dark squares and in text panels, by army index.
+    pub army_on_dark: [Color; 4],
+}
+
+impl Theme {
+    pub const CLASSIC: Theme = Theme {
+        name: "classic",
+        light_square: Color::Rgb(240, 217, 181),  // Wheat
+        dark_square: Color::Rgb(181, 136, 99),    // Brown
+        throne: Color::Rgb(200, 150, 100),        // Golden throne
+        throne_marker: Color::Rgb(139, 90, 43),
+        empty_marker: Color::Rgb(120, 120, 120),
+        selection: Color::Rgb(255, 255, 100),     // Bright yellow
+        cursor: Color::Rgb(135, 206, 250),        // Sky blue
+        legal_move: Color::Rgb(144, 238, 144),    // Light green
+        coverage: Color::Rgb(196, 186, 150),      // Dimmed wheat
+        army_on_light: [
+            Color::Rgb(0, 0, 200),      // Dark blue
+            Color::Rgb(40, 40, 40),     // Dark gray
+            Color::Rgb(200, 0, 0),      // Dark red
+            Color::Rgb(180, 140, 0),    // Dark yellow/gold
+        ],
+        army_on_dark: [
+            Color::Rgb(100, 150, 255),  // Brighter blue
+            Color::Rgb(220, 220, 220),  // Light gray (not pure white)
+            Color::Rgb(255, 100, 100),  // Brighter red
+            Color::Rgb(255, 220, 100),  // Brighter yellow
+        ],
+    };
+
+    /// Saturated pieces on near-black and near-white squares.
+    pub const HIGH_CONTRAST: Theme = Theme {
+        name: "high-contrast",
+        light_square: Color::Rgb(255, 255, 255),
+        dark_square: Color::Rgb(0, 0, 0),
+        throne: Color::Rgb(255, 0, 255),
+        throne_marker: Color::Rgb(0, 0, 0),
+        empty_marker: Color::Rgb(128, 128, 128),
+        selection: Color::Rgb(0, 255, 255),
+        cursor: Color::Rgb(255, 165, 0),
+        legal_move: Color::Rgb(0, 255, 0),
+        coverage: Color::Rgb(120, 120, 180),
+        army_on_light: [
+            Color::Rgb(0, 0, 255),
+            Color::Rgb(0, 0, 0),
+            Color::Rgb(255, 0, 0),
+            Color::Rgb(160, 96, 0),
+        ],
+        army_on_dark: [
+            Color::Rgb(80, 160, 255),
+            Color::Rgb(255, 255, 255),
+            Color::Rgb(255, 64, 64),
+            Color::Rgb(255, 255, 0),
+        ],
+    };
+
+    /// Grayscale only; armies are told apart by letter case and symbols.
+    pub const MONOCHROME: Theme = Theme {
+        name: "monochrome",
+        light_square: Color::Rgb(200, 200, 200),
+        dark_square: Color::Rgb(90, 90, 90),
+        throne: Color::Rgb(150, 150, 150),
+        throne_marker: Color::Rgb(60, 60, 60),
+        empty_marker: Color::Rgb(120, 120, 120),
+        selection: Color::Rgb(255, 255, 255),
+        cursor: Color::Rgb(225, 225, 225),
+        legal_move: Color::Rgb(170, 170, 170),
+        coverage: Color::Rgb(150, 150, 150),
+        army_on_light: [Color::Rgb(0, 0, 0); 4],
+        army_on_dark: [Color::Rgb(255, 255, 255); 4],
+    };
+
+    pub const ALL: [Theme; 3] = [Theme::CLASSIC, Theme::HIGH_CONTRAST, Theme::MONOCHROME];
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        let name = name.to_lowercase().replace(['_', ' '], "-");
+        let name = if name == "highcontrast" { "high-contrast" } else { name.as_str() };
+        Theme::ALL.into_iter().find(|theme| theme.name == name)
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        Theme::ALL.iter().map(|theme| theme.name).collect()
+    }
+
+    pub fn army_color(&self, army: Army) -> Color {
+        self.army_on_dark[army.index()]
+    }
+
+    pub fn army_color_on_light(&self, army: Army) -> Color {
+        self.army_on_light[army.index()]
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::CLASSIC
+    }
+}
@@ -1,28 +1,67 @@
+use crate::engine::ai::piece_value;
 use crate::engine::arrays::available_arrays;
-use crate::engine::types::{Army, PieceKind, PlayerId, Team};
-use crate::ui::app::{App, CurrentScreen};
+use crate::engine::game::Status;
+use crate::engine::types::{file_char, rank_char, Army, PieceKind, PlayerId, Team};
+use crate::ui::app::{App, CurrentScreen, HELP_CHROME_HEIGHT};
+use crate::ui::theme::Theme;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use ratatui::Frame;
+use std::time::Duration;
 
 const BG_COLOR: Color = Color::Black;
 
+/// Smallest terminal the TUI draws in; below this it shows a size error.
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 20;
+/// Below either of these the compact layout is used: single-square board,
+/// one-line header, status and command line.
+pub const COMPACT_WIDTH: u16 = 80;
+pub const COMPACT_HEIGHT: u16 = 24;
+/// From this height the header and command line get full borders.
+const TALL_HEIGHT: u16 = 30;
+/// From this width the header lists every shortcut.
+const WIDE_HEADER_WIDTH: u16 = 100;
+/// Width of the status panel shown beside the board when it fits.
+const SIDE_PANEL_WIDTH: u16 = 35;
+
 pub fn render(frame: &mut Frame, app: &mut App) {
-    // Capture frame for screenshots
-    let size = frame.area();
+    match app.current_screen {
+        CurrentScreen::Help => render_help(frame, app),
+        CurrentScreen::GameOver => {
+            render_main(frame, app);
+            render_game_over(frame, app);
+        }
+        _ => render_main(frame, app),
+    }
+    
+    // Only frames asked for with /screenshot or --capture are captured as
+    // text.
+    if app.capture_frames || app.pending_screenshot.is_some() {
+        app.last_frame = Some(frame_capture(app, frame.area()));
+    }
+}
+
+/// Plain-text record of the screen for `/screenshot`.
+fn frame_capture(app: &App, size: Rect) -> String {
     let mut capture = format!("Terminal: {}x{}\n", size.width, size.height);
     capture.push_str("═".repeat(size.width as usize).as_str());
     capture.push('\n');
     
     match app.current_screen {
         CurrentScreen::Help => {
-            render_help(frame, app);
             capture.push_str("Help Screen\n");
         }
+        CurrentScreen::GameOver => {
+            capture.push_str("Game Over Screen\n");
+            for line in game_over_lines(app) {
+                capture.push_str(&line.to_string());
+                capture.push('\n');
+            }
+        }
         _ => {
-            render_main(frame, app);
             // Capture board state
             capture.push_str(&format!("Turn: {}\n", app.game.current_army().display_name()));
             capture.push_str(&format!("Array: {}\n", app.selected_array));
@@ -36,8 +75,7 @@ pub fn render(frame: &mut Frame, app: &mut App) {
             }
         }
     }
-    
-    app.last_frame = Some(capture);
+    capture
 }
 
 fn render_help(frame: &mut Frame, app: &App) {
@@ -47,7 +85,7 @@ fn render_help(frame: &mut Frame, app: &App) {
     let visible_lines: Vec<Line> = help_lines
         .iter()
         .skip(app.help_scroll)
-        .take(size.height.saturating_sub(4) as usize)
+        .take(size.height.saturating_sub(HELP_CHROME_HEIGHT) as usize)
         .map(|s| Line::from(Span::styled(s.as_str(), Style::default().fg(Color::White).bg(BG_COLOR))))
         .collect();
     
@@ -64,8 +102,95 @@ fn render_help(frame: &mut Frame, app: &App) {
     frame.render_widget(help_text, size);
 }
 
+/// Result popup drawn over the final position.
+fn render_game_over(frame: &mut Frame, app: &App) {
+    let size = frame.area();
+    let width = size.width.min(60);
+    let height = size.height.min(22);
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+
+    let popup = Paragraph::new(game_over_lines(app))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Game Over")
+                .style(Style::default().fg(Color::Cyan).bg(BG_COLOR)),
+        )
+        .style(Style::default().bg(BG_COLOR))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+fn game_over_lines(app: &App) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    let result = match app.game.status {
+        Status::Won(team) => format!("🏆 {} TEAM WINS!", team.name().to_uppercase()),
+        _ => "⚖ DRAW".to_string(),
+    };
+    lines.push(Line::from(Span::styled(
+        result,
+        Style::default()
+            .fg(Color::Green)
+            .bg(BG_COLOR)
+            .add_modifier(Modifier::BOLD),
+    )));
+    lines.push(Line::from(""));
+
+    lines.push(Line::from(Span::styled(
+        "─── Final material ───",
+        Style::default().fg(Color::DarkGray).bg(BG_COLOR),
+    )));
+    for &army in Army::ALL.iter() {
+        let counts = app.game.piece_counts(army);
+        let material: i32 = PieceKind::ALL
+            .iter()
+            .filter(|&&kind| kind != PieceKind::King)
+            .map(|&kind| counts[kind.index()] as i32 * piece_value(kind))
+            .sum();
+        let pieces: u32 = counts.iter().sum();
+        let state = if app.game.army_is_frozen(army) { " (frozen)" } else { "" };
+        lines.push(Line::from(Span::styled(
+            format!("{}: {} pieces, {} points{}", army.display_name(), pieces, material, state),
+            Style::default().fg(army_color(&app.theme, army)).bg(BG_COLOR),
+        )));
+    }
+    lines.push(Line::from(""));
+
+    if !app.move_history.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("─── Moves ({}) ───", app.move_history.len()),
+            Style::default().fg(Color::DarkGray).bg(BG_COLOR),
+        )));
+        for (i, mv) in app.move_history.iter().enumerate().skip(app.game_over_scroll).take(6) {
+            lines.push(Line::from(Span::styled(
+                format!("{}. {}", i + 1, mv),
+                Style::default().fg(Color::Rgb(180, 180, 180)).bg(BG_COLOR),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
+    lines.push(Line::from(Span::styled(
+        "n: new game  ↑/↓: review moves  Ctrl-U: undo  q: quit",
+        Style::default().fg(Color::Yellow).bg(BG_COLOR),
+    )));
+    lines
+}
+
 fn render_main(frame: &mut Frame, app: &mut App) {
     let size = frame.area();
+    if size.width < COMPACT_WIDTH || size.height < COMPACT_HEIGHT {
+        render_compact(frame, app);
+        return;
+    }
     
     // Calculate optimal board size (8x8 board + borders + labels)
     // Each square needs: width chars × height lines
@@ -83,10 +208,10 @@ fn render_main(frame: &mut Frame, app: &mut App) {
     let board_height = 1 + square_size * 8 + 1 + 2; // turn + squares + labels + borders
     
     // Determine if we can fit info panel beside board
-    let info_width = 35;
+    let info_width = SIDE_PANEL_WIDTH;
     let can_fit_side_panel = size.width >= board_width + info_width + 2;
     
-    let (header_height, input_height) = if size.height < 30 { (1, 1) } else { (3, 3) };
+    let (header_height, input_height) = if size.height < TALL_HEIGHT { (1, 1) } else { (3, 3) };
     
     let layout = Layout::default()
         .direction(Direction::Vertical)
@@ -98,7 +223,7 @@ fn render_main(frame: &mut Frame, app: &mut App) {
         ])
         .split(size);
 
-    let header_text = if size.width < 100 {
+    let header_text = if size.width < WIDE_HEADER_WIDTH {
         "Enochian Chess | 1-4: Army | Ctrl-U: Undo | ? Help"
     } else {
         "Enochian Chess | 1-4: Select Army | Type square (e2) to select/move | Ctrl-U: Undo | Ctrl-R: Redo | ? Help"
@@ -113,7 +238,7 @@ fn render_main(frame: &mut Frame, app: &mut App) {
     ))
     .block(Block::default()
         .borders(Borders::ALL)
-        .title("Enochian Chess")
+        .title(header_title(app))
         .style(Style::default().bg(BG_COLOR)));
     frame.render_widget(header, layout[0]);
 
@@ -136,7 +261,7 @@ fn render_main(frame: &mut Frame, app: &mut App) {
             .split(layout[2])
     };
 
-    let board = Paragraph::new(text_from_board_scaled(app, Some(square_size)))
+    let board = Paragraph::new(cached_board_text(app, square_size))
         .block(Block::default()
             .title("Enochian Board")
             .borders(Borders::ALL)
@@ -191,6 +316,77 @@ fn render_main(frame: &mut Frame, app: &mut App) {
     frame.render_widget(input_line, layout[3]);
 }
 
+/// Layout for terminals under `COMPACT_WIDTH`x`COMPACT_HEIGHT`: a 1x1 board
+/// between a one-line header and army bar above and a one-line status and
+/// command line below.
+fn render_compact(frame: &mut Frame, app: &mut App) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(12),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    let header = Paragraph::new(Span::styled(
+        "Enochian Chess | 1-4: Army | ? Help",
+        Style::default()
+            .fg(Color::Yellow)
+            .bg(BG_COLOR)
+            .add_modifier(Modifier::BOLD),
+    ))
+    .style(Style::default().bg(BG_COLOR));
+    frame.render_widget(header, layout[0]);
+    frame.render_widget(build_army_selector(app), layout[1]);
+
+    let board_area = Rect {
+        width: layout[2].width.min(2 + 3 * 8 + 2),
+        ..layout[2]
+    };
+    let board = Paragraph::new(cached_board_text(app, 1))
+        .block(Block::default()
+            .title("Board")
+            .borders(Borders::ALL)
+            .style(Style::default().bg(BG_COLOR)))
+        .style(Style::default().bg(BG_COLOR));
+    frame.render_widget(board, board_area);
+
+    frame.render_widget(compact_status_line(app), layout[3]);
+
+    let input_line = Paragraph::new(Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Green).bg(BG_COLOR)),
+        Span::styled(app.input.clone(), Style::default().fg(Color::White).bg(BG_COLOR)),
+    ]))
+    .style(Style::default().bg(BG_COLOR));
+    frame.render_widget(input_line, layout[4]);
+}
+
+/// The turn, check flag and latest message on one line.
+fn compact_status_line(app: &App) -> Paragraph<'static> {
+    let current_army = app.game.current_army();
+    let in_check = app.game.king_in_check(current_army);
+    let mut spans = vec![Span::styled(
+        format!(
+            "{}'S TURN{}",
+            current_army.display_name().to_uppercase(),
+            if in_check { " ⚠ CHECK" } else { "" }
+        ),
+        Style::default()
+            .fg(if in_check { Color::Red } else { army_color(&app.theme, current_army) })
+            .bg(BG_COLOR)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if let Some(err) = &app.error_message {
+        spans.push(Span::styled(format!(" | {}", err), Style::default().fg(Color::Red).bg(BG_COLOR)));
+    } else if let Some(msg) = &app.status_message {
+        spans.push(Span::styled(format!(" | {}", msg), Style::default().fg(Color::Green).bg(BG_COLOR)));
+    }
+    Paragraph::new(Line::from(spans)).style(Style::default().bg(BG_COLOR))
+}
+
 pub fn render_size_error(frame: &mut Frame, min_width: u16, min_height: u16, size: Rect) {
     let warning = Paragraph::new(Text::from(vec![Line::from(vec![Span::styled(
         format!(
@@ -218,16 +414,34 @@ fn build_status_lines(app: &App) -> Text {
     lines.push(Line::from(vec![Span::styled(
         format!("▶▶▶ {}'S TURN ◀◀◀{}", current_army.display_name().to_uppercase(), check_indicator),
         Style::default()
-            .fg(if in_check { Color::Red } else { army_color(current_army) })
+            .fg(if in_check { Color::Red } else { army_color(&app.theme, current_army) })
             .bg(BG_COLOR)
             .add_modifier(Modifier::BOLD),
     )]));
 
+    let upcoming: Vec<&str> = app
+        .game
+        .armies_in_turn_order()
+        .iter()
+        .map(|army| army.display_name())
+        .collect();
+    lines.push(Line::from(Span::styled(
+        format!("{} to move | Order: {}", app.game.current_team().name(), upcoming.join(" → ")),
+        Style::default().fg(Color::Rgb(150, 150, 150)).bg(BG_COLOR),
+    )));
+
     lines.push(Line::from(Span::styled(
         format!("Array: {}", app.selected_array),
         Style::default().fg(Color::Rgb(150, 150, 150)).bg(BG_COLOR),
     )));
 
+    if let Some(info) = app.selection_info() {
+        lines.push(Line::from(Span::styled(
+            format!("» {}", info),
+            Style::default().fg(Color::Rgb(200, 200, 150)).bg(BG_COLOR),
+        )));
+    }
+
     // Last move indicator
     if let Some((army, from, to)) = app.last_move {
         let from_file = (b'a' + (from % 8)) as char;
@@ -236,18 +450,21 @@ fn build_status_lines(app: &App) -> Text {
         let to_rank = (b'1' + (to / 8)) as char;
         lines.push(Line::from(Span::styled(
             format!("Last: {} {}{}→{}{}", army.display_name(), from_file, from_rank, to_file, to_rank),
-            Style::default().fg(army_color(army)).bg(BG_COLOR),
+            Style::default().fg(army_color(&app.theme, army)).bg(BG_COLOR),
         )));
     }
 
-    let frozen: Vec<&str> = Army::ALL
-        .iter()
-        .filter(|&&army| app.game.army_is_frozen(army))
-        .map(|army| army.display_name())
-        .collect();
-    if !frozen.is_empty() {
+    for (army, revivable, [first, second]) in app.game.revival_status() {
         lines.push(Line::from(Span::styled(
-            format!("❄ Frozen: {}", frozen.join(", ")),
+            format!(
+                "❄ Frozen: {} (throne {}{}/{}{}{})",
+                army.display_name(),
+                file_char(first),
+                rank_char(first),
+                file_char(second),
+                rank_char(second),
+                if revivable { ", revivable" } else { "" }
+            ),
             Style::default().fg(Color::Cyan).bg(BG_COLOR),
         )));
     }
@@ -264,7 +481,7 @@ fn build_status_lines(app: &App) -> Text {
         )));
     }
 
-    if let Some(team) = app.game.winning_team() {
+    if let Status::Won(team) = app.game.status {
         lines.push(Line::from(Span::styled(
             format!("🏆 {} TEAM WINS!", team.name().to_uppercase()),
             Style::default()
@@ -335,7 +552,7 @@ fn build_status_lines(app: &App) -> Text {
                         .join(" ");
                     lines.push(Line::from(Span::styled(
                         format!("{}: {}", army.display_name(), piece_str),
-                        Style::default().fg(army_color(army)).bg(BG_COLOR),
+                        Style::default().fg(army_color(&app.theme, army)).bg(BG_COLOR),
                     )));
                 }
             }
@@ -378,6 +595,26 @@ fn array_list_text(app: &App) -> Text {
     Text::from(lines)
 }
 
+/// "Enochian Chess", then the round and ply count and, unless `/clock`
+/// hid it, the game clock.
+fn header_title(app: &App) -> String {
+    let mut title = format!(
+        "Enochian Chess · Round {} · Ply {}",
+        app.round(),
+        app.game.move_history.len()
+    );
+    if app.show_clock {
+        title.push_str(&format!(" · {}", format_clock(app.game_time)));
+    }
+    title
+}
+
+/// `mm:ss`, with minutes running past 59.
+fn format_clock(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
 fn army_status_lines(app: &App) -> Vec<Line> {
     let mut lines = Vec::new();
     lines.push(Line::from(Span::styled(
@@ -400,6 +637,10 @@ fn army_status_lines(app: &App) -> Vec<Line> {
         
         let controller = controller_label(app.game.board.controller_for(army));
         status_parts.push(controller);
+        let think = format_clock(app.think_time[army.index()]);
+        if app.show_clock {
+            status_parts.push(&think);
+        }
         
         let style = match army {
             Army::Blue => Style::default().fg(Color::Blue).bg(BG_COLOR),
@@ -428,9 +669,25 @@ fn army_status_lines(app: &App) -> Vec<Line> {
     lines
 }
 
-fn text_from_board_scaled(app: &mut App, square_size: Option<u16>) -> Text {
+/// The board text, rebuilt only when `App::board_render_key` changes: most
+/// repaints (typing a command, a tick with nothing new) leave it as it was.
+fn cached_board_text(app: &mut App, square_size: u16) -> Text<'static> {
+    let key = app.board_render_key(square_size);
+    if let Some((cached, text)) = &app.board_cache {
+        if *cached == key {
+            return text.clone();
+        }
+    }
+    let text = text_from_board_scaled(app, Some(square_size));
+    app.board_cache = Some((key, text.clone()));
+    text
+}
+
+fn text_from_board_scaled(app: &mut App, square_size: Option<u16>) -> Text<'static> {
     let mut lines = Vec::new();
     let current_army = app.game.current_army();
+    let coverage = app.heatmap.map(|team| app.game.coverage_counts(team));
+    let shaded = if app.coverage { app.game.army_coverage(current_army) } else { 0 };
     
     let square_height = square_size.unwrap_or(1);
     let square_width = (square_height * 2 + 1) as usize; // Convert to usize for formatting
@@ -439,7 +696,7 @@ fn text_from_board_scaled(app: &mut App, square_size: Option<u16>) -> Text {
     lines.push(Line::from(Span::styled(
         format!("▶ {} to move", current_army.display_name()),
         Style::default()
-            .fg(army_color(current_army))
+            .fg(army_color(&app.theme, current_army))
             .bg(BG_COLOR)
             .add_modifier(Modifier::BOLD),
     )));
@@ -461,7 +718,9 @@ fn text_from_board_scaled(app: &mut App, square_size: Option<u16>) -> Text {
             
             for file in 0..8 {
                 let square = rank * 8 + file;
-                let (text, style) = board_square_info(app, square, current_army);
+                let heat = coverage.map(|counts| counts[square as usize]);
+                let covered = shaded & (1u64 << square) != 0;
+                let (text, style) = board_square_info(app, square, current_army, heat, covered);
                 
                 // Center piece character in the middle row
                 let content = if row == square_height / 2 {
@@ -487,22 +746,26 @@ fn text_from_board_scaled(app: &mut App, square_size: Option<u16>) -> Text {
     Text::from(lines)
 }
 
-fn army_color(army: Army) -> Color {
-    match army {
-        Army::Blue => Color::Rgb(100, 150, 255),    // Brighter blue
-        Army::Black => Color::Rgb(220, 220, 220),   // Light gray (not pure white)
-        Army::Red => Color::Rgb(255, 100, 100),     // Brighter red
-        Army::Yellow => Color::Rgb(255, 220, 100),  // Brighter yellow
-    }
+fn army_color(theme: &Theme, army: Army) -> Color {
+    theme.army_color(army)
 }
 
-fn board_square_info(app: &mut App, square: u8, current_army: Army) -> (String, Style) {
+/// `heat` is the square's coverage count while the heatmap overlay is on; it
+/// replaces the marker on empty squares. `covered` squares, under the
+/// `/coverage` overlay, only change background so pieces stay readable.
+fn board_square_info(
+    app: &mut App,
+    square: u8,
+    current_army: Army,
+    heat: Option<u8>,
+    covered: bool,
+) -> (String, Style) {
     // Checkerboard pattern - light and dark squares
     let is_light_square = (square / 8 + square % 8) % 2 == 0;
-    let light_square = Color::Rgb(240, 217, 181);  // Wheat color
-    let dark_square = Color::Rgb(181, 136, 99);    // Brown color
+    let theme = app.theme;
     
     let is_selected = app.selected_square == Some(square);
+    let is_cursor = app.cursor == square;
     let is_legal_move = if let Some(from_sq) = app.selected_square {
         if let Some(army) = app.selected_army {
             app.game.is_legal_move(army, from_sq, square)
@@ -513,34 +776,29 @@ fn board_square_info(app: &mut App, square: u8, current_army: Army) -> (String,
         false
     };
     
-    let throne_bg = Color::Rgb(200, 150, 100);     // Golden throne
-    let selected_bg = Color::Rgb(255, 255, 100);   // Bright yellow
-    let legal_move_bg = Color::Rgb(144, 238, 144); // Light green
-    
     let throne = app.game.board.throne_owner(square);
     let bg = if is_selected {
-        selected_bg
+        theme.selection
+    } else if is_cursor {
+        theme.cursor
     } else if is_legal_move {
-        legal_move_bg
+        theme.legal_move
+    } else if covered {
+        theme.coverage
     } else if throne.is_some() {
-        throne_bg
+        theme.throne
     } else if is_light_square {
-        light_square
+        theme.light_square
     } else {
-        dark_square
+        theme.dark_square
     };
     
-    if let Some((army, kind)) = app.game.board.piece_at(square) {
+    let (text, style) = if let Some((army, kind)) = app.game.board.piece_at(square) {
         // Use darker colors for pieces on light squares, lighter on dark
-        let fg = if is_light_square || is_selected || is_legal_move {
-            match army {
-                Army::Blue => Color::Rgb(0, 0, 200),      // Dark blue
-                Army::Black => Color::Rgb(40, 40, 40),    // Dark gray
-                Army::Red => Color::Rgb(200, 0, 0),       // Dark red
-                Army::Yellow => Color::Rgb(180, 140, 0),  // Dark yellow/gold
-            }
+        let fg = if is_light_square || is_selected || is_cursor || is_legal_move || covered {
+            theme.army_color_on_light(army)
         } else {
-            army_color(army)  // Bright colors on dark squares
+            army_color(&theme, army)  // Bright colors on dark squares
         };
         
         let mut style = Style::default().fg(fg).bg(bg);
@@ -555,10 +813,19 @@ fn board_square_info(app: &mut App, square: u8, current_army: Army) -> (String,
         };
         
         (text, style)
+    } else if let Some(count) = heat.filter(|&count| count > 0) {
+        let style = Style::default().fg(theme.legal_move).bg(bg).add_modifier(Modifier::BOLD);
+        (count.min(9).to_string(), style)
     } else if throne.is_some() {
-        ("◆".to_string(), Style::default().fg(Color::Rgb(139, 90, 43)).bg(bg))
+        ("◆".to_string(), Style::default().fg(theme.throne_marker).bg(bg))
+    } else {
+        (".".to_string(), Style::default().fg(theme.empty_marker).bg(bg))
+    };
+    
+    if is_cursor {
+        (text, style.add_modifier(Modifier::UNDERLINED))
     } else {
-        (".".to_string(), Style::default().fg(Color::Rgb(120, 120, 120)).bg(bg))
+        (text, style)
     }
 }
 
@@ -588,11 +855,7 @@ fn army_symbol(army: Army) -> &'static str {
 }
 
 fn controller_label(id: PlayerId) -> &'static str {
-    match id.0 {
-        0 => "P1",
-        1 => "P2",
-        _ => "P?",
-    }
+    id.label()
 }
 
 fn command_help() -> String {
@@ -608,7 +871,7 @@ fn build_army_selector(app: &App) -> Paragraph {
         let is_current = app.game.current_army() == army;
         
         let mut style = Style::default()
-            .fg(army_color(army))
+            .fg(army_color(&app.theme, army))
             .bg(if is_selected { Color::Rgb(60, 60, 60) } else { BG_COLOR });
         
         if is_selected {
@@ -0,0 +1,266 @@
+mod common;
+
+use common::{game_with_pieces, square};
+use enoch::engine::ai;
+use enoch::engine::board::Board;
+use enoch::engine::game::Game;
+use enoch::engine::types::{Army, PieceKind, Square};
+use std::time::{Duration, Instant};
+
+#[test]
+fn search_returns_a_legal_move_at_depth_one() {
+    let game = Game::default();
+    let mv = ai::search(&game, Army::Blue, 1).expect("Blue has legal moves");
+    assert!(game
+        .generate_legal_moves(Army::Blue)
+        .iter()
+        .any(|m| m.from == mv.from && m.to == mv.to));
+}
+
+#[test]
+fn search_takes_a_free_piece() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Rook, square('e', 2)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Knight, square('e', 6)),
+    ]);
+
+    let mv = ai::search(&game, Army::Blue, 1).unwrap();
+    assert_eq!((mv.from, mv.to), (square('e', 2), square('e', 6)));
+}
+
+#[test]
+fn search_timed_returns_move_within_budget() {
+    let game = Game::default();
+    let budget = 20;
+    let start = Instant::now();
+    let mv = ai::search_timed(&game, Army::Blue, budget);
+    let elapsed = start.elapsed();
+
+    assert!(mv.is_some(), "at least the depth-1 result is returned");
+    // Depth 1 always completes; beyond that the budget is only overrun by
+    // the node being expanded when the deadline passes.
+    assert!(
+        elapsed < Duration::from_millis(budget) + Duration::from_millis(500),
+        "search took {:?}",
+        elapsed
+    );
+}
+
+#[test]
+fn search_timed_with_zero_budget_still_moves() {
+    let game = Game::default();
+    assert!(ai::search_timed(&game, Army::Blue, 0).is_some());
+}
+
+/// Blue can take the Red king on a8 or the Red queen on e6; the king capture
+/// must win. Blue's own king is in check from a Red rook, which the king
+/// capture doesn't resolve, so the forced-king-move rule is exercised too.
+fn king_capture_position() -> Game {
+    game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('h', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 1)),
+        (Army::Blue, PieceKind::Knight, square('d', 4)),
+        (Army::Red, PieceKind::King, square('a', 8)),
+        (Army::Red, PieceKind::Queen, square('e', 6)),
+        (Army::Red, PieceKind::Rook, square('h', 7)),
+    ])
+}
+
+#[test]
+fn king_capture_is_generated_and_flagged() {
+    let game = king_capture_position();
+    assert!(game.king_in_check(Army::Blue));
+
+    let moves = game.generate_legal_moves(Army::Blue);
+    let king_capture = moves
+        .iter()
+        .find(|m| m.to == square('a', 8))
+        .expect("rook takes king is legal even while in check");
+    assert!(king_capture.captures_king);
+    assert_eq!(moves.iter().filter(|m| m.captures_king).count(), 1);
+}
+
+#[test]
+fn ai_prefers_king_capture() {
+    let expected = (square('a', 1), square('a', 8));
+
+    let mut game = king_capture_position();
+    for _ in 0..20 {
+        let mv = ai::capture_preferring_move(&mut game, Army::Blue).unwrap();
+        assert_eq!((mv.from, mv.to), expected);
+    }
+    for depth in 1..=3 {
+        let mv = ai::search(&game, Army::Blue, depth).unwrap();
+        assert_eq!((mv.from, mv.to), expected, "depth {}", depth);
+    }
+    let mv = ai::search_timed(&game, Army::Blue, 50).unwrap();
+    assert_eq!((mv.from, mv.to), expected);
+
+    game.apply_move(Army::Blue, expected.0, expected.1, None)
+        .unwrap();
+    assert!(game.army_is_frozen(Army::Red));
+    assert_eq!(game.board.king_square(Army::Red), None);
+    assert!(!game.king_in_check(Army::Blue));
+}
+
+#[test]
+fn team_aware_move_declines_a_capture_that_exposes_the_allied_king() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('h', 1)),
+        // The Blue knight on d2 shields the Black king on c1 from the g5 bishop.
+        (Army::Blue, PieceKind::Knight, square('d', 2)),
+        (Army::Black, PieceKind::King, square('c', 1)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Bishop, square('g', 5)),
+        (Army::Red, PieceKind::Rook, square('b', 3)),
+        (Army::Yellow, PieceKind::King, square('a', 8)),
+    ]);
+
+    let greedy = ai::search(&game, Army::Blue, 1).unwrap();
+    assert_eq!((greedy.from, greedy.to), (square('d', 2), square('b', 3)));
+
+    let mv = ai::team_aware_move(&game, Army::Blue, 1).unwrap();
+    assert_ne!(mv.to, square('b', 3), "Nxb3 hands Red the Black king");
+}
+
+/// Black is frozen; its thrones are a4 and a5. The Blue king on `king` is
+/// the only Air piece that can move.
+fn frozen_ally_position(king: Square) -> Game {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, king),
+        (Army::Black, PieceKind::Rook, square('c', 1)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Yellow, PieceKind::King, square('h', 1)),
+    ]);
+    game.freeze_army(Army::Black);
+    game
+}
+
+fn steps_to_black_throne(square: Square) -> u8 {
+    [24u8, 32]
+        .iter()
+        .map(|&throne| (square % 8).abs_diff(throne % 8).max((square / 8).abs_diff(throne / 8)))
+        .min()
+        .unwrap()
+}
+
+#[test]
+fn team_aware_move_seizes_a_frozen_allys_throne() {
+    let game = frozen_ally_position(square('b', 4));
+    let mv = ai::team_aware_move(&game, Army::Blue, 1).unwrap();
+    assert!(
+        [square('a', 4), square('a', 5)].contains(&mv.to),
+        "the king should step onto a Black throne"
+    );
+
+    let mut next = game.clone();
+    next.apply_move(Army::Blue, mv.from, mv.to, None).unwrap();
+    assert!(!next.army_is_frozen(Army::Black));
+}
+
+#[test]
+fn team_aware_move_walks_the_king_toward_a_frozen_allys_throne() {
+    let game = frozen_ally_position(square('f', 4));
+    let mv = ai::team_aware_move(&game, Army::Blue, 1).unwrap();
+    assert_eq!(mv.kind, PieceKind::King);
+    assert!(steps_to_black_throne(mv.to) < steps_to_black_throne(mv.from));
+}
+
+#[test]
+fn team_aware_move_does_not_step_into_an_attack_for_a_revival() {
+    // Every step that brings the king nearer the throne lands on the c-file,
+    // which the Red rook on c8 covers.
+    let mut game = frozen_ally_position(square('d', 4));
+    game.board.place_piece(Army::Red, PieceKind::Rook, square('c', 8));
+    game.state.sync_with_board(&game.board);
+
+    let mv = ai::team_aware_move(&game, Army::Blue, 1).unwrap();
+    let mut next = game.clone();
+    next.apply_move(Army::Blue, mv.from, mv.to, None).unwrap();
+    assert!(!next.king_in_check(Army::Blue));
+    assert_ne!(mv.to % 8, 2, "the c-file is covered by the rook");
+}
+
+#[test]
+fn ordered_moves_put_the_most_valuable_victim_first() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Knight, square('d', 4)),
+        (Army::Blue, PieceKind::Pawn, square('e', 4)),
+        (Army::Blue, PieceKind::Queen, square('b', 4)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Rook, square('c', 6)),
+        (Army::Red, PieceKind::Pawn, square('f', 5)),
+        (Army::Red, PieceKind::Queen, square('d', 6)),
+    ]);
+
+    let moves = game.ordered_legal_moves(Army::Blue);
+    let order: Vec<(Square, Square)> = moves.iter().take(3).map(|m| (m.from, m.to)).collect();
+    assert_eq!(
+        order,
+        vec![
+            (square('d', 4), square('c', 6)), // knight takes rook
+            (square('e', 4), square('f', 5)), // pawn takes pawn
+            (square('d', 4), square('f', 5)), // knight takes pawn
+        ]
+    );
+    assert!(moves[3..].iter().all(|m| game.board.is_empty(m.to)));
+    // A queen never takes a queen, so the leap to d6 isn't offered at all.
+    assert!(!moves.iter().any(|m| m.from == square('b', 4) && m.to == square('d', 6)));
+    assert_eq!(moves.len(), game.generate_legal_moves(Army::Blue).len());
+}
+
+#[test]
+fn a_queen_with_targets_outscores_a_boxed_in_queen() {
+    use enoch::engine::types::Team;
+
+    let position = |red_rook: Square| {
+        game_with_pieces(&[
+            (Army::Blue, PieceKind::King, square('a', 1)),
+            (Army::Blue, PieceKind::Queen, square('d', 4)),
+            (Army::Red, PieceKind::King, square('h', 8)),
+            (Army::Red, PieceKind::Queen, square('f', 4)),
+            (Army::Red, PieceKind::Rook, red_rook),
+        ])
+    };
+    // The queen leaps to f6 and may take the rook there; the Red queen two
+    // squares away on f4 is no target, so with the rook on h6 the Blue
+    // queen has nothing to take.
+    let with_target = position(square('f', 6));
+    let boxed_in = position(square('h', 6));
+    assert!(ai::evaluate(&with_target, Team::Air) > ai::evaluate(&boxed_in, Team::Air));
+
+    let flat = ai::EvalWeights { idle_queen_penalty: 0, queen_target_bonus: 0 };
+    assert_eq!(
+        ai::evaluate_with(&with_target, Team::Air, &flat),
+        ai::evaluate_with(&boxed_in, Team::Air, &flat)
+    );
+}
+
+#[test]
+fn find_forks_reports_a_knight_fork_with_both_victims() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Knight, square('c', 3)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Rook, square('c', 7)),
+        (Army::Red, PieceKind::Knight, square('f', 4)),
+    ]);
+
+    let forks = game.find_forks(Army::Blue);
+    let (_, victims) = forks
+        .iter()
+        .find(|(mv, _)| (mv.from, mv.to) == (square('c', 3), square('d', 5)))
+        .expect("Nd5 forks the rook and knight");
+    assert_eq!(victims, &vec![square('f', 4), square('c', 7)]);
+
+    // Defend the rook and the fork is gone.
+    game.board.place_piece(Army::Red, PieceKind::Rook, square('c', 8));
+    game.state.sync_with_board(&game.board);
+    assert!(game
+        .find_forks(Army::Blue)
+        .iter()
+        .all(|(mv, _)| (mv.from, mv.to) != (square('c', 3), square('d', 5))));
+}
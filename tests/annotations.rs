@@ -0,0 +1,50 @@
+mod common;
+
+use common::{run, run_repl, temp_file};
+use enoch::engine::game::Game;
+
+#[test]
+fn annotation_survives_save_export_and_import() {
+    let state = temp_file("annotate", "json");
+    let pgn = temp_file("annotate", "pgn");
+    let imported = temp_file("annotate-imported", "json");
+
+    let stdout = run_repl(
+        &state,
+        "move blue: e2-e3\nmove red: b7-b6\nmove black: a5-b5\nannotate 3 ?\nquit\n",
+    );
+    assert!(stdout.contains("Annotated ply 3 with ?"));
+
+    let game = Game::from_json(&std::fs::read_to_string(&state).unwrap()).unwrap();
+    assert_eq!(game.annotation(3), Some("?"));
+    assert_eq!(game.annotation(1), None);
+
+    run(&["--headless", "--state", state.to_str().unwrap(), "--export-pgn", pgn.to_str().unwrap()]);
+    let exported = std::fs::read_to_string(&pgn).unwrap();
+    assert!(exported.contains("B:e2-e3 R:b7-b6 Bk:a5-b5? "), "{}", exported);
+
+    run(&["--headless", "--import-pgn", pgn.to_str().unwrap(), "--state", imported.to_str().unwrap()]);
+    let game = Game::from_json(&std::fs::read_to_string(&imported).unwrap()).unwrap();
+    assert_eq!(game.move_history.len(), 3);
+    assert_eq!(game.annotation(3), Some("?"));
+
+    for path in [state, pgn, imported] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn annotate_rejects_unknown_plies_and_symbols() {
+    let mut game = Game::default();
+    assert!(game.annotate(1, "!").is_err());
+
+    let from = enoch::engine::types::parse_square("e2").unwrap();
+    let to = enoch::engine::types::parse_square("e3").unwrap();
+    game.apply_move(enoch::engine::types::Army::Blue, from, to, None).unwrap();
+    assert!(game.annotate(1, "+").is_err());
+    assert_eq!(game.annotate(1, "!!"), Ok(()));
+    assert_eq!(game.annotation(1), Some("!!"));
+
+    game.undo(1).unwrap();
+    assert!(game.annotations.is_empty());
+}
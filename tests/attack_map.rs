@@ -0,0 +1,108 @@
+//! The cached attack maps must agree with testing each square against every
+//! piece set afresh, which is how check detection used to work.
+
+use enoch::engine::board::Board;
+use enoch::engine::game::Game;
+use enoch::engine::moves::{
+    compute_king_moves, compute_knights_moves, compute_pawns_moves, get_sliding_attacks,
+    BISHOP_RAYS_DIRECTIONS, QUEEN_LEAPS, ROOK_RAYS_DIRECTIONS,
+};
+use enoch::engine::types::{Army, PieceKind, Square};
+
+fn attacked_afresh(game: &Game, square: Square, army: Army) -> bool {
+    if game.army_is_frozen(army) {
+        return false;
+    }
+    let board = &game.board;
+    let mask = 1u64 << square;
+    let sliding = |kind: PieceKind, directions: &[usize]| {
+        get_sliding_attacks(
+            board.pieces(army, kind),
+            directions,
+            board.all_occupancy,
+        )
+    };
+    let (_, pawn_attacks) = compute_pawns_moves(board, army);
+    let queens = board.pieces(army, PieceKind::Queen);
+    let queen_leaps = (0..64)
+        .filter(|&sq| queens & (1u64 << sq) != 0)
+        .fold(0u64, |acc, sq| acc | QUEEN_LEAPS[sq]);
+    [
+        pawn_attacks,
+        compute_king_moves(board, army),
+        compute_knights_moves(board, army),
+        sliding(PieceKind::Bishop, &BISHOP_RAYS_DIRECTIONS),
+        sliding(PieceKind::Rook, &ROOK_RAYS_DIRECTIONS),
+        queen_leaps,
+    ]
+    .iter()
+    .any(|attacks| attacks & mask != 0)
+}
+
+/// Deterministic xorshift so failures reproduce.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+}
+
+fn random_position(rng: &mut Rng) -> Game {
+    let mut board = Board::new(&[]);
+    let mut free: Vec<Square> = (0..64).collect();
+    let mut take = |rng: &mut Rng| free.swap_remove(rng.next(free.len() as u64) as usize);
+    for army in Army::ALL {
+        board.place_piece(army, PieceKind::King, take(rng));
+        for _ in 0..rng.next(6) {
+            let kind = PieceKind::ALL[1 + rng.next(5) as usize];
+            board.place_piece(army, kind, take(rng));
+        }
+    }
+    let mut game = Game::default();
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+    game
+}
+
+fn assert_matches(game: &Game, position: usize) {
+    for army in Army::ALL {
+        for square in 0..64 {
+            assert_eq!(
+                game.is_square_attacked_by_army(square, army),
+                attacked_afresh(game, square, army),
+                "position {}: {} on square {}",
+                position,
+                army.display_name(),
+                square
+            );
+        }
+        let in_check = game.state.king_square(army).is_some_and(|king| {
+            army.team()
+                .opponent()
+                .armies()
+                .iter()
+                .any(|&enemy| attacked_afresh(game, king, enemy))
+        });
+        assert_eq!(game.king_in_check(army), in_check, "position {}", position);
+    }
+}
+
+#[test]
+fn cached_attacks_match_fresh_per_square_checks() {
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    for position in 0..200 {
+        let mut game = random_position(&mut rng);
+        assert_matches(&game, position);
+
+        // The cache must follow the pieces as they move.
+        let army = game.current_army();
+        if let Some(mv) = game.generate_legal_moves(army).first().copied() {
+            game.apply_move(army, mv.from, mv.to, mv.promotion).unwrap();
+            assert_matches(&game, position);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Helpers shared by the integration tests. Each test crate uses only some
+//! of them.
+#![allow(dead_code)]
+
+use enoch::engine::board::Board;
+use enoch::engine::game::Game;
+use enoch::engine::types::{Army, PieceKind, Square};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The square at `file` ('a'-'h') and `rank` (1-8).
+pub fn square(file: char, rank: u8) -> Square {
+    assert!((b'a'..=b'h').contains(&(file.to_ascii_lowercase() as u8)));
+    assert!((1..=8).contains(&rank));
+    (rank - 1) * 8 + (file.to_ascii_lowercase() as u8 - b'a')
+}
+
+/// The default game with its board replaced by just `placements`.
+pub fn game_with_pieces(placements: &[(Army, PieceKind, Square)]) -> Game {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+    for &(army, kind, sq) in placements {
+        board.place_piece(army, kind, sq);
+    }
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+    game
+}
+
+/// A fresh path in the temp dir, unique to this test process.
+pub fn temp_file(name: &str, ext: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("enoch-{}-{}.{}", name, std::process::id(), ext));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Runs the binary with `args`, which must succeed, and returns its stdout.
+pub fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(args)
+        .output()
+        .expect("failed to run enoch");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Feeds `input` to the headless REPL on `state` and returns its stdout.
+pub fn run_repl(state: &Path, input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--interactive", "--state"])
+        .arg(state)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to start enoch");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
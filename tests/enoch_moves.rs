@@ -1,15 +1,13 @@
+mod common;
+
+use common::square;
 use enoch::engine::{
-    board::Board,
+    board::{print_bitboard, Board},
+    game::Game,
     moves,
     types::{Army, PieceKind, Square},
 };
 
-fn square(file: char, rank: u8) -> Square {
-    let file = file.to_ascii_lowercase() as u8 - b'a';
-    let rank = rank - 1;
-    rank as Square * 8 + file as Square
-}
-
 fn bit(square: Square) -> u64 {
     1u64 << square
 }
@@ -84,11 +82,12 @@ fn pawns_move_in_army_direction() {
     let (red_moves, _) = moves::compute_pawns_moves(&board, Army::Red);
     assert_eq!(red_moves, bit(square('e', 6)));
 
+    // Black advances down the board as Red does, Yellow up as Blue does.
     let (black_moves, _) = moves::compute_pawns_moves(&board, Army::Black);
-    assert_eq!(black_moves, bit(square('h', 5)));
+    assert_eq!(black_moves, bit(square('g', 4)));
 
     let (yellow_moves, _) = moves::compute_pawns_moves(&board, Army::Yellow);
-    assert_eq!(yellow_moves, bit(square('a', 5)));
+    assert_eq!(yellow_moves, bit(square('b', 6)));
 }
 
 #[test]
@@ -184,3 +183,74 @@ fn pawn_diagonal_captures() {
     assert!(attacks & bit(square('d', 5)) != 0);
     assert!(attacks & bit(square('f', 5)) != 0);
 }
+
+#[test]
+fn print_bitboard_marks_single_square() {
+    let rows: Vec<String> = print_bitboard(bit(square('c', 6))).lines().map(String::from).collect();
+    assert_eq!(rows.len(), 9);
+    assert_eq!(rows[2], "6 . . X . . . . .");
+    assert_eq!(rows[8], "  a b c d e f g h");
+    assert_eq!(rows.iter().filter(|row| row.contains('X')).count(), 1);
+}
+
+#[test]
+fn debug_layers_labels_each_bitboard() {
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Red, PieceKind::Knight, square('h', 1));
+    let layers = board.debug_layers();
+    assert_eq!(layers.matches(" (0x").count(), 24);
+    assert!(layers.contains("Red Knight (0x0000000000000080)\n"));
+    assert!(layers.contains("1 . . . . . . . X"));
+}
+
+#[test]
+fn ascii_rows_oriented_for_yellow_puts_file_h_at_the_bottom() {
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Yellow, PieceKind::King, square('h', 5));
+    board.place_piece(Army::Blue, PieceKind::Rook, square('a', 1));
+    let rows = board.ascii_rows_oriented(Army::Yellow);
+
+    assert_eq!(rows.len(), 9);
+    assert_eq!(rows[8], "  1 2 3 4 5 6 7 8");
+    // Yellow looks from the h-file towards a, with rank 1 on its left.
+    assert_eq!(rows[0], "a R . . . . . . .");
+    assert_eq!(rows[7], "h . . . . k . . .");
+    assert_eq!(board.ascii_rows_oriented(Army::Blue)[..8], board.ascii_rows()[..]);
+}
+
+#[test]
+fn test_relative_squares_follow_each_armys_pawn_direction() {
+    assert_eq!(Army::Blue.relative_to_absolute(square('e', 2)), square('e', 2));
+    assert_eq!(Army::Yellow.relative_to_absolute(square('e', 2)), square('e', 2));
+    assert_eq!(Army::Red.relative_to_absolute(square('a', 1)), square('h', 8));
+    assert_eq!(Army::Black.relative_to_absolute(square('a', 1)), square('h', 8));
+    assert_eq!(Army::Black.relative_to_absolute(square('a', 2)), square('h', 7));
+}
+
+#[test]
+fn test_relative_pawn_push_is_the_legal_push() {
+    for army in [Army::Black, Army::Yellow] {
+        let mut board = Board::new(&[]);
+        board.place_piece(army, PieceKind::Pawn, square('d', 4));
+        board.place_piece(army, PieceKind::King, square('a', 1));
+        board.place_piece(Army::Red, PieceKind::King, square('h', 8));
+        let mut game = Game::default();
+        game.board = board;
+        game.state.sync_with_board(&game.board);
+        game.set_to_move(army).unwrap();
+
+        let pushes: Vec<Square> = game
+            .generate_legal_moves(army)
+            .iter()
+            .filter(|mv| mv.kind == PieceKind::Pawn)
+            .map(|mv| mv.to)
+            .collect();
+        assert_eq!(pushes.len(), 1, "{:?}", army);
+
+        // One rank forward on the army's own board is the legal push.
+        let relative = (0..64)
+            .find(|&sq| army.relative_to_absolute(sq) == square('d', 4))
+            .unwrap();
+        assert_eq!(army.relative_to_absolute(relative + 8), pushes[0], "{:?}", army);
+    }
+}
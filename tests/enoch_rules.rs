@@ -1,12 +1,17 @@
-use enoch::engine::board::Board;
-use enoch::engine::game::Game;
-use enoch::engine::types::{Army, Piece, PieceKind, Square};
+mod common;
 
-fn square(file: char, rank: u8) -> Square {
-    assert!((b'a'..=b'h').contains(&(file.to_ascii_lowercase() as u8)));
-    assert!((1..=8).contains(&rank));
-    (rank - 1) * 8 + (file.to_ascii_lowercase() as u8 - b'a')
-}
+use common::{game_with_pieces, square};
+use enoch::engine::arrays::{
+    available_arrays, remap_armies, rotate_bitboard_90, rotate_placements_90, ArraySpec,
+    TABLET_OF_AIR_PLACEHOLDER, TABLET_OF_EARTH_PLACEHOLDER, TABLET_OF_FIRE_PROTOTYPE,
+    TABLET_OF_WATER_PLACEHOLDER,
+};
+use enoch::engine::board::{diagonal_system, Board, DiagonalSystem};
+use enoch::engine::game::{
+    DivinationResult, Game, InvalidMoveReason, MoveError, Status, TimelineSlot,
+    DIVINATION_PASS_ROUNDS,
+};
+use enoch::engine::types::{Army, Piece, PieceKind, Square, Team};
 
 #[test]
 fn test_initial_game_setup() {
@@ -121,17 +126,17 @@ fn test_promotion_zones() {
     assert!(game.can_promote_at(Army::Blue, square('e', 8)));
     assert!(!game.can_promote_at(Army::Blue, square('e', 7)));
     
-    // Black promotes on file h (moves east)
-    assert!(game.can_promote_at(Army::Black, square('h', 4)));
-    assert!(!game.can_promote_at(Army::Black, square('g', 4)));
+    // Black promotes on rank 1 (marches south, as Red does)
+    assert!(game.can_promote_at(Army::Black, square('d', 1)));
+    assert!(!game.can_promote_at(Army::Black, square('h', 4)));
     
     // Red promotes on rank 1 (marches south)
     assert!(game.can_promote_at(Army::Red, square('e', 1)));
     assert!(!game.can_promote_at(Army::Red, square('e', 2)));
     
-    // Yellow promotes on file a (moves west)
-    assert!(game.can_promote_at(Army::Yellow, square('a', 4)));
-    assert!(!game.can_promote_at(Army::Yellow, square('b', 4)));
+    // Yellow promotes on rank 8 (marches north, as Blue does)
+    assert!(game.can_promote_at(Army::Yellow, square('d', 8)));
+    assert!(!game.can_promote_at(Army::Yellow, square('a', 4)));
 }
 
 #[test]
@@ -148,6 +153,30 @@ fn test_throne_squares() {
     assert_eq!(game.board.throne_owner(square('d', 4)), None);
 }
 
+#[test]
+fn test_occupancy_queries() {
+    let board = Game::default().board;
+
+    for (sq, army) in [
+        (square('e', 1), Army::Blue),
+        (square('e', 2), Army::Blue),
+        (square('a', 5), Army::Black),
+        (square('e', 8), Army::Red),
+        (square('h', 5), Army::Yellow),
+    ] {
+        assert!(board.is_occupied(sq));
+        assert!(!board.is_empty(sq));
+        assert_eq!(board.occupant_army(sq), Some(army));
+        assert_eq!(board.occupant_army(sq), board.piece_at(sq).map(|(army, _)| army));
+    }
+
+    for sq in [square('d', 4), square('e', 5), square('c', 3)] {
+        assert!(board.is_empty(sq));
+        assert!(!board.is_occupied(sq));
+        assert_eq!(board.occupant_army(sq), None);
+    }
+}
+
 #[test]
 fn test_turn_order() {
     let game = Game::default();
@@ -185,6 +214,40 @@ fn test_king_in_check_detection() {
     assert!(!game.king_in_check(Army::Red));
 }
 
+#[test]
+fn test_queen_checks_only_from_a_leap_away() {
+    // On an open rank, c8 is three squares from the king and e8 two.
+    let far = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Queen, square('c', 8)),
+        (Army::Red, PieceKind::King, square('g', 8)),
+    ]);
+    assert!(!far.king_in_check(Army::Red));
+
+    let near = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Queen, square('e', 8)),
+        (Army::Red, PieceKind::King, square('g', 8)),
+    ]);
+    assert!(near.king_in_check(Army::Red));
+}
+
+#[test]
+fn test_distant_queen_does_not_restrict_moves_as_a_checker() {
+    // The queen on e4 is three squares from the king: no check, so the rook
+    // is free to move anywhere, not just onto the e-file.
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 2)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Queen, square('e', 4)),
+    ]);
+    assert!(!game.king_in_check(Army::Blue));
+    let moves = game.generate_legal_moves(Army::Blue);
+    assert!(moves.iter().any(|m| m.from == square('a', 2) && m.to == square('a', 3)));
+    assert_eq!(moves.len(), game.generate_legal_moves_unpruned(Army::Blue).len());
+}
+
 #[test]
 fn test_legal_moves_exclude_self_check() {
     let mut game = Game::default();
@@ -234,6 +297,41 @@ fn test_forced_king_move_when_in_check() {
     assert!(moves.iter().all(|m| m.kind == PieceKind::King));
 }
 
+/// Blue king on e4 checked by a Red rook on e8, with a Blue rook on a6 that
+/// can block on e6.
+fn blue_checked_with_a_block(force_king_move_in_check: bool) -> Game {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Blue, PieceKind::King, square('e', 4));
+    board.place_piece(Army::Blue, PieceKind::Rook, square('a', 6));
+    board.place_piece(Army::Red, PieceKind::Rook, square('e', 8));
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+    game.set_force_king_move_in_check(force_king_move_in_check);
+    game
+}
+
+#[test]
+fn test_forced_king_move_rules_out_blocks() {
+    let game = blue_checked_with_a_block(true);
+    let moves = game.generate_legal_moves(Army::Blue);
+    assert!(!moves.is_empty());
+    assert!(!moves.iter().any(|m| m.from == square('a', 6) && m.to == square('e', 6)));
+}
+
+#[test]
+fn test_unforced_king_move_allows_blocks() {
+    let game = blue_checked_with_a_block(false);
+    let moves = game.generate_legal_moves(Army::Blue);
+    assert!(moves.iter().any(|m| m.from == square('a', 6) && m.to == square('e', 6)));
+    assert!(moves.iter().any(|m| m.kind == PieceKind::King));
+    // The rook may only move to block; every other rook move leaves the check.
+    assert!(moves
+        .iter()
+        .filter(|m| m.kind == PieceKind::Rook)
+        .all(|m| m.to == square('e', 6)));
+}
+
 #[test]
 fn test_capture_removes_piece() {
     let mut game = Game::default();
@@ -284,6 +382,29 @@ fn test_pawn_promotion() {
     assert_eq!(piece.unwrap(), (Army::Blue, PieceKind::Queen));
 }
 
+#[test]
+fn test_capturing_promotion_outcome() {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+
+    board.place_piece(Army::Blue, PieceKind::Pawn, square('e', 7));
+    board.place_piece(Army::Red, PieceKind::Knight, square('d', 8));
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+
+    let outcome = game
+        .apply_move(Army::Blue, square('e', 7), square('d', 8), Some(PieceKind::Queen))
+        .unwrap();
+    assert_eq!(
+        outcome.captured,
+        Some((Army::Red, PieceKind::Knight, square('d', 8)))
+    );
+    assert_eq!(outcome.promoted, Some(PieceKind::Queen));
+    assert_eq!(outcome.king_captured, None);
+    assert_eq!(outcome.moved.kind, PieceKind::Pawn);
+    assert_eq!(outcome.to_string(), "Blue moved Pawn to d8");
+}
+
 #[test]
 fn test_cannot_capture_own_piece() {
     let mut game = Game::default();
@@ -336,7 +457,1466 @@ fn test_multiple_armies_on_board() {
     
     // Check that all four armies have pieces
     for &army in Army::ALL.iter() {
-        let has_pieces = game.board.by_army_kind[army.index()].iter().any(|&bb| bb != 0);
+        let has_pieces = PieceKind::ALL.into_iter().any(|kind| game.board.pieces(army, kind) != 0);
         assert!(has_pieces, "{} should have pieces on the board", army.display_name());
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_pieces_returns_the_layer_for_army_and_kind() {
+    let game = Game::default();
+    let second_rank = 0xffu64 << 8;
+    assert_eq!(game.board.pieces(Army::Blue, PieceKind::Pawn), second_rank);
+    assert_eq!(game.board.pieces(Army::Blue, PieceKind::King), 1u64 << square('e', 1));
+}
+
+#[test]
+fn test_double_check_from_two_enemy_armies_forces_king_move() {
+    // Red rook checks along the e-file, Yellow bishop along the b1-h7 diagonal.
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 4)),
+        (Army::Blue, PieceKind::Rook, square('a', 2)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+        (Army::Yellow, PieceKind::Bishop, square('b', 1)),
+    ]);
+
+    assert!(game.is_square_attacked_by_army(square('e', 4), Army::Red));
+    assert!(game.is_square_attacked_by_army(square('e', 4), Army::Yellow));
+    assert!(game.king_in_check(Army::Blue));
+
+    let moves = game.generate_legal_moves(Army::Blue);
+    assert!(!moves.is_empty(), "King should have escape squares");
+    assert!(moves.iter().all(|m| m.kind == PieceKind::King));
+
+    // Staying on the e-file or the bishop's diagonal is not an escape.
+    let destinations: Vec<Square> = moves.iter().map(|m| m.to).collect();
+    assert!(!destinations.contains(&square('e', 5)));
+    assert!(!destinations.contains(&square('e', 3)));
+    assert!(!destinations.contains(&square('f', 5)));
+    assert!(!destinations.contains(&square('d', 3)));
+}
+
+#[test]
+fn test_king_escape_into_second_checker_is_excluded() {
+    // Red rook checks on the e-file; d5 dodges the rook but the Yellow
+    // knight on b6 covers it, so d5 must not appear among the escapes.
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 4)),
+        (Army::Blue, PieceKind::Knight, square('a', 1)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+        (Army::Yellow, PieceKind::Knight, square('b', 6)),
+    ]);
+
+    assert!(game.king_in_check(Army::Blue));
+    assert!(game.is_square_attacked_by_army(square('d', 5), Army::Yellow));
+
+    let moves = game.generate_legal_moves(Army::Blue);
+    assert!(moves.iter().all(|m| m.kind == PieceKind::King));
+    let destinations: Vec<Square> = moves.iter().map(|m| m.to).collect();
+    assert!(!destinations.contains(&square('d', 5)));
+    assert!(!destinations.contains(&square('e', 5)));
+    assert!(destinations.contains(&square('d', 4)));
+    assert!(destinations.contains(&square('f', 4)));
+}
+
+#[test]
+fn test_double_check_from_both_enemy_armies_each_alone() {
+    // Each checker alone is enough to force a king move; together they
+    // must not cancel out.
+    let red_only = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 4)),
+        (Army::Blue, PieceKind::Rook, square('a', 2)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+    ]);
+    let yellow_only = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 4)),
+        (Army::Blue, PieceKind::Rook, square('a', 2)),
+        (Army::Yellow, PieceKind::Bishop, square('b', 1)),
+    ]);
+
+    for game in [red_only, yellow_only] {
+        assert!(game.king_in_check(Army::Blue));
+        let moves = game.generate_legal_moves(Army::Blue);
+        assert!(!moves.is_empty());
+        assert!(moves.iter().all(|m| m.kind == PieceKind::King));
+    }
+}
+
+#[test]
+fn test_frozen_enemy_army_does_not_give_check() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 4)),
+        (Army::Blue, PieceKind::Rook, square('a', 2)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+        (Army::Yellow, PieceKind::Bishop, square('b', 1)),
+    ]);
+    game.freeze_army(Army::Red);
+
+    assert!(!game.is_square_attacked_by_army(square('e', 4), Army::Red));
+    assert!(game.king_in_check(Army::Blue), "Yellow still checks");
+    let destinations: Vec<Square> = game
+        .generate_legal_moves(Army::Blue)
+        .iter()
+        .map(|m| m.to)
+        .collect();
+    // With Red frozen, the e-file is no longer covered.
+    assert!(destinations.contains(&square('e', 5)));
+}
+
+#[test]
+fn test_promotion_squares_per_army() {
+    let game = Game::default();
+
+    let blue: Vec<Square> = ('a'..='h').map(|f| square(f, 8)).collect();
+    assert_eq!(game.promotion_squares(Army::Blue), blue);
+
+    let black: Vec<Square> = ('a'..='h').map(|f| square(f, 1)).collect();
+    assert_eq!(game.promotion_squares(Army::Black), black);
+}
+
+#[test]
+fn test_builtin_arrays_have_reachable_promotion_zones() {
+    for spec in enoch::engine::arrays::available_arrays() {
+        assert_eq!(spec.validate_promotion_zones(), Ok(()), "{}", spec.name);
+    }
+}
+
+#[test]
+fn test_promotion_zone_behind_the_pawns_is_rejected() {
+    let mut promotion_zones = TABLET_OF_FIRE_PROTOTYPE.promotion_zones;
+    promotion_zones[Army::Blue.index()] = enoch::engine::board::MASK_RANK_1;
+    let backwards = ArraySpec {
+        name: "Backwards",
+        promotion_zones,
+        ..TABLET_OF_FIRE_PROTOTYPE
+    };
+    let err = backwards.validate_promotion_zones().unwrap_err();
+    assert!(err.starts_with("Backwards: Blue pawns march north"), "{}", err);
+}
+
+#[test]
+fn test_promotion_zone_off_the_generators_pawn_path_is_rejected() {
+    // Black pawns step south along their file, so an h-file zone is never
+    // reached by a pawn on d7.
+    let mut promotion_zones = TABLET_OF_FIRE_PROTOTYPE.promotion_zones;
+    promotion_zones[Army::Black.index()] = enoch::engine::board::MASK_FILE_H;
+    let sideways = ArraySpec {
+        name: "Sideways",
+        promotion_zones,
+        placements: &[
+            (Army::Black, PieceKind::King, 1 << 32),
+            (Army::Black, PieceKind::Pawn, 1 << 51),
+        ],
+        ..TABLET_OF_FIRE_PROTOTYPE
+    };
+    let err = sideways.validate_promotion_zones().unwrap_err();
+    assert!(err.starts_with("Sideways: Black pawns march south"), "{}", err);
+}
+
+#[test]
+fn test_rotating_a_bitboard() {
+    let b1 = 1u64 << square('b', 1);
+    assert_eq!(rotate_bitboard_90(b1), 1 << square('a', 7));
+    assert_eq!(rotate_bitboard_90(1 << square('a', 8)), 1 << square('h', 8));
+
+    let fire_pawns = 0xFF00 | 0x0101_0101_0101_0101;
+    assert_eq!(
+        rotate_bitboard_90(rotate_bitboard_90(b1)),
+        1 << square('g', 8)
+    );
+    assert_eq!(
+        rotate_bitboard_90(rotate_bitboard_90(fire_pawns)),
+        u64::reverse_bits(fire_pawns)
+    );
+    let mut full_turn = fire_pawns;
+    for _ in 0..4 {
+        full_turn = rotate_bitboard_90(full_turn);
+    }
+    assert_eq!(full_turn, fire_pawns);
+}
+
+/// Each army's throne squares as one placement, so whole tablets can be
+/// compared while only the Tablet of Fire has its pieces transcribed.
+fn throne_placements(spec: &ArraySpec) -> Vec<(Army, PieceKind, u64)> {
+    Army::ALL
+        .iter()
+        .map(|&army| {
+            let thrones = spec.throne_squares[army.index()]
+                .iter()
+                .fold(0u64, |bitboard, &sq| bitboard | 1 << sq);
+            (army, PieceKind::King, thrones)
+        })
+        .collect()
+}
+
+fn sorted(mut placements: Vec<(Army, PieceKind, u64)>) -> Vec<(Army, PieceKind, u64)> {
+    placements.sort_by_key(|&(army, _, _)| army.index());
+    placements
+}
+
+#[test]
+fn test_air_and_earth_tablets_are_rotations_of_fire_and_water() {
+    let fire = throne_placements(&TABLET_OF_FIRE_PROTOTYPE);
+    let half_turn = rotate_placements_90(&rotate_placements_90(&fire));
+    let air = remap_armies(&half_turn, [Army::Blue, Army::Black, Army::Yellow, Army::Red]);
+    assert_eq!(sorted(air), throne_placements(&TABLET_OF_AIR_PLACEHOLDER));
+
+    let mut earth = throne_placements(&TABLET_OF_WATER_PLACEHOLDER);
+    for _ in 0..3 {
+        earth = rotate_placements_90(&earth);
+    }
+    assert_eq!(earth, throne_placements(&TABLET_OF_EARTH_PLACEHOLDER));
+}
+
+const TWO_BLUE_KINGS: &[(Army, PieceKind, u64)] = &[
+    (Army::Blue, PieceKind::King, 1 << 4),
+    (Army::Blue, PieceKind::King, 1 << 12),
+    (Army::Black, PieceKind::King, 1 << 32),
+    (Army::Red, PieceKind::King, 1 << 60),
+    (Army::Yellow, PieceKind::King, 1 << 39),
+];
+
+fn two_blue_kings_spec() -> ArraySpec {
+    ArraySpec {
+        name: "Malformed",
+        placements: TWO_BLUE_KINGS,
+        ..TABLET_OF_FIRE_PROTOTYPE
+    }
+}
+
+#[test]
+fn test_tablet_of_fire_has_full_complement() {
+    assert_eq!(TABLET_OF_FIRE_PROTOTYPE.validate_complement(), Ok(()));
+    let material = Game::default().board.total_material();
+    assert_eq!(material[Army::Blue.index()][PieceKind::King.index()], 1);
+    assert_eq!(material[Army::Blue.index()][PieceKind::Pawn.index()], 8);
+}
+
+#[test]
+fn test_validate_complement_names_army_and_count() {
+    let spec = two_blue_kings_spec();
+    assert_eq!(
+        spec.validate_complement(),
+        Err("Malformed: Blue has 2 kings (expected exactly 1)".to_string())
+    );
+    let spec = ArraySpec {
+        allow_incomplete: true,
+        ..spec
+    };
+    assert_eq!(spec.board().total_material()[Army::Blue.index()][PieceKind::King.index()], 2);
+}
+
+#[test]
+fn test_overlapping_placements_are_reported() {
+    const OVERLAP: &[(Army, PieceKind, u64)] = &[
+        (Army::Blue, PieceKind::King, 1 << 4),
+        (Army::Black, PieceKind::King, 1 << 32),
+        (Army::Black, PieceKind::Queen, 1 << 4),
+        (Army::Red, PieceKind::King, 1 << 60),
+        (Army::Yellow, PieceKind::King, 1 << 39),
+    ];
+    let spec = ArraySpec {
+        name: "Overlap",
+        placements: OVERLAP,
+        ..TABLET_OF_FIRE_PROTOTYPE
+    };
+    assert_eq!(
+        spec.validate_complement(),
+        Err("Overlap: Blue king and Black queen both start on e1".to_string())
+    );
+    for spec in available_arrays() {
+        assert_eq!(spec.validate_complement().err().filter(|e| e.contains("both start")), None);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Overlap: Cannot place Black Queen on e1")]
+fn test_overlapping_placements_are_not_dropped() {
+    const OVERLAP: &[(Army, PieceKind, u64)] = &[
+        (Army::Blue, PieceKind::King, 1 << 4),
+        (Army::Black, PieceKind::Queen, 1 << 4),
+    ];
+    ArraySpec {
+        name: "Overlap",
+        placements: OVERLAP,
+        allow_incomplete: true,
+        ..TABLET_OF_FIRE_PROTOTYPE
+    }
+    .board();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Blue has 2 kings")]
+fn test_malformed_array_fails_complement_assertion() {
+    two_blue_kings_spec().board();
+}
+
+#[test]
+fn test_validate_invariants_detects_desync() {
+    let mut game = Game::default();
+    assert_eq!(game.validate_invariants(), Ok(()));
+
+    // Moving the king on the board without updating the cached state.
+    game.board
+        .move_piece(Army::Blue, PieceKind::King, square('e', 1), square('e', 4));
+    assert!(game.validate_invariants().unwrap_err().contains("Blue king"));
+    game.state.sync_with_board(&game.board);
+    assert_eq!(game.validate_invariants(), Ok(()));
+
+    // Freezing through the state only.
+    game.state.set_frozen(Army::Red, true);
+    assert!(game.validate_invariants().unwrap_err().contains("frozen"));
+    game.state.set_frozen(Army::Red, false);
+
+    // Editing a piece bitboard without refreshing occupancy.
+    *game.board.pieces_mut(Army::Blue, PieceKind::Pawn) |= 1u64 << square('d', 4);
+    assert!(game.validate_invariants().unwrap_err().contains("occupancy"));
+    game.board.refresh_occupancy();
+    assert_eq!(game.validate_invariants(), Ok(()));
+
+    // Marking an army that can still move as stalemated.
+    game.state.set_stalemate(Army::Red, true);
+    assert!(game.validate_invariants().unwrap_err().contains("Red is marked stalemated"));
+    game.state.set_stalemate(Army::Red, false);
+    assert_eq!(game.validate_invariants(), Ok(()));
+}
+
+#[test]
+fn test_pawn_of_knight_promotes_to_knight() {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+    // King, rook and bishop alongside the pawn: not a privileged pawn.
+    board.place_piece(Army::Blue, PieceKind::King, square('a', 1));
+    board.place_piece(Army::Blue, PieceKind::Rook, square('h', 1));
+    board.place_piece(Army::Blue, PieceKind::Bishop, square('c', 1));
+    board.place_pawn_of(Army::Blue, PieceKind::Knight, square('e', 7));
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+
+    assert_eq!(
+        game.board.pawn_type_at(Army::Blue, square('e', 7)),
+        Some(PieceKind::Knight)
+    );
+
+    game.apply_move(Army::Blue, square('e', 7), square('e', 8), None)
+        .unwrap();
+    assert_eq!(
+        game.board.piece_at(square('e', 8)),
+        Some((Army::Blue, PieceKind::Knight))
+    );
+    assert_eq!(game.board.pawn_type_at(Army::Blue, square('e', 8)), None);
+}
+
+#[test]
+fn test_pawn_type_follows_the_pawn_and_survives_serialization() {
+    let placements = [(
+        Army::Blue,
+        Piece {
+            army: Army::Blue,
+            kind: PieceKind::Pawn,
+            pawn_type: Some(PieceKind::Rook),
+        },
+        1u64 << square('d', 2),
+    )];
+    let mut board = Board::new(&placements);
+    board.place_piece(Army::Blue, PieceKind::King, square('a', 1));
+    let mut game = Game::new(board);
+
+    game.apply_move(Army::Blue, square('d', 2), square('d', 3), None)
+        .unwrap();
+    assert_eq!(game.board.pawn_type_at(Army::Blue, square('d', 2)), None);
+    assert_eq!(
+        game.board.pawn_type_at(Army::Blue, square('d', 3)),
+        Some(PieceKind::Rook)
+    );
+
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(
+        loaded.board.pawn_type_at(Army::Blue, square('d', 3)),
+        Some(PieceKind::Rook)
+    );
+}
+
+#[test]
+fn test_privileged_pawn_overrides_pawn_type() {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+    // King + pawn only: the pawn is privileged and may pick its promotion.
+    board.place_piece(Army::Blue, PieceKind::King, square('a', 1));
+    board.place_pawn_of(Army::Blue, PieceKind::Knight, square('e', 7));
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+
+    game.apply_move(Army::Blue, square('e', 7), square('e', 8), Some(PieceKind::Rook))
+        .unwrap();
+    assert_eq!(
+        game.board.piece_at(square('e', 8)),
+        Some((Army::Blue, PieceKind::Rook))
+    );
+}
+
+#[test]
+fn test_privileged_pawn_defaults_to_queen() {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Blue, PieceKind::King, square('a', 1));
+    board.place_pawn_of(Army::Blue, PieceKind::Knight, square('e', 7));
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+
+    let outcome = game
+        .apply_move(Army::Blue, square('e', 7), square('e', 8), None)
+        .unwrap();
+    assert_eq!(outcome.promoted, Some(PieceKind::Queen));
+    assert!(outcome.promotion_defaulted);
+    assert!(outcome.to_string().ends_with("(promoted to Queen by default)"));
+    assert_eq!(
+        game.board.piece_at(square('e', 8)),
+        Some((Army::Blue, PieceKind::Queen))
+    );
+}
+
+#[test]
+fn test_privileged_pawn_cannot_promote_to_king_or_pawn() {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Blue, PieceKind::King, square('a', 1));
+    board.place_pawn_of(Army::Blue, PieceKind::Knight, square('e', 7));
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+    let before = game.board;
+
+    for target in [PieceKind::King, PieceKind::Pawn] {
+        let err = game
+            .apply_move(Army::Blue, square('e', 7), square('e', 8), Some(target))
+            .err()
+            .unwrap();
+        assert_eq!(err, MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotionTarget));
+        assert!(err.to_string().contains("King or a Pawn"), "{}", err);
+        assert!(matches!(
+            game.peek_move(Army::Blue, square('e', 7), square('e', 8), Some(target)),
+            Err(MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotionTarget))
+        ));
+    }
+    assert!(game.board == before);
+    assert_eq!(game.current_army(), Army::Blue);
+    assert!(game.move_history.is_empty());
+    assert!(game.state_history.is_empty());
+}
+
+#[test]
+fn test_promoting_to_a_queen_in_play_demotes_the_old_queen() {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+    // King + queen + pawn: privileged, and the army keeps one queen.
+    board.place_piece(Army::Blue, PieceKind::King, square('a', 1));
+    board.place_piece(Army::Blue, PieceKind::Queen, square('c', 1));
+    board.place_pawn_of(Army::Blue, PieceKind::Queen, square('e', 7));
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+
+    let outcome = game
+        .apply_move(Army::Blue, square('e', 7), square('e', 8), Some(PieceKind::Queen))
+        .unwrap();
+    assert_eq!(outcome.promoted, Some(PieceKind::Queen));
+    assert_eq!(game.board.piece_at(square('e', 8)), Some((Army::Blue, PieceKind::Queen)));
+    assert_eq!(game.board.piece_at(square('c', 1)), Some((Army::Blue, PieceKind::Pawn)));
+    assert_eq!(game.board.pawn_type_at(Army::Blue, square('c', 1)), Some(PieceKind::Queen));
+    assert_eq!(game.board.king_square(Army::Blue), Some(square('a', 1)));
+
+    // With two queens (a hand-built position), the one on the lower square
+    // goes; a king is never demoted.
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Red, PieceKind::Queen, square('h', 8));
+    board.place_piece(Army::Red, PieceKind::Queen, square('b', 3));
+    board.place_piece(Army::Red, PieceKind::King, square('e', 8));
+    assert_eq!(board.demote_piece_to_pawn(Army::Red, PieceKind::Queen), Some(square('b', 3)));
+    assert_eq!(board.demote_piece_to_pawn(Army::Red, PieceKind::King), None);
+    assert_eq!(board.king_square(Army::Red), Some(square('e', 8)));
+}
+
+#[test]
+fn test_promotion_on_a_non_promoting_move_is_rejected() {
+    let mut game = Game::default();
+
+    assert_eq!(
+        game.apply_move(Army::Blue, square('e', 2), square('e', 3), Some(PieceKind::Queen))
+            .err(),
+        Some(MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotion))
+    );
+    assert_eq!(game.board.piece_at(square('e', 2)), Some((Army::Blue, PieceKind::Pawn)));
+    assert_eq!(game.current_army(), Army::Blue);
+
+    assert!(matches!(
+        game.peek_move(Army::Blue, square('b', 1), square('c', 3), Some(PieceKind::Queen)),
+        Err(MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotion))
+    ));
+    assert_eq!(
+        game.apply_san(Army::Blue, "e3=Q").unwrap_err(),
+        MoveError::InvalidMove(InvalidMoveReason::PawnInvalidPromotion)
+    );
+}
+
+#[test]
+fn test_disambiguate_returns_both_rooks() {
+    // Rooks on a3 and h3 can both reach d3.
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 3)),
+        (Army::Blue, PieceKind::Rook, square('h', 3)),
+        (Army::Red, PieceKind::King, square('e', 8)),
+    ]);
+
+    assert_eq!(
+        game.disambiguate(Army::Blue, PieceKind::Rook, square('d', 3)),
+        vec![square('a', 3), square('h', 3)]
+    );
+    assert_eq!(
+        game.disambiguate(Army::Blue, PieceKind::Rook, square('a', 5)),
+        vec![square('a', 3)]
+    );
+    assert!(game
+        .disambiguate(Army::Blue, PieceKind::Knight, square('d', 3))
+        .is_empty());
+}
+
+#[test]
+fn test_apply_san_requires_hint_for_ambiguous_source() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 3)),
+        (Army::Blue, PieceKind::Rook, square('h', 3)),
+        (Army::Red, PieceKind::King, square('e', 8)),
+    ]);
+
+    assert_eq!(
+        game.apply_san(Army::Blue, "Rd3"),
+        Err(MoveError::AmbiguousSource)
+    );
+    assert!(game.apply_san(Army::Blue, "Rad3").is_ok());
+    assert_eq!(game.board.piece_at(square('d', 3)), Some((Army::Blue, PieceKind::Rook)));
+    assert_eq!(game.board.piece_at(square('a', 3)), None);
+}
+
+#[test]
+fn test_apply_san_pawn_move() {
+    let mut game = Game::default();
+    assert!(game.apply_san(Army::Blue, "e3").is_ok());
+    assert_eq!(game.board.piece_at(square('e', 3)), Some((Army::Blue, PieceKind::Pawn)));
+    assert_eq!(game.apply_san(Army::Red, "Zz9"), Err(MoveError::ParseError));
+}
+
+#[test]
+fn test_control_map_rook_defends_blocker_but_not_beyond() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('h', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 1)),
+        (Army::Blue, PieceKind::Pawn, square('a', 3)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Pawn, square('d', 1)),
+    ]);
+    let control = game.control_map(Team::Air);
+    let bit = |sq: Square| 1u64 << sq;
+
+    // Own blocker is defended, enemy blocker is attacked, nothing past either.
+    assert_ne!(control & bit(square('a', 2)), 0);
+    assert_ne!(control & bit(square('a', 3)), 0);
+    assert_eq!(control & bit(square('a', 5)), 0);
+    assert_ne!(control & bit(square('d', 1)), 0);
+    assert_eq!(control & bit(square('e', 1)), 0);
+    assert_eq!(
+        game.army_moves_bitboard(Army::Blue) & bit(square('a', 3)),
+        0,
+        "moves exclude own pieces while the control map includes them"
+    );
+}
+
+#[test]
+fn test_control_map_queen_does_not_defend_queen() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('h', 1)),
+        (Army::Blue, PieceKind::Queen, square('d', 4)),
+        (Army::Blue, PieceKind::Queen, square('d', 6)),
+        (Army::Blue, PieceKind::Rook, square('f', 4)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    let control = game.control_map(Team::Air);
+
+    assert_eq!(control & (1u64 << square('d', 6)), 0);
+    assert_ne!(control & (1u64 << square('f', 4)), 0);
+    assert_ne!(control & (1u64 << square('b', 4)), 0);
+}
+
+#[test]
+fn test_control_map_bishop_does_not_attack_bishop() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('h', 1)),
+        (Army::Blue, PieceKind::Bishop, square('c', 1)),
+        (Army::Red, PieceKind::Bishop, square('f', 4)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    let control = game.control_map(Team::Air);
+
+    assert_eq!(control & (1u64 << square('f', 4)), 0);
+    assert_ne!(control & (1u64 << square('e', 3)), 0);
+    assert_eq!(control & (1u64 << square('g', 5)), 0, "the bishop still blocks the diagonal");
+}
+
+#[test]
+fn test_try_place_piece_rejects_occupied_square() {
+    let mut board = Board::new(&[]);
+    assert_eq!(
+        board.try_place_piece(Army::Blue, PieceKind::Rook, square('d', 4)),
+        Ok(())
+    );
+    let err = board
+        .try_place_piece(Army::Red, PieceKind::Knight, square('d', 4))
+        .unwrap_err();
+    assert!(err.contains("d4"), "{}", err);
+
+    // The original piece is untouched and no second one was added.
+    assert_eq!(
+        board.piece_at(square('d', 4)),
+        Some((Army::Blue, PieceKind::Rook))
+    );
+    assert_eq!(board.pieces(Army::Red, PieceKind::Knight), 0);
+}
+
+#[test]
+fn test_loaded_arrays_hold_one_piece_per_square() {
+    for spec in enoch::engine::arrays::available_arrays() {
+        let board = spec.board();
+        let pieces: u32 = Army::ALL
+            .into_iter()
+            .flat_map(|army| PieceKind::ALL.map(|kind| board.pieces(army, kind)))
+            .map(|bitboard| bitboard.count_ones())
+            .sum();
+        assert_eq!(pieces, board.all_occupancy.count_ones(), "{}", spec.name);
+    }
+}
+
+fn en_passant_game() -> Game {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Pawn, square('e', 2)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Pawn, square('d', 4)),
+    ]);
+    game.config.double_step_and_en_passant = true;
+    game
+}
+
+#[test]
+fn test_double_step_needs_variant_rule() {
+    let mut game = en_passant_game();
+    game.config.double_step_and_en_passant = false;
+    assert!(game
+        .apply_move(Army::Blue, square('e', 2), square('e', 4), None)
+        .is_err());
+}
+
+#[test]
+fn test_blue_double_step_captured_en_passant_by_red() {
+    let mut game = en_passant_game();
+    game.apply_move(Army::Blue, square('e', 2), square('e', 4), None)
+        .unwrap();
+    assert_eq!(game.state.en_passant, Some(square('e', 3)));
+
+    // The target square survives a save/load round trip.
+    let mut game = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(game.state.en_passant, Some(square('e', 3)));
+
+    game.apply_move(Army::Red, square('d', 4), square('e', 3), None)
+        .unwrap();
+    assert_eq!(game.board.piece_at(square('e', 3)), Some((Army::Red, PieceKind::Pawn)));
+    assert_eq!(game.board.piece_at(square('e', 4)), None);
+    assert_eq!(
+        game.move_details.last().unwrap().captured,
+        Some((Army::Blue, PieceKind::Pawn))
+    );
+    assert_eq!(game.state.en_passant, None);
+}
+
+#[test]
+fn test_en_passant_never_takes_an_allys_pawn() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Pawn, square('e', 2)),
+        (Army::Black, PieceKind::King, square('a', 8)),
+        (Army::Black, PieceKind::Pawn, square('d', 4)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Yellow, PieceKind::King, square('h', 1)),
+    ]);
+    game.config.double_step_and_en_passant = true;
+    game.config.turn_order = [Army::Blue, Army::Black, Army::Red, Army::Yellow];
+    game.apply_move(Army::Blue, square('e', 2), square('e', 4), None)
+        .unwrap();
+    assert_eq!(game.current_army(), Army::Black);
+
+    assert!(!game
+        .generate_legal_moves(Army::Black)
+        .iter()
+        .any(|m| m.from == square('d', 4) && m.to == square('e', 3)));
+    assert!(game
+        .apply_move(Army::Black, square('d', 4), square('e', 3), None)
+        .is_err());
+    assert_eq!(game.board.piece_at(square('e', 4)), Some((Army::Blue, PieceKind::Pawn)));
+}
+
+#[test]
+fn test_en_passant_target_expires_after_one_ply() {
+    let mut game = en_passant_game();
+    game.apply_move(Army::Blue, square('e', 2), square('e', 4), None)
+        .unwrap();
+    game.apply_move(Army::Red, square('h', 8), square('h', 7), None)
+        .unwrap();
+    assert_eq!(game.state.en_passant, None);
+    game.apply_move(Army::Blue, square('a', 1), square('b', 1), None)
+        .unwrap();
+
+    assert!(!game
+        .generate_legal_moves(Army::Red)
+        .iter()
+        .any(|m| m.from == square('d', 4) && m.to == square('e', 3)));
+}
+
+#[test]
+fn test_explain_illegal_turn_and_source_reasons() {
+    let mut game = Game::default();
+    let explain = |game: &Game, army, from: (char, u8), to: (char, u8)| {
+        game.explain_illegal(army, square(from.0, from.1), square(to.0, to.1))
+    };
+
+    assert_eq!(
+        explain(&game, Army::Red, ('e', 7), ('e', 6)),
+        "It is not Red's turn (Blue to move)"
+    );
+    assert_eq!(explain(&game, Army::Blue, ('e', 4), ('e', 5)), "No piece at e4");
+    assert_eq!(
+        explain(&game, Army::Blue, ('e', 7), ('e', 6)),
+        "The piece at e7 belongs to Red"
+    );
+    assert_eq!(
+        explain(&game, Army::Blue, ('a', 1), ('a', 2)),
+        "Cannot capture your own Pawn on a2"
+    );
+    assert_eq!(
+        explain(&game, Army::Blue, ('b', 1), ('b', 3)),
+        "A Knight cannot move from b1 to b3"
+    );
+
+    // `apply_move` reports the same reason.
+    assert_eq!(
+        game.apply_move(Army::Blue, square('b', 1), square('b', 3), None),
+        Err(MoveError::Rejected("A Knight cannot move from b1 to b3".to_string()))
+    );
+
+    game.freeze_army(Army::Blue);
+    assert_eq!(explain(&game, Army::Blue, ('e', 2), ('e', 3)), "Blue's army is frozen");
+}
+
+#[test]
+fn test_explain_illegal_king_safety_reasons() {
+    let pinned = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('e', 2)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    assert_eq!(
+        pinned.explain_illegal(Army::Blue, square('e', 2), square('d', 2)),
+        "Moving the Rook from e2 would leave the Blue King in check"
+    );
+
+    let checked = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 5)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    assert_eq!(
+        checked.explain_illegal(Army::Blue, square('a', 5), square('b', 5)),
+        "Blue is in check and a5-b5 does not resolve it"
+    );
+    // Blocking on e5 would resolve the check, but the king can still move.
+    assert_eq!(
+        checked.explain_illegal(Army::Blue, square('a', 5), square('e', 5)),
+        "Blue is in check and must move the King"
+    );
+}
+
+fn lone_kings_and_blue_pawn() -> Game {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Pawn, square('c', 2)),
+        (Army::Red, PieceKind::King, square('e', 8)),
+        (Army::Black, PieceKind::King, square('a', 8)),
+        (Army::Yellow, PieceKind::King, square('h', 8)),
+    ]);
+    game.config.divination_mode = true;
+    game
+}
+
+#[test]
+fn test_divination_pass_advances_turn() {
+    let mut game = lone_kings_and_blue_pawn();
+
+    // Blue has no knight to move on a roll of 2.
+    assert_eq!(
+        game.apply_divination_roll(Army::Blue, 2),
+        Ok(DivinationResult::Passed)
+    );
+    assert_eq!(game.current_army(), Army::Red);
+    assert_eq!(game.move_history.len(), 1);
+    assert!(enoch::engine::game::is_pass(&game.move_history[0]));
+    assert_eq!(game.board.piece_at(square('c', 2)), Some((Army::Blue, PieceKind::Pawn)));
+
+    game.undo(1).unwrap();
+    assert_eq!(game.current_army(), Army::Blue);
+    assert!(game.move_history.is_empty());
+}
+
+#[test]
+fn test_divination_roll_with_single_move_is_applied() {
+    let mut game = lone_kings_and_blue_pawn();
+
+    let result = game.apply_divination_roll(Army::Blue, 6).unwrap();
+    match result {
+        DivinationResult::Moved(mv) => {
+            assert_eq!((mv.from, mv.to), (square('c', 2), square('c', 3)));
+        }
+        other => panic!("expected the only pawn move, got {:?}", other),
+    }
+    assert_eq!(game.board.piece_at(square('c', 3)), Some((Army::Blue, PieceKind::Pawn)));
+    assert_eq!(game.current_army(), Army::Red);
+    assert_eq!(game.state.consecutive_passes, 0);
+
+    // The king alone has several moves, so the player must choose.
+    let mut game = lone_kings_and_blue_pawn();
+    assert!(matches!(
+        game.apply_divination_roll(Army::Blue, 1),
+        Ok(DivinationResult::Choose(moves)) if moves.len() > 1
+    ));
+    assert_eq!(game.current_army(), Army::Blue);
+}
+
+#[test]
+fn test_divination_all_pass_rounds_end_in_draw() {
+    let mut game = lone_kings_and_blue_pawn();
+    let plies = DIVINATION_PASS_ROUNDS * 4;
+
+    for ply in 1..plies {
+        let army = game.current_army();
+        assert_eq!(
+            game.apply_divination_roll(army, 2),
+            Ok(DivinationResult::Passed),
+            "pass {}",
+            ply
+        );
+    }
+    let army = game.current_army();
+    assert_eq!(game.apply_divination_roll(army, 2), Ok(DivinationResult::Drawn));
+    assert_eq!(game.status, Status::Draw);
+    assert!(game.draw_condition());
+    assert!(game.apply_divination_roll(game.current_army(), 6).is_err());
+}
+
+#[test]
+fn test_divination_move_resets_pass_count() {
+    let mut game = lone_kings_and_blue_pawn();
+    game.apply_divination_roll(Army::Blue, 2).unwrap();
+    game.apply_divination_roll(Army::Red, 2).unwrap();
+    assert_eq!(game.state.consecutive_passes, 2);
+
+    game.apply_move(Army::Black, square('a', 8), square('b', 8), None).unwrap();
+    assert_eq!(game.state.consecutive_passes, 0);
+}
+
+/// Blue's lone king on a1 is not in check, but Red's rooks cover every
+/// square it could move to. Yellow is to move.
+fn blue_about_to_be_stalemated(stalemate_is_draw: bool) -> Game {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Rook, square('b', 8)),
+        (Army::Red, PieceKind::Rook, square('h', 2)),
+        (Army::Black, PieceKind::King, square('d', 5)),
+        (Army::Yellow, PieceKind::King, square('g', 5)),
+    ]);
+    game.config.stalemate_is_draw = stalemate_is_draw;
+    game.state.current_turn_index = 3;
+    game
+}
+
+#[test]
+fn test_stalemate_skips_turn_by_default() {
+    let mut game = blue_about_to_be_stalemated(false);
+    game.apply_move(Army::Yellow, square('g', 5), square('g', 4), None)
+        .unwrap();
+
+    assert!(game.army_in_stalemate(Army::Blue));
+    assert_eq!(game.current_army(), Army::Red);
+    assert_eq!(game.status, Status::Ongoing);
+    assert!(!game.draw_condition());
+}
+
+#[test]
+fn test_stalemate_is_draw_variant_ends_game() {
+    let mut game = blue_about_to_be_stalemated(true);
+    game.apply_move(Army::Yellow, square('g', 5), square('g', 4), None)
+        .unwrap();
+
+    assert!(game.army_in_stalemate(Army::Blue));
+    assert_eq!(game.status, Status::Draw);
+    assert!(game.draw_condition());
+    let current = game.current_army();
+    assert!(!game.generate_legal_moves(current).is_empty());
+    assert!(game.apply_move(current, square('h', 8), square('g', 8), None).is_err());
+}
+
+#[test]
+fn test_reset_to_start_keeps_rule_flags() {
+    let mut game = Game::default();
+    game.config.divination_mode = true;
+    game.config.stalemate_is_draw = true;
+    game.set_force_king_move_in_check(false);
+    game.apply_move(Army::Blue, square('e', 2), square('e', 3), None)
+        .unwrap();
+    game.apply_move(Army::Red, square('d', 7), square('d', 6), None)
+        .unwrap();
+
+    game.reset_to_start();
+
+    assert!(game.board == Game::default().board);
+    assert!(game.move_history.is_empty());
+    assert_eq!(game.status, Status::Ongoing);
+    assert_eq!(game.current_army(), Army::Blue);
+    assert_eq!(game.array_name(), Game::default().array_name());
+    assert!(game.config.divination_mode);
+    assert!(game.config.stalemate_is_draw);
+    assert!(!game.config.force_king_move_in_check);
+}
+
+#[test]
+fn test_checked_army_without_moves_passes_and_loses_its_king() {
+    // Blue's king on a1 is walled in by its own pieces and checked by the
+    // Red knight on c2, which nothing of Blue's can take.
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Pawn, square('a', 2)),
+        (Army::Blue, PieceKind::Pawn, square('b', 2)),
+        (Army::Blue, PieceKind::Rook, square('b', 1)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Knight, square('c', 2)),
+        (Army::Black, PieceKind::King, square('d', 5)),
+        (Army::Yellow, PieceKind::King, square('g', 5)),
+    ]);
+    game.set_to_move(Army::Yellow).unwrap();
+    game.apply_move(Army::Yellow, square('g', 5), square('g', 4), None)
+        .unwrap();
+
+    assert_eq!(game.current_army(), Army::Blue);
+    assert!(game.king_in_check(Army::Blue));
+    assert!(game.generate_legal_moves(Army::Blue).is_empty());
+    assert!(!game.army_in_stalemate(Army::Blue));
+
+    assert!(game.skip_stuck_armies());
+    assert_eq!(game.current_army(), Army::Red);
+    assert!(!game.army_in_stalemate(Army::Blue));
+    assert_eq!(game.move_history.len(), 2, "the skip is recorded as a pass");
+    assert_eq!(game.verify_history(), Ok(()));
+
+    game.undo(1).unwrap();
+    assert_eq!(game.current_army(), Army::Blue);
+    assert!(game.skip_stuck_armies());
+    assert_eq!(game.current_army(), Army::Red);
+
+    game.apply_move(Army::Red, square('c', 2), square('a', 1), None)
+        .unwrap();
+    assert!(game.army_is_frozen(Army::Blue));
+    assert_eq!(game.status, Status::Ongoing);
+}
+
+#[test]
+fn test_reach_of_pinned_rook_excludes_leaving_the_pin() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('e', 2)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+
+    let (pseudo_legal, legal) = game.reach(square('e', 2)).expect("rook on e2");
+    assert!(pseudo_legal.count_ones() > legal.count_ones());
+    assert_eq!(legal & !pseudo_legal, 0);
+    let e_file = 0x1010_1010_1010_1010u64;
+    assert_eq!(legal & !e_file, 0, "A pinned rook may only move along the pin");
+    assert!(legal & (1u64 << square('e', 8)) != 0, "Capturing the pinner is legal");
+
+    assert!(game.reach(square('a', 1)).is_none());
+}
+
+#[test]
+fn test_air_covers_the_centre_more_than_the_corners() {
+    let game = Game::default();
+    let counts = game.coverage_counts(Team::Air);
+    let total = |squares: &[Square]| squares.iter().map(|&sq| counts[sq as usize] as u32).sum::<u32>();
+
+    let centre = total(&[square('d', 4), square('e', 4), square('d', 5), square('e', 5)]);
+    let corners = total(&[square('a', 1), square('h', 1), square('a', 8), square('h', 8)]);
+    assert!(centre > corners, "centre {} vs corners {}", centre, corners);
+
+    // The Blue knight on b1 defends the pawn on d2.
+    assert!(counts[square('d', 2) as usize] >= 1);
+}
+
+#[test]
+fn test_king_returning_to_its_own_throne_changes_nothing() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 2)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    let controllers = game.board.armies;
+
+    let outcome = game.apply_move(Army::Blue, square('e', 2), square('e', 1), None).unwrap();
+
+    assert_eq!(outcome.throne_seized, None);
+    assert_eq!(outcome.revived, vec![]);
+    assert_eq!(game.board.armies, controllers);
+    assert_eq!(game.enemy_on_throne(Army::Blue), None);
+}
+
+#[test]
+fn test_king_on_ally_throne_revives_the_ally() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('b', 5)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    game.freeze_army(Army::Black);
+    assert!(game.army_is_frozen(Army::Black));
+
+    let outcome = game.apply_move(Army::Blue, square('b', 5), square('a', 5), None).unwrap();
+
+    assert_eq!(outcome.throne_seized, Some(Army::Black));
+    assert_eq!(outcome.revived, vec![Army::Black]);
+    assert!(!game.army_is_frozen(Army::Black));
+    assert_eq!(
+        game.board.controller_for(Army::Black),
+        game.board.controller_for(Army::Blue)
+    );
+}
+
+#[test]
+fn test_king_capturing_onto_ally_throne_takes_the_piece_then_revives() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('b', 4)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Knight, square('a', 5)),
+    ]);
+    game.freeze_army(Army::Black);
+
+    let outcome = game.apply_move(Army::Blue, square('b', 4), square('a', 5), None).unwrap();
+    assert_eq!(outcome.captured, Some((Army::Red, PieceKind::Knight, square('a', 5))));
+    assert_eq!(outcome.revived, vec![Army::Black]);
+    assert_eq!(game.board.piece_at(square('a', 5)), Some((Army::Blue, PieceKind::King)));
+    assert_eq!(game.board.piece_counts(Army::Red)[PieceKind::Knight.index()], 0);
+    assert!(!game.army_is_frozen(Army::Black));
+    assert_eq!(game.state.king_square(Army::Blue), Some(square('a', 5)));
+    assert_eq!(game.validate_invariants(), Ok(()));
+}
+
+#[test]
+fn test_enemy_king_on_throne_gains_nothing_and_blocks_exchange() {
+    // Blue and Red have both lost their kings; Yellow's steps onto e1.
+    let mut game = game_with_pieces(&[
+        (Army::Yellow, PieceKind::King, square('e', 2)),
+        (Army::Black, PieceKind::King, square('a', 5)),
+    ]);
+    let blue_controller = game.board.controller_for(Army::Blue);
+
+    game.set_to_move(Army::Yellow).unwrap();
+    let outcome = game.apply_move(Army::Yellow, square('e', 2), square('e', 1), None).unwrap();
+
+    assert_eq!(outcome.throne_seized, None);
+    assert_eq!(game.board.controller_for(Army::Blue), blue_controller);
+    assert_eq!(game.enemy_on_throne(Army::Blue), Some(Army::Yellow));
+    assert_eq!(game.enemy_on_throne(Army::Red), None);
+
+    assert!(!game.exchange_prisoners(Army::Blue, Army::Red));
+    assert_eq!(game.board.piece_at(square('e', 1)), Some((Army::Yellow, PieceKind::King)));
+}
+
+#[test]
+fn test_king_squares_track_moves_captures_and_revivals() {
+    // Black starts without a king, so it and Yellow can be exchanged once
+    // Black's rook takes Yellow's.
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('c', 2)),
+        (Army::Red, PieceKind::King, square('f', 8)),
+        (Army::Black, PieceKind::Rook, square('h', 6)),
+        (Army::Yellow, PieceKind::King, square('h', 1)),
+    ]);
+    let assert_in_sync = |game: &Game| {
+        for army in Army::ALL {
+            assert_eq!(game.state.king_square(army), game.board.king_square(army), "{:?}", army);
+        }
+    };
+
+    game.apply_move(Army::Blue, square('c', 2), square('c', 3), None).unwrap();
+    assert_eq!(game.state.king_square(Army::Blue), Some(square('c', 3)));
+    assert_in_sync(&game);
+
+    game.apply_move(Army::Red, square('f', 8), square('f', 7), None).unwrap();
+    let outcome = game.apply_move(Army::Black, square('h', 6), square('h', 1), None).unwrap();
+    assert_eq!(outcome.king_captured, Some(Army::Yellow));
+    assert_eq!(game.state.king_square(Army::Yellow), None);
+    assert_in_sync(&game);
+
+    assert!(game.exchange_prisoners(Army::Black, Army::Yellow));
+    assert_eq!(game.state.king_square(Army::Black), Some(square('a', 4)));
+    assert_eq!(game.state.king_square(Army::Yellow), Some(square('h', 4)));
+    assert_in_sync(&game);
+}
+
+#[test]
+fn test_shuffling_kings_is_a_repetition_draw() {
+    // The rook keeps the position alive; bare kings would be a dead draw.
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Rook, square('c', 3)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    let shuffle = [
+        (Army::Blue, square('a', 1), square('b', 1)),
+        (Army::Red, square('h', 8), square('g', 8)),
+        (Army::Blue, square('b', 1), square('a', 1)),
+        (Army::Red, square('g', 8), square('h', 8)),
+    ];
+
+    for round in 0..2 {
+        for &(army, from, to) in &shuffle {
+            assert_eq!(game.claimable_draw(), None, "drawn too early in round {}", round);
+            game.apply_move(army, from, to, None).unwrap();
+        }
+    }
+
+    assert_eq!(game.repetition_count(), 3);
+    assert_eq!(game.plies_without_progress(), 8);
+    assert_eq!(game.rounds_without_progress(), 4, "Black and Yellow are skipped");
+    assert_eq!(game.claimable_draw(), Some("repetition"));
+}
+
+#[test]
+fn test_all_legal_moves_matches_per_army_generation() {
+    let mut game = Game::default();
+    let all = game.all_legal_moves();
+    assert!(!all[Army::Blue.index()].is_empty());
+    assert!(!all[Army::Red.index()].is_empty());
+    for army in Army::ALL {
+        assert_eq!(all[army.index()], game.generate_legal_moves(army));
+    }
+
+    game.freeze_army(Army::Black);
+    let all = game.all_legal_moves();
+    assert!(all[Army::Black.index()].is_empty());
+    assert!(!all[Army::Yellow.index()].is_empty());
+}
+
+#[test]
+fn test_king_and_aries_bishop_against_king_is_dead() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Bishop, square('d', 1)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    assert_eq!(diagonal_system(square('d', 1)), DiagonalSystem::Aries);
+    assert!(game.is_dead_position());
+
+    game.apply_move(Army::Blue, square('d', 1), square('e', 2), None).unwrap();
+    assert_eq!(game.status, Status::Draw);
+}
+
+#[test]
+fn test_bishops_on_both_systems_are_not_dead() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Bishop, square('d', 1)),
+        (Army::Blue, PieceKind::Bishop, square('c', 1)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    assert!(!game.is_dead_position());
+}
+
+#[test]
+fn test_king_and_knight_against_king_is_not_dead() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('a', 1)),
+        (Army::Blue, PieceKind::Knight, square('d', 1)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+    ]);
+    assert!(!game.is_dead_position());
+
+    game.apply_move(Army::Blue, square('d', 1), square('e', 3), None).unwrap();
+    assert_eq!(game.status, Status::Ongoing);
+}
+
+#[test]
+fn test_timeline_brackets_and_skips_frozen_armies() {
+    let mut game = Game::default();
+    game.freeze_army(Army::Black);
+
+    let timeline = game.turn_timeline(8);
+    assert_eq!(timeline.iter().filter(|slot| matches!(slot, TimelineSlot::Moves(_))).count(), 8);
+    assert!(!timeline.contains(&TimelineSlot::Moves(Army::Black)));
+    assert_eq!(
+        game.timeline_text(5),
+        "Blue → Red → [Black frozen] → Yellow → Blue → Red → …"
+    );
+
+    // The army to move moves first even when stuck, and is skipped after.
+    game.state.set_stalemate(Army::Blue, true);
+    assert_eq!(game.turn_timeline(1), vec![TimelineSlot::Moves(Army::Blue)]);
+    assert_eq!(
+        game.timeline_text(4),
+        "Blue → Red → [Black frozen] → Yellow → [Blue stalemated] → Red → …"
+    );
+}
+
+#[test]
+fn test_armies_in_turn_order_skips_frozen_armies() {
+    let mut game = Game::default();
+    assert_eq!(game.current_team(), Team::Air);
+    assert_eq!(game.team_to_move_count(), 2);
+    assert_eq!(
+        game.armies_in_turn_order(),
+        vec![Army::Blue, Army::Red, Army::Black, Army::Yellow]
+    );
+
+    game.freeze_army(Army::Black);
+    assert_eq!(game.team_to_move_count(), 1);
+    assert_eq!(game.armies_in_turn_order(), vec![Army::Blue, Army::Red, Army::Yellow]);
+
+    game.apply_move(Army::Blue, square('b', 2), square('b', 3), None).unwrap();
+    assert_eq!(game.current_team(), Team::Earth);
+    assert_eq!(game.armies_in_turn_order(), vec![Army::Red, Army::Yellow, Army::Blue]);
+}
+
+#[test]
+fn test_peek_move_leaves_turn_and_original_untouched() {
+    let game = Game::default();
+    let before = game.to_json().unwrap();
+
+    let peeked = game
+        .peek_move(Army::Blue, square('e', 2), square('e', 3), None)
+        .unwrap();
+    assert_eq!(peeked.board.piece_at(square('e', 3)), Some((Army::Blue, PieceKind::Pawn)));
+    assert_eq!(peeked.board.piece_at(square('e', 2)), None);
+    assert_eq!(peeked.state.current_turn_index, game.state.current_turn_index);
+    assert!(peeked.move_history.is_empty());
+
+    assert_eq!(game.to_json().unwrap(), before);
+    assert_eq!(game.board.piece_at(square('e', 2)), Some((Army::Blue, PieceKind::Pawn)));
+
+    // Any army may be peeked, but the move still has to be legal.
+    assert!(game.peek_move(Army::Red, square('e', 7), square('e', 6), None).is_ok());
+    assert!(matches!(
+        game.peek_move(Army::Blue, square('e', 2), square('e', 5), None),
+        Err(MoveError::InvalidMove(_))
+    ));
+}
+
+#[test]
+fn test_peek_move_and_apply_move_agree_on_pins_and_checks() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 4)),
+        (Army::Blue, PieceKind::Rook, square('e', 6)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+    ]);
+    assert_eq!(
+        game.peek_move(Army::Blue, square('e', 6), square('d', 6), None).err(),
+        Some(MoveError::Pinned)
+    );
+    let pinned = game.apply_move(Army::Blue, square('e', 6), square('d', 6), None).err().unwrap().to_string();
+    assert!(pinned.contains("would leave the Blue King in check"), "{}", pinned);
+
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 4)),
+        (Army::Blue, PieceKind::Rook, square('a', 1)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+    ]);
+    assert_eq!(
+        game.peek_move(Army::Blue, square('a', 1), square('a', 2), None).err(),
+        Some(MoveError::Checked)
+    );
+    let checked = game.apply_move(Army::Blue, square('a', 1), square('a', 2), None).err().unwrap().to_string();
+    assert!(checked.contains("is in check"), "{}", checked);
+}
+
+#[test]
+fn test_is_legal_move_checks_turn_and_freezing() {
+    let mut game = Game::default();
+    assert!(game.is_legal_move(Army::Blue, square('b', 2), square('b', 3)));
+    assert!(!game.is_legal_move(Army::Blue, square('b', 2), square('b', 5)));
+    assert!(!game.is_legal_move(Army::Red, square('b', 7), square('b', 6)), "not Red's turn");
+
+    assert_eq!(game.legal_moves(Army::Blue).len(), game.generate_legal_moves(Army::Blue).len());
+    assert!(game.is_legal_move(Army::Blue, square('b', 2), square('b', 3)), "answered from the cache");
+
+    game.freeze_army(Army::Blue);
+    assert!(!game.is_legal_move(Army::Blue, square('b', 2), square('b', 3)), "Blue is frozen");
+}
+
+#[test]
+fn test_revival_status_lists_frozen_armies_and_their_thrones() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Black, PieceKind::King, square('a', 5)),
+        (Army::Red, PieceKind::King, square('e', 8)),
+        (Army::Yellow, PieceKind::King, square('h', 5)),
+    ]);
+    assert!(game.revival_status().is_empty());
+
+    game.capture_king(Army::Black);
+    assert_eq!(
+        game.revival_status(),
+        vec![(Army::Black, true, [square('a', 4), square('a', 5)])]
+    );
+
+    // With Blue's king gone too, no Air king is left to revive Black.
+    game.capture_king(Army::Blue);
+    assert_eq!(
+        game.revival_status(),
+        vec![
+            (Army::Blue, false, [square('d', 1), square('e', 1)]),
+            (Army::Black, false, [square('a', 4), square('a', 5)]),
+        ]
+    );
+}
+
+#[test]
+fn test_set_to_move_hands_the_turn_to_an_army() {
+    let mut game = Game::default();
+    game.set_to_move(Army::Red).unwrap();
+    assert_eq!(game.current_army(), Army::Red);
+    assert!(game.generate_legal_moves(Army::Red).iter().any(|m| m.from == square('e', 7)));
+    game.apply_move(Army::Red, square('e', 7), square('e', 6), None).unwrap();
+    assert_eq!(game.current_army(), Army::Black, "the turn order carries on from Red");
+
+    game.freeze_army(Army::Yellow);
+    assert!(game.set_to_move(Army::Yellow).unwrap_err().contains("frozen"));
+    assert_eq!(game.current_army(), Army::Black);
+}
+
+#[test]
+fn test_capturing_both_enemy_kings_wins_the_game() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 1)),
+        (Army::Black, PieceKind::King, square('c', 3)),
+        (Army::Black, PieceKind::Rook, square('h', 1)),
+        (Army::Red, PieceKind::King, square('a', 8)),
+        (Army::Yellow, PieceKind::King, square('h', 5)),
+    ]);
+
+    let outcome = game.apply_move(Army::Blue, square('a', 1), square('a', 8), None).unwrap();
+    assert_eq!(outcome.status_after, Status::Ongoing);
+    assert_eq!(game.current_army(), Army::Black, "frozen Red is skipped");
+    assert!(!game.army_in_stalemate(Army::Red), "a frozen army is not stalemated");
+
+    let outcome = game.apply_move(Army::Black, square('h', 1), square('h', 5), None).unwrap();
+    assert_eq!(outcome.status_after, Status::Won(Team::Air));
+    assert_eq!(game.status, Status::Won(Team::Air));
+    assert_eq!(
+        game.apply_move(Army::Blue, square('e', 1), square('e', 2), None),
+        Err(MoveError::GameOver)
+    );
+
+    game.undo(1).unwrap();
+    assert_eq!(game.status, Status::Ongoing);
+    assert_eq!(game.board.piece_at(square('h', 5)), Some((Army::Yellow, PieceKind::King)));
+    assert!(!game.army_is_frozen(Army::Yellow));
+}
+
+#[test]
+fn test_mobility_counts_match_legal_moves() {
+    let game = Game::default();
+    let counts = game.mobility_counts();
+    // The default arrays are not mirror images, so the counts differ by
+    // army; each is the size of that army's legal move list.
+    for army in Army::ALL {
+        assert!(counts[army.index()] > 0, "{:?} has no moves", army);
+        assert_eq!(counts[army.index()], game.generate_legal_moves(army).len());
+    }
+}
+
+#[test]
+fn test_null_move_passes_the_turn_and_is_refused_in_check() {
+    let mut game = Game::default();
+    let board = game.board;
+    assert_eq!(game.current_army(), Army::Blue);
+    game.apply_null_move().unwrap();
+    assert_eq!(game.current_army(), Army::Red);
+    assert_eq!(game.board, board);
+    assert!(game.move_history.is_empty());
+
+    game.undo_null_move().unwrap();
+    assert_eq!(game.current_army(), Army::Blue);
+    assert!(game.undo_null_move().is_err());
+
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Red, PieceKind::King, square('a', 8)),
+        (Army::Red, PieceKind::Rook, square('e', 8)),
+        (Army::Black, PieceKind::King, square('h', 8)),
+        (Army::Yellow, PieceKind::King, square('h', 1)),
+    ]);
+    assert_eq!(game.apply_null_move(), Err(MoveError::Checked));
+    assert_eq!(game.current_army(), Army::Blue);
+}
+
+#[test]
+fn test_null_move_cannot_be_undone_past_a_later_move() {
+    let mut game = Game::default();
+    game.apply_null_move().unwrap();
+    let reply = game.generate_legal_moves(Army::Red)[0];
+    game.apply_move(Army::Red, reply.from, reply.to, reply.promotion).unwrap();
+
+    assert!(game.undo_null_move().is_err());
+    assert_eq!(game.move_history.len(), 1);
+
+    game.undo(1).unwrap();
+    game.undo_null_move().unwrap();
+    assert_eq!(game.current_army(), Army::Blue);
+    assert!(game.move_history.is_empty());
+}
+
+#[test]
+fn test_pieces_and_moves_are_listed_in_square_order() {
+    let game = game_with_pieces(&[
+        (Army::Blue, PieceKind::Rook, square('h', 1)),
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Pawn, square('b', 2)),
+        (Army::Blue, PieceKind::Knight, square('a', 1)),
+        (Army::Blue, PieceKind::Queen, square('d', 4)),
+        (Army::Red, PieceKind::King, square('e', 8)),
+    ]);
+
+    let pieces: Vec<_> = game.board.all_pieces_for_army(Army::Blue).collect();
+    assert_eq!(
+        pieces,
+        vec![
+            (square('a', 1), PieceKind::Knight),
+            (square('e', 1), PieceKind::King),
+            (square('h', 1), PieceKind::Rook),
+            (square('b', 2), PieceKind::Pawn),
+            (square('d', 4), PieceKind::Queen),
+        ]
+    );
+
+    let origins: Vec<Square> = game.generate_legal_moves(Army::Blue).iter().map(|mv| mv.from).collect();
+    let mut sorted = origins.clone();
+    sorted.sort();
+    assert_eq!(origins, sorted);
+    assert_eq!(origins.first(), Some(&square('a', 1)));
+}
@@ -1,14 +1,14 @@
+mod common;
+
+use common::square;
 use enoch::engine::{
-    game::Game,
-    types::{Army, PieceKind, Square},
+    arrays::TABLET_OF_WATER_PLACEHOLDER,
+    game::{Game, Status},
+    load::{self, LoadError},
+    viewer,
+    types::{Army, Move, PieceKind, PlayerId, Square, Team},
 };
 
-fn square(file: char, rank: u8) -> Square {
-    let file = file.to_ascii_lowercase() as u8 - b'a';
-    let rank = rank - 1;
-    rank as Square * 8 + file as Square
-}
-
 #[test]
 fn test_serialization_roundtrip() {
     let mut game = Game::default();
@@ -33,11 +33,373 @@ fn test_serialization_roundtrip() {
     assert_eq!(game.state.current_turn_index, loaded_game.state.current_turn_index);
     assert_eq!(game.config.turn_order, loaded_game.config.turn_order);
     
-    // Verify board state (derived fields should be reconstructed)
-    let blue_pawns_orig = game.board.by_army_kind[Army::Blue.index()][PieceKind::Pawn.index()];
-    let blue_pawns_loaded = loaded_game.board.by_army_kind[Army::Blue.index()][PieceKind::Pawn.index()];
-    assert_eq!(blue_pawns_orig, blue_pawns_loaded);
+    assert_eq!(game.state, loaded_game.state);
+    assert_eq!(game.board, loaded_game.board);
 
+    // Derived fields are not part of equality and should be reconstructed
     assert_eq!(game.board.all_occupancy, loaded_game.board.all_occupancy);
     assert_eq!(game.board.free, loaded_game.board.free);
 }
+
+#[test]
+fn test_board_equality_compares_positions() {
+    let game = Game::default();
+    let other = Game::default();
+    assert_eq!(game.board, other.board);
+    assert_eq!(game.state, other.state);
+
+    let mut moved = Game::default();
+    moved
+        .apply_move(Army::Blue, square('b', 2), square('b', 3), None)
+        .expect("b2-b3 is legal");
+    assert_ne!(moved.board, game.board);
+    assert_ne!(moved.state, game.state);
+
+    // One pawn fewer is a different position.
+    let mut missing = game.board;
+    missing.remove_piece(Army::Red, PieceKind::Pawn, square('b', 7));
+    assert_ne!(missing, game.board);
+}
+
+#[test]
+fn test_four_controllers_roundtrip() {
+    let mut game = Game::default();
+    game.assign_players(4).expect("four players are supported");
+
+    let json = game.to_json().expect("Failed to serialize");
+    let loaded_game = Game::from_json(&json).expect("Failed to deserialize");
+
+    // Default turn order is Blue, Red, Black, Yellow.
+    let expected = [
+        (Army::Blue, PlayerId::PLAYER_ONE),
+        (Army::Red, PlayerId::PLAYER_TWO),
+        (Army::Black, PlayerId::PLAYER_THREE),
+        (Army::Yellow, PlayerId::PLAYER_FOUR),
+    ];
+    for (army, controller) in expected {
+        assert_eq!(loaded_game.board.controller_for(army), controller);
+        assert_eq!(loaded_game.config.controller_map[army.index()], controller);
+    }
+}
+
+#[test]
+fn test_unsupported_player_count_is_rejected() {
+    let mut game = Game::default();
+    assert!(game.assign_players(3).is_err());
+    assert!(game.assign_players(0).is_err());
+    assert!(game.assign_players(5).is_err());
+}
+
+#[test]
+fn test_compact_round_trip_preserves_board() {
+    let game = Game::default();
+    let compact = game.to_compact();
+    assert!(compact.starts_with("blue:Ra1,Nb1,Bc1,Qd1,Ke1"));
+
+    let imported = load::from_compact(&compact).expect("compact output parses");
+    let pieces: Vec<_> = game.board.iter_pieces().collect();
+    let imported_pieces: Vec<_> = imported.board.iter_pieces().collect();
+    assert_eq!(imported_pieces, pieces);
+    assert_eq!(imported.to_compact(), compact);
+}
+
+#[test]
+fn test_compact_import_rejects_bad_specs() {
+    assert_eq!(
+        load::from_compact("blue:Ke1,Xe2").err(),
+        Some(LoadError::BadPieceSpec("Unknown piece: X".to_string()))
+    );
+    assert_eq!(
+        load::from_compact("green:Ke1").err(),
+        Some(LoadError::BadPieceSpec("Unknown army: green".to_string()))
+    );
+    assert!(load::from_compact("blue:Ke9")
+        .err()
+        .is_some_and(|e| e.to_string().starts_with("Invalid square e9")));
+    assert_eq!(
+        load::from_compact("blue:Ke1\nred:Ke1").err(),
+        Some(LoadError::DuplicateSquare(square('e', 1)))
+    );
+    assert_eq!(
+        load::from_compact("\n").err(),
+        Some(LoadError::BadPieceSpec("No pieces specified".to_string()))
+    );
+}
+
+/// Plays `moves` from a position where only Blue and Red have pieces, so
+/// the frozen Black and Yellow armies are skipped.
+fn play(moves: &[(Army, &str, &str)]) -> Game {
+    let mut game = Game::default();
+    let mut board = enoch::engine::board::Board::new(&[]);
+    for (army, kind, sq) in [
+        (Army::Blue, PieceKind::King, "e1"),
+        (Army::Blue, PieceKind::Knight, "b1"),
+        (Army::Blue, PieceKind::Knight, "g1"),
+        (Army::Red, PieceKind::King, "e8"),
+        (Army::Red, PieceKind::Knight, "b8"),
+        (Army::Red, PieceKind::Knight, "g8"),
+    ] {
+        board.place_piece(army, kind, enoch::engine::types::parse_square(sq).unwrap());
+    }
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+    for &(army, from, to) in moves {
+        let from = enoch::engine::types::parse_square(from).unwrap();
+        let to = enoch::engine::types::parse_square(to).unwrap();
+        game.apply_move(army, from, to, None)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+    game
+}
+
+#[test]
+fn test_transpositions_share_a_position_hash() {
+    let first = play(&[
+        (Army::Blue, "b1", "c3"),
+        (Army::Red, "b8", "c6"),
+        (Army::Blue, "g1", "f3"),
+        (Army::Red, "g8", "f6"),
+    ]);
+    let second = play(&[
+        (Army::Blue, "g1", "f3"),
+        (Army::Red, "g8", "f6"),
+        (Army::Blue, "b1", "c3"),
+        (Army::Red, "b8", "c6"),
+    ]);
+    assert_ne!(first.move_history, second.move_history);
+    assert_eq!(first.position_hash(), second.position_hash());
+    assert_ne!(first.position_hash(), play(&[]).position_hash());
+
+    // Same pieces, different army to move.
+    let mut other_turn = first.clone();
+    other_turn.advance_to_next_army();
+    assert_ne!(other_turn.position_hash(), first.position_hash());
+}
+
+#[test]
+fn test_packed_moves_round_trip() {
+    for &kind in PieceKind::ALL.iter() {
+        for (from, to) in [(0, 63), (63, 0), (square('e', 2), square('e', 4))] {
+            let mv = Move { from, to, kind, promotion: None, captures_king: false };
+            assert_eq!(Move::decode(mv.encode()), Ok(mv));
+        }
+        let promotion = Move {
+            from: square('b', 7),
+            to: square('b', 8),
+            kind: PieceKind::Pawn,
+            promotion: Some(kind),
+            captures_king: false,
+        };
+        assert_eq!(Move::decode(promotion.encode()), Ok(promotion));
+    }
+    assert!(Move::decode(0x7000).is_err());
+}
+
+#[test]
+fn test_saves_store_packed_history_and_load_old_saves() {
+    let mut game = Game::default();
+    game.apply_move(Army::Blue, square('b', 2), square('b', 3), None).unwrap();
+
+    let json = game.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let packed = Move {
+        from: square('b', 2),
+        to: square('b', 3),
+        kind: PieceKind::King,
+        promotion: None,
+        captures_king: false,
+    }
+    .encode();
+    assert_eq!(value["move_history"], serde_json::json!([["Blue", packed]]));
+    assert_eq!(Game::from_json(&json).unwrap().move_history, game.move_history);
+
+    let mut old = value;
+    old["move_history"] = serde_json::json!([["Blue", square('b', 2), square('b', 3), null]]);
+    let loaded = Game::from_json(&old.to_string()).unwrap();
+    assert_eq!(loaded.move_history, game.move_history);
+}
+
+#[test]
+fn test_verify_history_pinpoints_an_inserted_illegal_ply() {
+    let mut game = Game::default();
+    for (army, from, to) in [
+        (Army::Blue, square('b', 2), square('b', 3)),
+        (Army::Red, square('b', 7), square('b', 6)),
+        (Army::Black, square('a', 5), square('b', 5)),
+        (Army::Yellow, square('h', 5), square('g', 5)),
+    ] {
+        game.apply_move(army, from, to, None).unwrap_or_else(|e| panic!("{}", e));
+    }
+    assert_eq!(game.verify_history(), Ok(()));
+
+    let json = game.to_json().unwrap();
+    let loaded = Game::from_json(&json).unwrap();
+    assert_eq!(loaded.verify_history(), Ok(()));
+
+    // A plausible Blue pawn push, but it is Black's turn at ply 2.
+    let mut tampered = loaded.clone();
+    tampered
+        .move_history
+        .insert(2, (Army::Blue, square('e', 2), square('e', 3), None));
+    let (ply, reason) = tampered.verify_history().unwrap_err();
+    assert_eq!(ply, 2);
+    assert!(reason.starts_with("Blue e2-e3"), "{}", reason);
+}
+
+#[test]
+fn test_verify_history_rejects_a_board_that_does_not_match() {
+    let mut game = Game::default();
+    game.apply_move(Army::Blue, square('b', 2), square('b', 3), None).unwrap();
+    game.board.remove_piece(Army::Blue, PieceKind::Rook, square('a', 1));
+
+    let (ply, reason) = game.verify_history().unwrap_err();
+    assert_eq!(ply, 1);
+    assert!(reason.contains("board"));
+}
+
+#[test]
+fn test_verify_history_replays_under_the_games_own_rules() {
+    let mut game = Game::default();
+    game.config.double_step_and_en_passant = true;
+    game.apply_move(Army::Blue, square('e', 2), square('e', 4), None).unwrap();
+    game.apply_move(Army::Red, square('d', 7), square('d', 6), None).unwrap();
+
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.verify_history(), Ok(()));
+    assert!(loaded.replay_start().config.double_step_and_en_passant);
+}
+
+#[test]
+fn test_verify_history_replays_from_a_generated_position() {
+    let mut game = enoch::engine::load::from_generate("Ka1,Pe3:blue Kh8:red Ka8:black Kh1:yellow")
+        .unwrap();
+    game.set_to_move(Army::Red).unwrap();
+    game.apply_move(Army::Red, square('h', 8), square('g', 8), None).unwrap();
+    game.apply_move(Army::Black, square('a', 8), square('b', 8), None).unwrap();
+
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.verify_history(), Ok(()));
+    let start = loaded.replay_start();
+    assert_eq!(start.current_army(), Army::Red);
+    assert_eq!(start.board.iter_pieces().count(), 5);
+}
+
+#[test]
+fn test_takeback_waits_for_grant_then_reverts_the_requesters_last_ply() {
+    let mut game = Game::default();
+    game.apply_move(Army::Blue, square('b', 2), square('b', 3), None).unwrap();
+    let after_blue = game.board;
+    game.apply_move(Army::Red, square('b', 7), square('b', 6), None).unwrap();
+    game.apply_move(Army::Black, square('a', 5), square('b', 5), None).unwrap();
+
+    game.request_takeback(Army::Red).unwrap();
+    assert_eq!(game.move_history.len(), 3, "nothing changes before the grant");
+    assert!(game.request_takeback(Army::Blue).is_err());
+
+    // The request survives a save, and the loaded game has no undo
+    // snapshots, so granting it replays the history.
+    let mut loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.state.pending_takeback, Some(Army::Red));
+    assert_eq!(loaded.grant_takeback(Army::Blue), Ok(2));
+    assert_eq!(loaded.move_history.len(), 1);
+    assert_eq!(loaded.board, after_blue);
+    assert_eq!(loaded.current_army(), Army::Red);
+    assert_eq!(loaded.state.pending_takeback, None);
+
+    assert_eq!(game.grant_takeback(Army::Black), Ok(2));
+    assert_eq!(game.board, after_blue);
+    assert!(game.grant_takeback(Army::Black).is_err(), "the request was used up");
+}
+
+#[test]
+fn test_playing_on_declines_a_takeback() {
+    let mut game = Game::default();
+    game.apply_move(Army::Blue, square('b', 2), square('b', 3), None).unwrap();
+    game.request_takeback(Army::Blue).unwrap();
+    game.apply_move(Army::Red, square('b', 7), square('b', 6), None).unwrap();
+    assert_eq!(game.state.pending_takeback, None);
+    assert!(game.grant_takeback(Army::Red).is_err());
+}
+
+#[test]
+fn test_only_an_opponent_can_grant_a_takeback() {
+    let mut game = Game::default();
+    game.apply_move(Army::Blue, square('b', 2), square('b', 3), None).unwrap();
+    game.request_takeback(Army::Blue).unwrap();
+
+    let err = game.grant_takeback(Army::Blue).unwrap_err();
+    assert_eq!(err, "Blue cannot grant Blue's takeback; an opponent must");
+    assert!(game.grant_takeback(Army::Black).is_err(), "Black is Blue's ally");
+    assert_eq!(game.move_history.len(), 1);
+    assert_eq!(game.state.pending_takeback, Some(Army::Blue));
+
+    assert_eq!(game.grant_takeback(Army::Red), Ok(1));
+    assert!(game.move_history.is_empty());
+}
+
+#[test]
+fn test_array_name_survives_json() {
+    let game = Game::from_array_spec(&TABLET_OF_WATER_PLACEHOLDER);
+    assert_eq!(game.array_name(), Some(TABLET_OF_WATER_PLACEHOLDER.name));
+
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.array_name(), Some(TABLET_OF_WATER_PLACEHOLDER.name));
+
+    let mut unknown = loaded;
+    unknown.metadata.array = None;
+    assert_eq!(Game::from_json(&unknown.to_json().unwrap()).unwrap().array_name(), None);
+}
+
+#[test]
+fn test_html_export_embeds_the_game_and_its_plies() {
+    let mut game = Game::default();
+    game.metadata.event = Some("Lodge </script> match".to_string());
+    game.apply_move(Army::Blue, square('b', 2), square('b', 3), None).unwrap();
+    game.apply_move(Army::Red, square('b', 7), square('b', 6), None).unwrap();
+    let black = game.generate_legal_moves(Army::Black)[0];
+    game.apply_move(Army::Black, black.from, black.to, None).unwrap();
+
+    let html = viewer::to_html(&game).unwrap();
+    assert!(html.contains("data-plies=\"3\""), "{}", html);
+    assert!(html.contains("Lodge &lt;/script&gt; match"));
+
+    let start = html.find("id=\"game-data\">").unwrap() + "id=\"game-data\">".len();
+    let end = start + html[start..].find("</script>").unwrap();
+    let data: serde_json::Value = serde_json::from_str(&html[start..end]).unwrap();
+    let embedded = Game::from_json(&data["game"].to_string()).unwrap();
+    assert_eq!(embedded.move_history, game.move_history);
+    assert_eq!(data["game"]["move_history"].as_array().unwrap().len(), 3);
+    let positions = data["positions"].as_array().unwrap();
+    assert_eq!(positions.len(), 4);
+    assert_eq!(positions[0], Game::default().to_compact());
+    assert_eq!(positions[3], game.to_compact());
+}
+
+#[test]
+fn test_html_export_leaves_placeholders_in_the_title_alone() {
+    let mut game = Game::default();
+    game.metadata.event = Some("{{DATA}} and {{PLIES}}".to_string());
+    let html = viewer::to_html(&game).unwrap();
+    assert!(html.contains("<h1>{{DATA}} and {{PLIES}}</h1>"), "{}", html);
+    assert_eq!(html.matches("id=\"game-data\"").count(), 1);
+}
+
+#[test]
+fn test_won_status_round_trips_and_old_checkmate_saves_load() {
+    let mut game = Game::default();
+    game.capture_king(Army::Red);
+    game.capture_king(Army::Yellow);
+    assert_eq!(game.status, Status::Won(Team::Air));
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.status, Status::Won(Team::Air));
+
+    // Saves from before `Won` existed could carry a bare "Checkmate".
+    let mut old: serde_json::Value = serde_json::from_str(&game.to_json().unwrap()).unwrap();
+    old["status"] = serde_json::json!("Checkmate");
+    let loaded = Game::from_json(&old.to_string()).unwrap();
+    assert_eq!(loaded.status, Status::Won(Team::Air), "winner worked out from the kings");
+
+    let mut old: serde_json::Value = serde_json::from_str(&Game::default().to_json().unwrap()).unwrap();
+    old["status"] = serde_json::json!("Checkmate");
+    assert_eq!(Game::from_json(&old.to_string()).unwrap().status, Status::Ongoing);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::square;
+use enoch::engine::compare::{compare_games, Comparison, SquareDiff};
+use enoch::engine::game::Game;
+use enoch::engine::types::{Army, PieceKind, Square};
+
+fn play(moves: &[(Army, Square, Square)]) -> Game {
+    let mut game = Game::default();
+    for &(army, from, to) in moves {
+        game.apply_move(army, from, to, None).unwrap();
+    }
+    game
+}
+
+#[test]
+fn game_compared_with_itself_is_identical() {
+    let game = play(&[
+        (Army::Blue, square('e', 2), square('e', 3)),
+        (Army::Red, square('d', 7), square('d', 6)),
+    ]);
+    let loaded = Game::from_json(&game.to_json().unwrap()).unwrap();
+    assert_eq!(
+        compare_games(&Game::default(), &game, &loaded),
+        Comparison::Identical
+    );
+}
+
+#[test]
+fn altered_move_diverges_at_expected_ply() {
+    let left = play(&[
+        (Army::Blue, square('e', 2), square('e', 3)),
+        (Army::Red, square('d', 7), square('d', 6)),
+    ]);
+    let right = play(&[
+        (Army::Blue, square('e', 2), square('e', 3)),
+        (Army::Red, square('c', 7), square('c', 6)),
+    ]);
+
+    match compare_games(&Game::default(), &left, &right) {
+        Comparison::Diverged {
+            ply,
+            left: left_move,
+            right: right_move,
+            board_diff,
+        } => {
+            assert_eq!(ply, 2);
+            assert_eq!(left_move.1, square('d', 7));
+            assert_eq!(right_move.1, square('c', 7));
+            let pawn = Some((Army::Red, PieceKind::Pawn));
+            assert_eq!(
+                board_diff,
+                vec![
+                    SquareDiff { square: square('c', 6), left: None, right: pawn },
+                    SquareDiff { square: square('d', 6), left: pawn, right: None },
+                    SquareDiff { square: square('c', 7), left: pawn, right: None },
+                    SquareDiff { square: square('d', 7), left: None, right: pawn },
+                ]
+            );
+        }
+        other => panic!("expected divergence, got {:?}", other),
+    }
+}
+
+#[test]
+fn shorter_game_is_reported_as_prefix() {
+    let short = play(&[(Army::Blue, square('e', 2), square('e', 3))]);
+    let long = play(&[
+        (Army::Blue, square('e', 2), square('e', 3)),
+        (Army::Red, square('d', 7), square('d', 6)),
+    ]);
+    assert_eq!(
+        compare_games(&Game::default(), &short, &long),
+        Comparison::Prefix {
+            left_is_shorter: true,
+            plies: 1
+        }
+    );
+}
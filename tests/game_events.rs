@@ -0,0 +1,113 @@
+mod common;
+
+use common::{game_with_pieces, square};
+use enoch::engine::events::{GameEvent, GameObserver};
+use enoch::engine::game::Game;
+use enoch::engine::types::{Army, PieceKind, PlayerId, Square, Team};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Clone, Default)]
+struct Recorder(Rc<RefCell<Vec<GameEvent>>>);
+
+impl GameObserver for Recorder {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.0.borrow_mut().push(*event);
+    }
+}
+
+#[test]
+fn capturing_the_last_enemy_king_reports_capture_move_and_win() {
+    // Yellow has no king, so Red's is Earth's last.
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 1)),
+        (Army::Black, PieceKind::King, square('h', 1)),
+        (Army::Red, PieceKind::King, square('a', 8)),
+    ]);
+    let recorder = Recorder::default();
+    game.set_observer(Box::new(recorder.clone()));
+
+    game.apply_move(Army::Blue, square('a', 1), square('a', 8), None)
+        .unwrap();
+    assert_eq!(
+        *recorder.0.borrow(),
+        vec![
+            GameEvent::KingCaptured { army: Army::Red },
+            GameEvent::Moved {
+                army: Army::Blue,
+                kind: PieceKind::Rook,
+                from: square('a', 1),
+                to: square('a', 8),
+            },
+            GameEvent::GameEnded {
+                winner: Some(Team::Air),
+            },
+        ]
+    );
+}
+
+#[test]
+fn ordinary_captures_are_reported_and_lookahead_is_silent() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('e', 1)),
+        (Army::Blue, PieceKind::Rook, square('a', 1)),
+        (Army::Black, PieceKind::King, square('h', 1)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Red, PieceKind::Knight, square('a', 5)),
+        (Army::Yellow, PieceKind::King, square('e', 8)),
+    ]);
+    let recorder = Recorder::default();
+    game.set_observer(Box::new(recorder.clone()));
+
+    game.perft(2);
+    let mut copy = game.clone();
+    copy.apply_move(Army::Blue, square('a', 1), square('a', 2), None)
+        .unwrap();
+    assert!(recorder.0.borrow().is_empty());
+
+    game.apply_move(Army::Blue, square('a', 1), square('a', 5), None)
+        .unwrap();
+    assert_eq!(
+        recorder.0.borrow()[0],
+        GameEvent::Captured {
+            by: Army::Blue,
+            army: Army::Red,
+            kind: PieceKind::Knight,
+            square: square('a', 5),
+        }
+    );
+    assert_eq!(recorder.0.borrow().len(), 2);
+    assert!(game.take_observer().is_some());
+}
+
+#[test]
+fn seizing_a_throne_hands_the_ally_and_its_squares_to_the_seizing_player() {
+    let mut game = game_with_pieces(&[
+        (Army::Blue, PieceKind::King, square('b', 4)),
+        (Army::Black, PieceKind::King, square('h', 1)),
+        (Army::Black, PieceKind::Pawn, square('c', 2)),
+        (Army::Red, PieceKind::King, square('h', 8)),
+        (Army::Yellow, PieceKind::King, square('h', 6)),
+    ]);
+    game.assign_players(4).unwrap();
+    game.capture_king(Army::Black);
+    let blue_player = game.board.controller_for(Army::Blue);
+    let black_player = game.board.controller_for(Army::Black);
+    assert_eq!(game.board.squares_controlled_by(blue_player), 1u64 << square('b', 4));
+
+    let recorder = Recorder::default();
+    game.set_observer(Box::new(recorder.clone()));
+    game.apply_move(Army::Blue, square('b', 4), square('a', 4), None)
+        .unwrap();
+
+    assert!(recorder.0.borrow().contains(&GameEvent::ControllerChanged {
+        army: Army::Black,
+        from: black_player,
+        to: blue_player,
+    }));
+    let controlled = game.board.squares_controlled_by(blue_player);
+    assert_eq!(controlled, (1u64 << square('a', 4)) | (1u64 << square('c', 2)));
+    assert_eq!(game.board.squares_controlled_by(black_player), 0);
+    assert_eq!(blue_player, PlayerId::PLAYER_ONE);
+}
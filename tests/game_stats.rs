@@ -0,0 +1,92 @@
+use enoch::engine::game::Game;
+use enoch::engine::stats::GameStats;
+use enoch::engine::types::{Army, PieceKind};
+
+/// Plays `plies` moves, preferring knight moves, and returns how many knight
+/// moves each army made.
+fn play_preferring_knights(game: &mut Game, plies: usize) -> [usize; 4] {
+    let mut knight_plies = [0; 4];
+    for _ in 0..plies {
+        let army = game.current_army();
+        let moves = game.generate_legal_moves(army);
+        let mv = moves
+            .iter()
+            .find(|m| m.kind == PieceKind::Knight)
+            .or_else(|| moves.first())
+            .copied()
+            .expect("army has a legal move");
+        if mv.kind == PieceKind::Knight {
+            knight_plies[army.index()] += 1;
+        }
+        game.apply_move(army, mv.from, mv.to, None).unwrap();
+    }
+    knight_plies
+}
+
+#[test]
+fn knight_move_counts_match_knight_plies() {
+    let mut game = Game::default();
+    let knight_plies = play_preferring_knights(&mut game, 12);
+    assert!(knight_plies.iter().sum::<usize>() > 0);
+
+    let stats = GameStats::from_game(&game);
+    assert_eq!(stats.moves_played, 12);
+    for army in Army::ALL {
+        let army_stats = stats.army(army);
+        assert_eq!(
+            army_stats.piece(PieceKind::Knight).moves,
+            knight_plies[army.index()],
+            "{:?} knight moves",
+            army
+        );
+        let total: usize = army_stats.by_kind.iter().map(|p| p.moves).sum();
+        assert_eq!(total, army_stats.moves);
+    }
+}
+
+#[test]
+fn stats_replay_details_missing_from_older_saves() {
+    let mut game = Game::default();
+    play_preferring_knights(&mut game, 12);
+    let expected = GameStats::from_game(&game);
+
+    let mut json: serde_json::Value = serde_json::from_str(&game.to_json().unwrap()).unwrap();
+    json.as_object_mut().unwrap().remove("move_details");
+    let loaded = Game::from_json(&json.to_string()).unwrap();
+    assert!(loaded.move_details.is_empty());
+    assert_eq!(GameStats::from_game(&loaded), expected);
+}
+
+#[test]
+fn captures_are_credited_to_the_capturing_piece() {
+    let mut game = Game::default();
+    play_preferring_knights(&mut game, 40);
+
+    let stats = GameStats::from_game(&game);
+    let captures: usize = stats.armies.iter().map(|a| a.captures).sum();
+    let lost: usize = stats.armies.iter().map(|a| a.pieces_lost).sum();
+    let recorded = game.move_details.iter().filter(|d| d.captured.is_some()).count();
+    assert_eq!(captures, recorded);
+    assert_eq!(lost, recorded);
+}
+
+#[test]
+fn stats_json_is_stable() {
+    let mut game = Game::default();
+    play_preferring_knights(&mut game, 4);
+
+    let json = GameStats::from_game(&game).to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["moves_played"], 4);
+    assert_eq!(value["armies"][0]["army"], "Blue");
+    assert_eq!(value["armies"][0]["by_kind"][3]["kind"], "Knight");
+}
+
+#[test]
+fn undo_drops_move_details() {
+    let mut game = Game::default();
+    play_preferring_knights(&mut game, 3);
+    game.undo(2).unwrap();
+    assert_eq!(game.move_details.len(), game.move_history.len());
+    assert_eq!(GameStats::from_game(&game).moves_played, 1);
+}
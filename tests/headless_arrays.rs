@@ -0,0 +1,94 @@
+mod common;
+
+use common::{run_repl, temp_file};
+use enoch::engine::arrays::find_array_by_name;
+use enoch::engine::game::Game;
+use std::process::Command;
+
+fn saved_game(state: &std::path::Path) -> Game {
+    Game::from_json(&std::fs::read_to_string(state).unwrap()).unwrap()
+}
+
+#[test]
+fn repl_array_command_loads_named_array() {
+    let state = temp_file("repl-array", "json");
+    let stdout = run_repl(
+        &state,
+        "move blue: e2-e3\narray Tablet of Water (placeholder)\ny\nquit\n",
+    );
+    assert!(stdout.contains("Loaded array: Tablet of Water (placeholder)"));
+    assert!(stdout.contains("Turn order:"));
+
+    let spec = find_array_by_name("Tablet of Water (placeholder)").unwrap();
+    let expected = Game::from_array_spec(spec);
+    let game = saved_game(&state);
+    assert_eq!(game.board.by_army_kind, expected.board.by_army_kind);
+    assert_eq!(game.config.turn_order, spec.turn_order);
+    assert!(game.move_history.is_empty());
+    let _ = std::fs::remove_file(&state);
+}
+
+#[test]
+fn repl_array_command_can_be_cancelled() {
+    let state = temp_file("repl-array-cancel", "json");
+    let stdout = run_repl(&state, "move blue: e2-e3\narray next\nn\nquit\n");
+    assert!(stdout.contains("Cancelled"));
+    assert_eq!(saved_game(&state).move_history.len(), 1);
+    let _ = std::fs::remove_file(&state);
+}
+
+#[test]
+fn batch_array_next_and_prev_cycle() {
+    let state = temp_file("batch-array", "json");
+    let batch = state.with_extension("txt");
+    std::fs::write(&batch, "array next\narray next\narray prev\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--batch"])
+        .arg(&batch)
+        .arg("--state")
+        .arg(&state)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let spec = find_array_by_name("Tablet of Water (placeholder)").unwrap();
+    let game = saved_game(&state);
+    assert_eq!(
+        game.board.by_army_kind,
+        Game::from_array_spec(spec).board.by_army_kind
+    );
+    let _ = std::fs::remove_file(&state);
+    let _ = std::fs::remove_file(&batch);
+}
+
+#[test]
+fn list_arrays_json_reports_piece_counts() {
+    let output = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--list-arrays", "--format", "json"])
+        .output()
+        .expect("failed to start enoch");
+    assert!(output.status.success());
+    let arrays: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let arrays = arrays.as_array().unwrap();
+    assert_eq!(arrays.len(), enoch::engine::arrays::available_arrays().len());
+
+    let fire = arrays
+        .iter()
+        .find(|spec| spec["name"] == "Tablet of Fire (prototype)")
+        .expect("Tablet of Fire listed");
+    assert_eq!(fire["turn_order"][0], "Blue");
+    assert_eq!(fire["throne_squares"][0], serde_json::json!(["d1", "e1"]));
+    let blue = fire["armies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|army| army["army"] == "Blue")
+        .unwrap();
+    let pawns = blue["by_kind"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["kind"] == "Pawn")
+        .unwrap();
+    assert_eq!(pawns["count"], 8);
+}
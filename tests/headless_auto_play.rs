@@ -0,0 +1,111 @@
+use serde_json::Value;
+use std::process::Command;
+
+fn auto_play(format: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--auto-play", "--format", format])
+        .output()
+        .expect("failed to start enoch");
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn ndjson_auto_play_emits_one_object_per_ply() {
+    let stdout = auto_play("ndjson");
+    let records: Vec<Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every line is JSON"))
+        .collect();
+    let (summary, plies) = records.split_last().expect("at least a summary");
+    assert!(!plies.is_empty());
+
+    for (index, record) in plies.iter().enumerate() {
+        assert_eq!(record["ply"], index as u64 + 1);
+        let army = record["army"].as_str().unwrap();
+        assert!(["Blue", "Black", "Red", "Yellow"].contains(&army));
+        for field in ["from", "to"] {
+            let square = record[field].as_str().unwrap();
+            assert_eq!(square.len(), 2, "{} is a square name", field);
+        }
+        let capture = &record["capture"];
+        assert!(capture.is_null() || capture["piece"].is_string());
+    }
+
+    assert_eq!(summary["plies"], plies.len() as u64);
+    let result = summary["result"].as_str().unwrap();
+    assert!(["win", "draw", "aborted"].contains(&result), "unexpected result {}", result);
+    assert_eq!(summary["winner"].is_null(), result != "win");
+}
+
+#[test]
+fn text_auto_play_stays_the_default() {
+    let stdout = auto_play("text");
+    assert!(stdout.starts_with("1. "));
+    assert!(serde_json::from_str::<Value>(stdout.lines().next().unwrap()).is_err());
+}
+
+#[test]
+fn auto_play_passes_over_a_stalemated_army() {
+    let dir = std::env::temp_dir().join(format!("enoch-stalemate-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let state = dir.join("game.json");
+    let state = state.to_str().unwrap();
+
+    // Blue to move with a lone king boxed in by the Red rooks.
+    let generated = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--generate", "Ka1:blue Rb8,Rh2,Kh8:red", "--state", state])
+        .output()
+        .expect("failed to start enoch");
+    assert!(generated.status.success());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--state", state, "--auto-play", "--format", "ndjson"])
+        .output()
+        .expect("failed to start enoch");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let first: Value = serde_json::from_str(stdout.lines().next().unwrap()).unwrap();
+    assert_eq!(first["ply"], 1);
+    assert_eq!(first["army"], "Red");
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn lone_kings_end_in_a_draw_rather_than_the_move_limit() {
+    let dir = std::env::temp_dir().join(format!("enoch-lone-kings-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let state = dir.join("game.json");
+    let state = state.to_str().unwrap();
+
+    let generated = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--generate", "Kd4:blue Kh8:red", "--state", state])
+        .output()
+        .expect("failed to start enoch");
+    assert!(generated.status.success());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--state", state, "--auto-play", "--format", "ndjson"])
+        .output()
+        .expect("failed to start enoch");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let summary: Value = serde_json::from_str(stdout.lines().last().unwrap()).unwrap();
+    assert_eq!(summary["result"], "draw");
+    assert_eq!(summary["reason"], "dead position");
+    assert_eq!(summary["plies"], 1);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn ai_team_is_rejected_with_a_time_budget() {
+    let output = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--auto-play", "--ai-time", "50", "--ai-team"])
+        .output()
+        .expect("failed to start enoch");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cannot be used with '--ai-team'"), "{}", stderr);
+}
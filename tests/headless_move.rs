@@ -0,0 +1,170 @@
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(args)
+        .output()
+        .expect("failed to run enoch")
+}
+
+#[test]
+fn wrong_turn_move_exits_with_code_2() {
+    let output = run(&["--headless", "--move", "red: b7-b6"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("It is not Red's turn (Blue to move)"), "{}", stderr);
+}
+
+#[test]
+fn illegal_move_exits_with_code_3() {
+    let output = run(&["--headless", "--move", "blue: e2-e5"]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("A Pawn cannot move from e2 to e5"), "{}", stderr);
+}
+
+#[test]
+fn malformed_move_exits_with_code_1() {
+    let output = run(&["--headless", "--move", "blue e2-e3"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn line_plays_each_move_and_reports_the_position() {
+    let output = run(&["--headless", "--line", "blue: e2-e3, red: e7-e6, black: a8-b8"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("After 3 moves, Yellow to move"), "{}", stdout);
+    assert!(stdout.contains("Material:"), "{}", stdout);
+}
+
+#[test]
+fn line_stops_at_the_first_illegal_move() {
+    let output = run(&["--headless", "--line", "blue: e2-e3, red: e7-e6, black: a8-b8, yellow: h5-e5"]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("move 4 of 4 (yellow: h5-e5)"), "{}", stderr);
+    assert!(!String::from_utf8(output.stdout).unwrap().contains("Material:"));
+}
+
+#[test]
+fn headless_move_promotes_to_the_named_piece() {
+    let state = std::env::temp_dir().join(format!("enoch-promote-{}.json", std::process::id()));
+    let state = state.to_str().unwrap();
+    let generated = run(&["--headless", "--generate", "Ka1,Pe7:blue Kh8:red", "--state", state]);
+    assert!(generated.status.success());
+
+    // King and pawn only: the pawn is privileged and may become a knight.
+    let output = run(&["--headless", "--state", state, "--move", "blue: e7-e8=N"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("promoting to Knight"));
+
+    let game = enoch::engine::game::Game::from_json(&std::fs::read_to_string(state).unwrap()).unwrap();
+    assert_eq!(
+        game.board.piece_at(60),
+        Some((enoch::engine::types::Army::Blue, enoch::engine::types::PieceKind::Knight))
+    );
+    let _ = std::fs::remove_file(state);
+}
+
+#[test]
+fn unknown_promotion_piece_is_malformed() {
+    let output = run(&["--headless", "--move", "blue: e2-e3=K"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Invalid promotion piece 'K'"));
+}
+
+#[test]
+fn mobility_prints_one_count_per_army_in_turn_order() {
+    let output = run(&["--headless", "--mobility"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let armies: Vec<&str> = stdout.trim().split(' ').map(|entry| entry.split(':').next().unwrap()).collect();
+    assert_eq!(armies, ["Blue", "Red", "Black", "Yellow"]);
+
+    let output = run(&["--headless", "--mobility", "--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["Black"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn new_restarts_the_saved_game_from_its_array() {
+    let state = std::env::temp_dir().join(format!("enoch-new-{}.json", std::process::id()));
+    let state = state.to_str().unwrap();
+    let _ = std::fs::remove_file(state);
+    let moved = run(&["--headless", "--state", state, "--move", "blue: e2-e3"]);
+    assert!(moved.status.success(), "{}", String::from_utf8_lossy(&moved.stderr));
+
+    let output = run(&["--headless", "--state", state, "--new"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!("New game saved to {}", state)), "{}", stdout);
+
+    let game = enoch::engine::game::Game::from_json(&std::fs::read_to_string(state).unwrap()).unwrap();
+    assert!(game.move_history.is_empty());
+    assert!(game.board == enoch::engine::game::Game::default().board);
+    let _ = std::fs::remove_file(state);
+}
+
+#[test]
+fn new_keeps_a_generated_position() {
+    let state = std::env::temp_dir().join(format!("enoch-new-generated-{}.json", std::process::id()));
+    let state = state.to_str().unwrap();
+    let generated = run(&["--headless", "--generate", "Ka1,Pe6:blue Kh8:red", "--state", state]);
+    assert!(generated.status.success(), "{}", String::from_utf8_lossy(&generated.stderr));
+    let moved = run(&["--headless", "--state", state, "--move", "blue: e6-e7"]);
+    assert!(moved.status.success(), "{}", String::from_utf8_lossy(&moved.stderr));
+
+    let output = run(&["--headless", "--state", state, "--new"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let game = enoch::engine::game::Game::from_json(&std::fs::read_to_string(state).unwrap()).unwrap();
+    assert!(game.move_history.is_empty());
+    assert_eq!(game.board.iter_pieces().count(), 3);
+    let e6 = enoch::engine::types::parse_square("e6").unwrap();
+    assert_eq!(game.board.piece_at(e6).map(|(_, kind)| kind), Some(enoch::engine::types::PieceKind::Pawn));
+    let _ = std::fs::remove_file(state);
+}
+
+#[test]
+fn new_reports_a_failed_save() {
+    let dir = std::env::temp_dir().join(format!("enoch-new-dir-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = run(&["--headless", "--state", dir.to_str().unwrap(), "--new"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("New game saved"), "{}", stdout);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Could not save the new game"), "{}", stderr);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn array_file_games_verify() {
+    let dir = std::env::temp_dir().join(format!("enoch-array-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let array = dir.join("array.json");
+    std::fs::write(
+        &array,
+        r#"{"name": "Pawn Race", "position": "blue:Ke1,Pa2\nred:Ke8,Ph7\nblack:Ka5\nyellow:Kh4"}"#,
+    )
+    .unwrap();
+    let state = dir.join("game.json");
+    let (array, state) = (array.to_str().unwrap(), state.to_str().unwrap());
+
+    let moved = run(&["--headless", "--array-file", array, "--state", state, "--move", "blue: a2-a3"]);
+    assert!(moved.status.success(), "{}", String::from_utf8_lossy(&moved.stderr));
+    let verified = run(&["--headless", "--state", state, "--verify"]);
+    assert!(verified.status.success(), "{}", String::from_utf8_lossy(&verified.stderr));
+    assert!(String::from_utf8(verified.stdout).unwrap().contains("History verified (1 plies)"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn format_is_checked_against_every_command_before_any_runs() {
+    let output = run(&["--headless", "--stats", "--format", "json", "--auto-play"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty(), "{}", String::from_utf8_lossy(&output.stdout));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--format json does not apply to --auto-play (use text or ndjson)"), "{}", stderr);
+}
@@ -0,0 +1,60 @@
+use enoch::engine::game::Game;
+use enoch::engine::types::{parse_square, Army};
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Writes `game` next to `path` and renames it into place, as an editor or
+/// GUI doing an atomic save would.
+fn save_atomically(game: &Game, path: &std::path::Path) {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, game.to_json().unwrap()).unwrap();
+    std::fs::rename(&tmp, path).unwrap();
+}
+
+#[test]
+fn watch_emits_one_line_per_write() {
+    let state = std::env::temp_dir().join(format!("enoch-watch-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&state);
+
+    // The file does not exist yet; the watcher waits for it.
+    let mut child = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--watch"])
+        .arg(&state)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to start enoch");
+    let stdout = child.stdout.take().unwrap();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let next_line = || rx.recv_timeout(Duration::from_secs(10)).expect("no analysis emitted");
+
+    let mut game = Game::default();
+    save_atomically(&game, &state);
+    let first: serde_json::Value = serde_json::from_str(&next_line()).unwrap();
+
+    game.apply_move(Army::Blue, parse_square("e2").unwrap(), parse_square("e3").unwrap(), None)
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    save_atomically(&game, &state);
+    let second: serde_json::Value = serde_json::from_str(&next_line()).unwrap();
+
+    child.kill().ok();
+    child.wait().ok();
+    let _ = std::fs::remove_file(&state);
+
+    assert_eq!(first["turn"], "Blue");
+    assert_eq!(first["plies"], 0);
+    assert_eq!(first["result"], "ongoing");
+    assert!(first["legal_moves"].as_u64().unwrap() > 0);
+    assert_eq!(second["turn"], "Red");
+    assert_eq!(second["plies"], 1);
+    assert!(rx.try_recv().is_err(), "only two writes were made");
+}
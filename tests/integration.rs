@@ -194,7 +194,7 @@ fn test_frozen_army_cannot_move() {
     // Try to move - should fail
     let result = game.apply_move(Army::Blue, 12, 20, None);
     assert!(result.is_err(), "Frozen army should not be able to move");
-    assert!(result.unwrap_err().contains("frozen"));
+    assert!(result.unwrap_err().to_string().contains("frozen"));
 }
 
 #[test]
@@ -0,0 +1,129 @@
+use enoch::engine::load::{self, LoadError};
+
+#[test]
+fn unknown_array_name_is_reported() {
+    assert_eq!(
+        load::from_array(Some("Tablet of Nowhere")).err(),
+        Some(LoadError::UnknownArray("Tablet of Nowhere".to_string()))
+    );
+    assert!(load::from_array(None).is_ok());
+}
+
+#[test]
+fn malformed_generate_specs_are_bad_piece_specs() {
+    for spec in ["Kb1,Qc2", "Kb1:green", "Xb1:blue", "K:blue", ""] {
+        assert!(
+            matches!(load::from_generate(spec), Err(LoadError::BadPieceSpec(_))),
+            "{:?} should be a bad piece spec",
+            spec
+        );
+    }
+}
+
+#[test]
+fn off_board_square_is_a_bad_square() {
+    assert_eq!(
+        load::from_generate("Kb9:blue").err(),
+        Some(LoadError::BadSquare("b9".to_string()))
+    );
+    assert_eq!(
+        load::from_compact("red:Kz1").err(),
+        Some(LoadError::BadSquare("z1".to_string()))
+    );
+}
+
+#[test]
+fn two_pieces_on_one_square_is_a_duplicate() {
+    let err = load::from_generate("Ke1:blue Ke1:red").err().expect("duplicate rejected");
+    assert_eq!(err, LoadError::DuplicateSquare(4));
+    assert_eq!(err.to_string(), "Square e1 is occupied more than once");
+}
+
+#[test]
+fn missing_file_is_an_io_error() {
+    assert!(matches!(
+        load::read_source("/nonexistent/enoch/position.txt"),
+        Err(LoadError::Io(_))
+    ));
+}
+
+#[test]
+fn array_file_accepts_asymmetric_promotion_zones() {
+    // Blue promotes only on the queen's half of rank 8.
+    let json = r#"{
+        "name": "Half Zone",
+        "position": "blue:Ke1,Pa2,Pb2\nred:Ke8,Pa7",
+        "promotion_zones": {"Blue": 1080863910568919040}
+    }"#;
+    let spec = load::array_spec_from_json(json).unwrap();
+    assert_eq!(spec.name, "Half Zone");
+    assert_eq!(spec.promotion_zones[0], 0x0F00_0000_0000_0000);
+}
+
+#[test]
+fn array_file_with_a_zone_behind_the_pawns_is_invalid() {
+    let json = r#"{
+        "name": "Backwards",
+        "position": "blue:Ke1,Pa2\nred:Ke8",
+        "promotion_zones": {"Blue": 255}
+    }"#;
+    assert!(matches!(
+        load::array_spec_from_json(json),
+        Err(LoadError::InvalidArray(reason)) if reason.contains("Blue pawns march north")
+    ));
+    assert!(matches!(
+        load::array_spec_from_json(r#"{"name": "x"}"#),
+        Err(LoadError::InvalidArray(_))
+    ));
+}
+
+#[test]
+fn random_positions_are_valid_and_reproducible() {
+    use enoch::engine::types::{Army, PieceKind};
+
+    for seed in 0..20 {
+        let game = load::random_position(5, seed).unwrap();
+        assert_eq!(game.validate_invariants(), Ok(()));
+        for army in Army::ALL {
+            let kings = game.board.pieces(army, PieceKind::King);
+            assert_eq!(kings.count_ones(), 1, "seed {}: {:?} kings", seed, army);
+        }
+        assert_eq!(game.board.iter_pieces().count(), 20);
+        assert!(!game.king_in_check(game.current_army()), "seed {}", seed);
+        assert_eq!(game.board, load::random_position(5, seed).unwrap().board);
+    }
+    assert!(matches!(load::random_position(0, 1), Err(LoadError::BadPieceSpec(_))));
+    assert!(matches!(load::random_position(10, 1), Err(LoadError::BadPieceSpec(_))));
+}
+
+#[test]
+fn overlapping_bitboards_are_rejected_with_the_square() {
+    use enoch::engine::game::Game;
+    use enoch::engine::types::{Army, PieceKind};
+
+    let mut game = Game::default();
+    let king = game.board.king_square(Army::Blue).unwrap();
+    *game.board.pieces_mut(Army::Red, PieceKind::Pawn) |= 1u64 << king;
+    let expected = format!(
+        "Square {}{} is held by both Blue King and Red Pawn",
+        (b'a' + king % 8) as char,
+        king / 8 + 1
+    );
+    assert_eq!(game.board.validate(), Err(expected.clone()));
+
+    let err = Game::from_json(&game.to_json().unwrap()).err().unwrap();
+    assert!(err.to_string().contains(&expected), "{}", err);
+}
+
+#[test]
+fn a_second_king_is_rejected() {
+    use enoch::engine::game::Game;
+    use enoch::engine::types::{Army, PieceKind};
+
+    let mut game = Game::default();
+    // d4 is empty in the default array.
+    *game.board.pieces_mut(Army::Black, PieceKind::King) |= 1u64 << 27;
+    assert!(game.board.validate().unwrap_err().starts_with("Black has 2 kings"));
+    assert!(Game::from_json(&game.to_json().unwrap()).is_err());
+    assert!(Game::default().board.validate().is_ok());
+}
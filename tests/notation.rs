@@ -0,0 +1,43 @@
+use enoch::engine::notation::{parse_army_move, NotationError};
+use enoch::engine::types::{Army, PieceKind, Square};
+
+fn sq(name: &str) -> Square {
+    let bytes = name.as_bytes();
+    (bytes[1] - b'1') * 8 + (bytes[0] - b'a')
+}
+
+type Parsed = Result<(Army, Square, Square, Option<PieceKind>), NotationError>;
+
+#[test]
+fn every_front_end_form_parses_the_same_way() {
+    let cases: &[(&str, Parsed)] = &[
+        ("blue: e2-e4", Ok((Army::Blue, sq("e2"), sq("e4"), None))),
+        ("blue:e2e4", Ok((Army::Blue, sq("e2"), sq("e4"), None))),
+        ("  Red :  d7 - d6 ", Ok((Army::Red, sq("d7"), sq("d6"), None))),
+        ("black: a8xb8", Ok((Army::Black, sq("a8"), sq("b8"), None))),
+        ("yellow: H5XG5", Ok((Army::Yellow, sq("h5"), sq("g5"), None))),
+        ("blue: e7-e8=Q", Ok((Army::Blue, sq("e7"), sq("e8"), Some(PieceKind::Queen)))),
+        ("blue: d7xe8=n", Ok((Army::Blue, sq("d7"), sq("e8"), Some(PieceKind::Knight)))),
+        ("red: b2-b1 = R", Ok((Army::Red, sq("b2"), sq("b1"), Some(PieceKind::Rook)))),
+        ("blue: e7-e8=K", Err(NotationError::BadPromotion("K".to_string()))),
+        ("green: e2-e4", Err(NotationError::UnknownArmy("green".to_string()))),
+        ("blue e2-e4", Err(NotationError::BadFormat(
+            "Move must follow format 'army: e2-e4' (e.g., 'blue: e2-e3')".to_string(),
+        ))),
+        ("blue: e2", Err(NotationError::BadFormat(
+            "Move must be in format 'e2-e4', 'e2xe4' or 'e2e4'".to_string(),
+        ))),
+        ("blue: e2-e9", Err(NotationError::BadSquare("Invalid rank '9'. Must be 1-8".to_string()))),
+        ("blue: i2-e4", Err(NotationError::BadSquare("Invalid file 'i'. Must be a-h".to_string()))),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(&parse_army_move(input), expected, "{:?}", input);
+    }
+}
+
+#[test]
+fn unknown_army_suggests_a_spelling() {
+    let err = parse_army_move("blu: e2-e4").unwrap_err();
+    assert_eq!(err.to_string(), "Unknown army 'blu'. Did you mean 'blue' or 'black'?");
+}
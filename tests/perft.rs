@@ -0,0 +1,139 @@
+//! Perft reference counts.
+//!
+//! The start-position totals were produced by `enoch --headless --perft N`
+//! on the Tablet of Fire prototype (turn order Blue, Red, Black, Yellow) and
+//! are committed here so changes to move generation show up as a count
+//! mismatch. The depth-1 counts for the hand-built positions were checked by
+//! listing every move on paper; the deeper counts are regression values.
+
+mod common;
+
+use common::square;
+use enoch::engine::board::Board;
+use enoch::engine::game::Game;
+use enoch::engine::load;
+use enoch::engine::types::{Army, PieceKind, Square};
+
+fn position(pieces: &[(Army, PieceKind, char, u8)]) -> Game {
+    let mut game = Game::default();
+    let mut board = Board::new(&[]);
+    for &(army, kind, file, rank) in pieces {
+        board.place_piece(army, kind, square(file, rank));
+    }
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+    game
+}
+
+#[test]
+fn perft_start_position_matches_reference() {
+    let expected = [15, 225, 3871, 52132];
+    for (depth, &nodes) in (1..=4).zip(expected.iter()) {
+        let mut game = Game::default();
+        assert_eq!(game.perft(depth), nodes, "perft({})", depth);
+    }
+}
+
+#[test]
+fn perft_leaves_the_game_unchanged() {
+    let mut game = Game::default();
+    let before = serde_json::to_string(&game).unwrap();
+    game.perft(2);
+    assert_eq!(serde_json::to_string(&game).unwrap(), before);
+}
+
+/// Blue queen d4 leaps exactly two squares: d2, f2, b2, b4, f4, b6 and f6
+/// (capturing the pawn). d6 holds a queen, which queens cannot take. The d5
+/// pawn is jumped over and is itself blocked by that queen. King a1 adds a2,
+/// b1 and b2, for 10 moves.
+#[test]
+fn perft_queen_leaps() {
+    let mut game = position(&[
+        (Army::Blue, PieceKind::King, 'a', 1),
+        (Army::Blue, PieceKind::Queen, 'd', 4),
+        (Army::Blue, PieceKind::Pawn, 'd', 5),
+        (Army::Red, PieceKind::King, 'h', 8),
+        (Army::Red, PieceKind::Queen, 'd', 6),
+        (Army::Red, PieceKind::Pawn, 'f', 6),
+    ]);
+    assert_eq!(game.perft(1), 10);
+    assert_eq!(game.perft(2), 96);
+}
+
+/// A queen's leap targets always lie on its own diagonal system, so enemy
+/// bishops on b6 and f6 are both capturable: eight queen moves plus g1, g2
+/// and h2 for the king.
+#[test]
+fn perft_queen_takes_bishops_on_its_diagonal_system() {
+    let mut game = position(&[
+        (Army::Blue, PieceKind::King, 'h', 1),
+        (Army::Blue, PieceKind::Queen, 'd', 4),
+        (Army::Red, PieceKind::King, 'a', 8),
+        (Army::Red, PieceKind::Bishop, 'b', 6),
+        (Army::Red, PieceKind::Bishop, 'f', 6),
+    ]);
+    assert_eq!(game.perft(1), 11);
+    assert_eq!(game.perft(2), 211);
+}
+
+/// Yellow is frozen, so its rook on c1 no longer checks the king on h1 but
+/// still blocks the rook on a1 along the first rank, as does its pawn on a4
+/// along the file. Rook: b1, c1, a2, a3, a4; king: g1, g2, h2.
+#[test]
+fn perft_frozen_army_is_terrain() {
+    let mut game = position(&[
+        (Army::Blue, PieceKind::King, 'h', 1),
+        (Army::Blue, PieceKind::Rook, 'a', 1),
+        (Army::Red, PieceKind::King, 'h', 8),
+        (Army::Yellow, PieceKind::Rook, 'c', 1),
+        (Army::Yellow, PieceKind::Pawn, 'a', 4),
+    ]);
+    game.freeze_army(Army::Yellow);
+    assert_eq!(game.perft(1), 8);
+    assert_eq!(game.perft(2), 24);
+}
+
+#[test]
+fn movegen_profile_times_every_step() {
+    let mut game = Game::default();
+    let profile = game.profile_movegen(2);
+
+    assert!(profile.positions > 1);
+    assert!(profile.pieces[PieceKind::Pawn.index()] > 0);
+    assert!(profile.pseudo_legal[PieceKind::Pawn.index()] > std::time::Duration::ZERO);
+    assert!(profile.candidates > 0);
+    assert!(profile.self_check_filter > std::time::Duration::ZERO);
+}
+
+#[test]
+fn check_evasions_match_the_unpruned_generator() {
+    let mut checked = 0;
+    for seed in 0..400 {
+        let game = load::random_position(6, seed).unwrap();
+        for army in Army::ALL {
+            if !game.king_in_check(army) {
+                continue;
+            }
+            checked += 1;
+            let mut fast = game.generate_legal_moves(army);
+            let mut slow = game.generate_legal_moves_unpruned(army);
+            fast.sort_by_key(|m| (m.from, m.to));
+            slow.sort_by_key(|m| (m.from, m.to));
+            assert_eq!(fast, slow, "seed {} {:?}\n{}", seed, army, game.to_compact());
+        }
+    }
+    assert!(checked > 50, "only {} positions in check", checked);
+}
+
+#[test]
+fn move_tree_matches_legal_moves_and_perft() {
+    let mut game = Game::default();
+    let army = game.current_army();
+    let tree = game.move_tree(1);
+    assert_eq!(tree.children.len(), game.legal_moves(army).len());
+    assert!(tree.children.iter().all(|child| child.army == Some(army) && child.children.is_empty()));
+
+    let tree = game.move_tree(2);
+    let leaves: usize = tree.children.iter().map(|child| child.children.len()).sum();
+    assert_eq!(leaves as u64, game.perft(2));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{run, temp_file};
+use enoch::engine::arrays::TABLET_OF_FIRE_PROTOTYPE;
+use enoch::engine::game::Game;
+use enoch::engine::load;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const SMALL_PGN: &str = "[Event \"Piped\"]\n\n1. B:e2-e3 R:e7-e6 K:pass Y:h5-g5\n";
+
+#[test]
+fn array_and_event_headers_survive_export_and_import() {
+    let state = temp_file("headers", "json");
+    let pgn = temp_file("headers", "pgn");
+    let imported = temp_file("headers-imported", "json");
+
+    let mut game = Game::from_array_spec(&TABLET_OF_FIRE_PROTOTYPE);
+    for _ in 0..4 {
+        let army = game.current_army();
+        let mv = game.legal_moves(army)[0];
+        game.apply_move(army, mv.from, mv.to, None).unwrap();
+    }
+    let event = r#"Lodge "A" match \ 2"#;
+    game.metadata.event = Some(event.to_string());
+    std::fs::write(&state, game.to_json().unwrap()).unwrap();
+
+    run(&["--headless", "--state", state.to_str().unwrap(), "--export-pgn", pgn.to_str().unwrap()]);
+    let exported = std::fs::read_to_string(&pgn).unwrap();
+    assert!(exported.contains("[Array \"Tablet of Fire (prototype)\"]"), "{}", exported);
+    assert!(exported.contains(r#"[Event "Lodge \"A\" match \\ 2"]"#), "{}", exported);
+
+    run(&["--headless", "--import-pgn", pgn.to_str().unwrap(), "--state", imported.to_str().unwrap()]);
+    let restored = Game::from_json(&std::fs::read_to_string(&imported).unwrap()).unwrap();
+    assert_eq!(restored.metadata.array.as_deref(), Some(TABLET_OF_FIRE_PROTOTYPE.name));
+    assert_eq!(restored.metadata.event.as_deref(), Some(event));
+    assert_eq!(restored.move_history, game.move_history);
+    assert!(restored.board == game.board);
+
+    for path in [state, pgn, imported] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn pgn_imports_from_any_reader() {
+    let import = load::from_pgn(SMALL_PGN.as_bytes()).unwrap();
+    assert_eq!(import.moves, 4);
+    assert!(import.warnings.is_empty());
+    assert_eq!(import.game.move_history.len(), 4);
+    assert_eq!(import.game.metadata.event.as_deref(), Some("Piped"));
+}
+
+#[test]
+fn dash_pipes_pgn_from_stdin_to_stdout() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--import-pgn", "-", "--export-pgn", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run enoch");
+    child.stdin.take().unwrap().write_all(SMALL_PGN.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("[Event \"Piped\"]"), "{}", stdout);
+    assert!(stdout.contains("B:e2-e3 R:e7-e6 Bk:pass Y:h5-g5"), "{}", stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Imported 4 moves from stdin"));
+}
+
+#[test]
+fn legacy_short_and_full_army_tags_import_alike() {
+    let legacy = load::from_pgn("1. B:e2-e3 R:e7-e6 K:a8-b8 Y:h5-g5\n".as_bytes()).unwrap();
+    let short = load::from_pgn("1. B:e2-e3 R:e7-e6 Bk:a8-b8 Y:h5-g5\n".as_bytes()).unwrap();
+    let full = load::from_pgn("1. Blue:e2-e3 red:e7-e6 BLACK:a8-b8 Yellow:h5-g5\n".as_bytes()).unwrap();
+
+    assert!(legacy.warnings.is_empty(), "{:?}", legacy.warnings);
+    assert_eq!(legacy.moves, 4);
+    for import in [&short, &full] {
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert_eq!(import.game.board, legacy.game.board);
+        assert_eq!(import.game.move_history, legacy.game.move_history);
+    }
+}
+
+#[test]
+fn export_tags_black_as_bk_and_names_the_tags() {
+    let pgn = temp_file("army-tags", "pgn");
+    let state = temp_file("army-tags", "json");
+    let mut game = Game::default();
+    for (army, from, to) in [("blue", "e2", "e3"), ("red", "e7", "e6"), ("black", "a8", "b8")] {
+        let from = enoch::engine::types::parse_square(from).unwrap();
+        let to = enoch::engine::types::parse_square(to).unwrap();
+        game.apply_move(enoch::engine::types::Army::from_str(army).unwrap(), from, to, None).unwrap();
+    }
+    std::fs::write(&state, game.to_json().unwrap()).unwrap();
+
+    run(&["--headless", "--state", state.to_str().unwrap(), "--export-pgn", pgn.to_str().unwrap()]);
+    let exported = std::fs::read_to_string(&pgn).unwrap();
+    assert!(exported.contains("[Variant \"Enochian; armies B=Blue Bk=Black R=Red Y=Yellow\"]"), "{}", exported);
+    assert!(exported.contains("B:e2-e3 R:e7-e6 Bk:a8-b8"), "{}", exported);
+
+    let reimported = load::from_pgn(exported.as_bytes()).unwrap();
+    assert_eq!(reimported.game.board, game.board);
+
+    for path in [state, pgn] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn export_result_follows_the_game_status() {
+    let pgn = temp_file("drawn", "pgn");
+    let state = temp_file("drawn", "json");
+    let mut game = Game::default();
+    game.status = enoch::engine::game::Status::Draw;
+    game.metadata.result = Some("*".to_string());
+    std::fs::write(&state, game.to_json().unwrap()).unwrap();
+
+    run(&["--headless", "--state", state.to_str().unwrap(), "--export-pgn", pgn.to_str().unwrap()]);
+    let exported = std::fs::read_to_string(&pgn).unwrap();
+    assert!(exported.contains("[Result \"Draw\"]"), "{}", exported);
+
+    for path in [state, pgn] {
+        let _ = std::fs::remove_file(path);
+    }
+}
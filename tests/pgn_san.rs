@@ -0,0 +1,81 @@
+use enoch::engine::board::Board;
+use enoch::engine::game::Game;
+use enoch::engine::piece_kind::parse_move;
+use enoch::engine::types::{parse_square, Army, PieceKind};
+use std::process::Command;
+
+fn sq(name: &str) -> u8 {
+    parse_square(name).unwrap()
+}
+
+#[test]
+fn san_export_parses_back_and_replays() {
+    let dir = std::env::temp_dir().join(format!("enoch-san-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let state = dir.join("game.json");
+    let pgn = dir.join("game.pgn");
+
+    let mut game = Game::default();
+    let plies = [
+        (Army::Blue, "e2", "e3"),
+        (Army::Red, "e7", "e6"),
+        (Army::Black, "a5", "b5"),
+        (Army::Yellow, "h5", "g5"),
+        (Army::Blue, "b1", "c3"),
+    ];
+    for (army, from, to) in plies {
+        game.apply_move(army, sq(from), sq(to), None).unwrap();
+    }
+    std::fs::write(&state, game.to_json().unwrap()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .args(["--headless", "--state", state.to_str().unwrap()])
+        .args(["--export-pgn", pgn.to_str().unwrap(), "--pgn-dialect", "san"])
+        .output()
+        .expect("failed to start enoch");
+    assert!(output.status.success());
+    let exported = std::fs::read_to_string(&pgn).unwrap();
+    assert!(exported.contains("[Dialect \"SAN\"]"));
+    assert!(exported.contains("[Armies \"Blue Red Black Yellow\"]"));
+
+    let tokens: Vec<&str> = exported
+        .lines()
+        .filter(|line| !line.starts_with('['))
+        .flat_map(|line| line.split_whitespace())
+        .filter(|token| !token.ends_with('.'))
+        .collect();
+    assert_eq!(tokens.len(), plies.len());
+    assert_eq!(tokens[1], "e6");
+    assert_eq!(tokens[4], "Nc3");
+
+    let mut replay = Game::default();
+    for (token, (army, _, _)) in tokens.iter().zip(plies) {
+        parse_move(token).unwrap_or_else(|e| panic!("{} does not parse: {:?}", token, e));
+        replay.apply_san(army, token).unwrap();
+    }
+    assert_eq!(replay.board, game.board);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn san_disambiguates_promotes_and_marks_check() {
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Blue, PieceKind::King, sq("a1"));
+    board.place_piece(Army::Blue, PieceKind::Rook, sq("a4"));
+    board.place_piece(Army::Blue, PieceKind::Rook, sq("h4"));
+    board.place_piece(Army::Blue, PieceKind::Pawn, sq("c7"));
+    board.place_piece(Army::Blue, PieceKind::Pawn, sq("e7"));
+    board.place_piece(Army::Red, PieceKind::King, sq("g8"));
+    let mut game = Game::default();
+    game.board = board;
+    game.state.sync_with_board(&game.board);
+
+    assert_eq!(game.san(Army::Blue, sq("a4"), sq("d4"), None).unwrap(), "Rad4");
+    // Queens leap exactly two squares: from e8 the king on g8 is in reach,
+    // from c8 it is not.
+    assert_eq!(game.san(Army::Blue, sq("e7"), sq("e8"), Some(PieceKind::Queen)).unwrap(), "e8=Q+");
+    assert_eq!(game.san(Army::Blue, sq("c7"), sq("c8"), Some(PieceKind::Queen)).unwrap(), "c8=Q");
+    assert_eq!(game.san(Army::Blue, sq("h4"), sq("g4"), None).unwrap(), "Rhg4+");
+    assert!(game.san(Army::Red, sq("a4"), sq("d4"), None).is_err());
+}
@@ -0,0 +1,144 @@
+use enoch::engine::game::Game;
+use enoch::engine::rules::Topic;
+use enoch::engine::types::Army;
+
+fn assert_topic(queries: &[&str], expected: Topic) {
+    for query in queries {
+        assert_eq!(
+            Topic::from_query(query),
+            Some(expected),
+            "query {:?} should map to {:?}",
+            query,
+            expected
+        );
+    }
+}
+
+#[test]
+fn queen_capture_queen_phrasings() {
+    assert_topic(
+        &[
+            "queen capture queen",
+            "can a queen take a queen?",
+            "Can queens capture each other",
+            "queen takes queen",
+        ],
+        Topic::QueenCaptureQueen,
+    );
+}
+
+#[test]
+fn bishop_capture_bishop_phrasings() {
+    assert_topic(
+        &[
+            "bishop capture bishop",
+            "can a bishop take another bishop",
+            "bishops capturing bishops",
+        ],
+        Topic::BishopCaptureBishop,
+    );
+}
+
+#[test]
+fn queen_bishop_phrasings() {
+    assert_topic(
+        &[
+            "queen bishop",
+            "can a bishop capture a queen?",
+            "may a queen take a bishop",
+        ],
+        Topic::QueenBishopCapture,
+    );
+}
+
+#[test]
+fn check_phrasings() {
+    assert_topic(
+        &["check", "what happens when my king is in check", "checkmate?"],
+        Topic::Check,
+    );
+}
+
+#[test]
+fn promotion_phrasings() {
+    assert_topic(
+        &[
+            "promotion",
+            "when does a pawn promote",
+            "promoting pawns",
+            "promtion",
+        ],
+        Topic::Promotion,
+    );
+}
+
+#[test]
+fn frozen_phrasings() {
+    assert_topic(
+        &["frozen", "what does freezing an army do", "how are armies revived"],
+        Topic::Frozen,
+    );
+}
+
+#[test]
+fn throne_phrasings() {
+    assert_topic(&["throne", "what are throne squares", "thrones"], Topic::Throne);
+}
+
+#[test]
+fn victory_phrasings() {
+    assert_topic(
+        &["victory", "how do I win", "which team wins", "what is the goal"],
+        Topic::Victory,
+    );
+}
+
+#[test]
+fn queen_movement_phrasings() {
+    assert_topic(
+        &["queen move", "how does the queen move", "queen leap", "queeen"],
+        Topic::QueenMovement,
+    );
+}
+
+#[test]
+fn pawn_movement_phrasings() {
+    assert_topic(
+        &["pawn move", "how do pawns capture", "pawn movement"],
+        Topic::PawnMovement,
+    );
+}
+
+#[test]
+fn stalemate_phrasings() {
+    assert_topic(
+        &["stalemate", "what if an army is stuck", "stalmate"],
+        Topic::Stalemate,
+    );
+}
+
+#[test]
+fn unrelated_queries_are_rejected() {
+    assert_eq!(Topic::from_query(""), None);
+    assert_eq!(Topic::from_query("hello there"), None);
+    assert_eq!(Topic::from_query("good window"), None);
+}
+
+#[test]
+fn every_example_query_maps_back_to_its_topic() {
+    for topic in Topic::ALL {
+        assert_eq!(Topic::from_query(topic.example_query()), Some(topic));
+        assert!(!topic.explanation().is_empty());
+    }
+}
+
+#[test]
+fn promotion_explanation_names_the_default_zones() {
+    let game = Game::default();
+    let text = Topic::Promotion.explanation();
+    for army in Army::ALL {
+        let rank = game.promotion_squares(army)[0] / 8 + 1;
+        let line = format!("{} pawns promote on rank {}", army.display_name(), rank);
+        assert!(text.contains(&line), "missing {:?} in {}", line, text);
+    }
+}
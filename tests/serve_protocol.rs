@@ -0,0 +1,86 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn serve(requests: &str) -> Vec<serde_json::Value> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_enoch"))
+        .arg("--serve")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to start enoch");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(requests.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each response is one JSON line"))
+        .collect()
+}
+
+#[test]
+fn move_then_state_reflects_the_move() {
+    let responses = serve(concat!(
+        r#"{"cmd":"move","army":"Blue","from":"e2","to":"e3"}"#,
+        "\n",
+        r#"{"cmd":"state"}"#,
+        "\n",
+    ));
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["type"], "moved");
+
+    let state = &responses[1];
+    assert_eq!(state["type"], "state");
+    assert_eq!(state["turn"], "Red");
+    assert_eq!(state["plies"], 1);
+    let pieces = state["pieces"].as_array().unwrap();
+    let at = |square: &str| pieces.iter().find(|piece| piece["square"] == square);
+    assert!(at("e2").is_none());
+    assert_eq!(at("e3").unwrap()["piece"], "Pawn");
+    assert_eq!(at("e3").unwrap()["army"], "Blue");
+    assert_eq!(state["board"][5], "3 n . . . P . . n");
+}
+
+#[test]
+fn bad_requests_get_error_responses() {
+    let responses = serve(concat!(
+        r#"{"cmd":"move","army":"Red","from":"b7","to":"b6"}"#,
+        "\n",
+        "not json\n",
+        r#"{"cmd":"undo","n":1}"#,
+        "\n",
+        r#"{"cmd":"legal","army":"blue"}"#,
+        "\n",
+    ));
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[0]["type"], "error");
+    assert_eq!(responses[0]["message"], "It is not Red's turn (Blue to move)");
+    assert_eq!(responses[1]["type"], "error");
+    assert_eq!(responses[2]["message"], "No moves to undo");
+    assert_eq!(responses[3]["army"], "Blue");
+    assert!(!responses[3]["moves"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn packed_moves_from_legal_can_be_played() {
+    let legal = serve(concat!(r#"{"cmd":"legal","army":"blue"}"#, "\n"));
+    let e3 = legal[0]["moves"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|mv| mv["from"] == "e2" && mv["to"] == "e3")
+        .expect("e2-e3 is legal");
+    let packed = e3["packed"].as_u64().unwrap();
+
+    let responses = serve(&format!(
+        "{{\"cmd\":\"packed\",\"army\":\"blue\",\"mv\":{}}}\n{{\"cmd\":\"state\"}}\n",
+        packed
+    ));
+    assert_eq!(responses[0]["type"], "moved");
+    assert_eq!(responses[1]["board"][5], "3 n . . . P . . n");
+}
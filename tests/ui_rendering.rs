@@ -3,6 +3,7 @@ use enoch::ui::ui::render;
 use ratatui::backend::TestBackend;
 use ratatui::Terminal;
 use std::fs;
+use std::path::Path;
 
 fn render_at_size(width: u16, height: u16) -> String {
     let backend = TestBackend::new(width, height);
@@ -30,6 +31,53 @@ fn render_at_size(width: u16, height: u16) -> String {
     output
 }
 
+/// Set this environment variable to rewrite the golden screenshots from the
+/// current render instead of comparing against them.
+const UPDATE_GOLDEN: &str = "UPDATE_GOLDEN";
+
+/// Compares `screenshot` with `tests/screenshots/golden/<name>.txt`,
+/// failing with the lines that differ.
+fn assert_matches_golden(name: &str, screenshot: &str) {
+    let path = Path::new("tests/screenshots/golden").join(format!("{}.txt", name));
+    if std::env::var_os(UPDATE_GOLDEN).is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, screenshot).unwrap();
+        return;
+    }
+    let golden = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!("Cannot read {}: {} (run with {}=1 to create it)", path.display(), e, UPDATE_GOLDEN)
+    });
+    if golden == screenshot {
+        return;
+    }
+    let expected: Vec<&str> = golden.lines().collect();
+    let actual: Vec<&str> = screenshot.lines().collect();
+    let mut diff = String::new();
+    for line in 0..expected.len().max(actual.len()) {
+        let (want, got) = (expected.get(line), actual.get(line));
+        if want != got {
+            diff.push_str(&format!(
+                "line {}:\n  - {}\n  + {}\n",
+                line + 1,
+                want.unwrap_or(&"<missing>"),
+                got.unwrap_or(&"<missing>")
+            ));
+        }
+    }
+    panic!(
+        "{} differs from its golden screenshot (rerun with {}=1 if the change is intended):\n{}",
+        name, UPDATE_GOLDEN, diff
+    );
+}
+
+#[test]
+fn test_render_matches_golden_screenshots() {
+    for (width, height) in [(80, 24), (132, 46)] {
+        let screenshot = render_at_size(width, height);
+        assert_matches_golden(&format!("ui_{}x{}", width, height), &screenshot);
+    }
+}
+
 #[test]
 fn test_render_minimum_size() {
     let screenshot = render_at_size(80, 24);
@@ -178,3 +226,508 @@ fn test_with_game_state() {
     // Should show move history
     assert!(output.contains("Moves") || output.contains("Blue"));
 }
+
+#[test]
+fn test_resize_preserves_selection() {
+    use enoch::engine::types::Army;
+    use enoch::ui::app::CurrentScreen;
+    
+    let backend = TestBackend::new(132, 46);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = App::new(false);
+    
+    app.selected_army = Some(Army::Blue);
+    assert!(app.try_select_square("e2"));
+    let selected = app.selected_square;
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    
+    for (width, height) in [(80, 24), (200, 60), (100, 30)] {
+        terminal.backend_mut().resize(width, height);
+        app.handle_resize(height);
+        terminal.draw(|f| render(f, &mut app)).unwrap();
+        assert_eq!(app.selected_square, selected, "selection lost at {}x{}", width, height);
+    }
+    
+    // Help scrolled to the end of a tall viewport is pulled back when the
+    // viewport shrinks, so the last page stays filled.
+    app.current_screen = CurrentScreen::Help;
+    app.scroll_help(10_000);
+    terminal.backend_mut().resize(80, 24);
+    app.handle_resize(24);
+    let help_lines = App::get_help_text().len();
+    assert_eq!(app.help_scroll, help_lines - (24 - 4));
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+}
+
+#[test]
+fn test_resize_drops_stale_selection() {
+    use enoch::engine::types::Army;
+    
+    let mut app = App::new(false);
+    app.selected_army = Some(Army::Blue);
+    assert!(app.try_select_square("e2"));
+    app.game.board.clear_square(app.selected_square.unwrap());
+    app.handle_resize(24);
+    assert_eq!(app.selected_square, None);
+}
+
+#[test]
+fn test_selecting_a_piece_then_a_destination_moves_it() {
+    use enoch::engine::types::{Army, PieceKind};
+    
+    let mut app = App::new(false);
+    app.select_army(Army::Red);
+    assert!(!app.try_select_square("e2"), "e2 holds a Blue piece");
+    app.cycle_selected_army(-1);
+    assert_eq!(app.selected_army, Some(Army::Blue));
+    
+    assert!(app.try_select_square("e2"));
+    assert_eq!(app.selected_square, Some(12));
+    assert!(app.try_select_square("e3"));
+    assert_eq!(app.game.board.piece_at(20), Some((Army::Blue, PieceKind::Pawn)));
+    assert_eq!(app.game.board.piece_at(12), None);
+    assert_eq!(app.selected_square, None);
+    assert_eq!(app.selected_army, Some(Army::Red), "selection follows the turn");
+    assert_eq!(app.move_history.len(), 1);
+}
+
+/// Air to move with only the Red king left for Earth; Blue's rook on a1 can
+/// take it on a8.
+fn bare_king_app() -> App {
+    use enoch::engine::board::Board;
+    use enoch::engine::types::{Army, PieceKind};
+    
+    let mut app = App::new(false);
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Blue, PieceKind::King, 7);
+    board.place_piece(Army::Blue, PieceKind::Rook, 0);
+    board.place_piece(Army::Black, PieceKind::King, 15);
+    board.place_piece(Army::Red, PieceKind::King, 56);
+    app.game.board = board;
+    app.game.state.sync_with_board(&app.game.board);
+    app.selected_army = Some(Army::Blue);
+    app
+}
+
+fn buffer_text(terminal: &Terminal<TestBackend>) -> String {
+    let buffer = terminal.backend().buffer();
+    let area = buffer.area;
+    let mut output = String::new();
+    for y in 0..area.height {
+        for x in 0..area.width {
+            output.push_str(buffer[(x, y)].symbol());
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[test]
+fn test_game_over_screen_after_bare_king_win() {
+    use enoch::engine::game::Status;
+    use enoch::engine::types::{Army, PieceKind};
+    use enoch::ui::app::CurrentScreen;
+    
+    let mut app = bare_king_app();
+    assert!(app.try_select_square("a1"));
+    assert!(app.try_select_square("a8"));
+    assert!(matches!(app.current_screen, CurrentScreen::GameOver));
+    
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    let output = buffer_text(&terminal);
+    let screenshot = std::env::temp_dir().join(format!("enoch-ui_game_over-{}.txt", std::process::id()));
+    fs::write(&screenshot, &output).unwrap();
+    assert!(output.contains("Game Over"));
+    assert!(output.contains("AIR TEAM WINS"));
+    assert!(output.contains("n: new game"));
+    
+    // Moves are locked out until a new game starts.
+    assert!(!app.try_select_square("h1"));
+    app.input = "blue: h1-g1".to_string();
+    app.submit_command();
+    assert!(app.error_message.is_some());
+    
+    // Undoing the winning capture reopens play with the Red king back.
+    app.undo();
+    assert!(matches!(app.current_screen, CurrentScreen::Main));
+    assert_eq!(app.game.status, Status::Ongoing);
+    assert!(app.game.winning_team().is_none());
+    assert_eq!(app.game.current_army(), Army::Blue);
+    assert_eq!(app.game.board.piece_at(56), Some((Army::Red, PieceKind::King)));
+    app.redo();
+    assert!(matches!(app.current_screen, CurrentScreen::GameOver));
+    
+    app.new_game();
+    assert!(matches!(app.current_screen, CurrentScreen::Main));
+    assert!(app.game.winning_team().is_none());
+    fs::remove_file(screenshot).ok();
+}
+
+#[test]
+fn test_loading_finished_game_shows_game_over() {
+    use enoch::ui::app::CurrentScreen;
+    
+    let mut finished = bare_king_app();
+    assert!(finished.try_select_square("a1"));
+    assert!(finished.try_select_square("a8"));
+    let path = std::env::temp_dir().join(format!("enoch-game-over-{}.json", std::process::id()));
+    fs::write(&path, finished.game.to_json().unwrap()).unwrap();
+    
+    let mut app = App::new(false);
+    app.input = format!("/load {}", path.display());
+    app.submit_command();
+    assert!(matches!(app.current_screen, CurrentScreen::GameOver));
+    
+    app.undo();
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_loading_a_game_selects_its_array() {
+    use enoch::engine::arrays::TABLET_OF_WATER_PLACEHOLDER;
+    use enoch::engine::game::Game;
+    
+    let path = std::env::temp_dir().join(format!("enoch-load-array-{}.json", std::process::id()));
+    let saved = Game::from_array_spec(&TABLET_OF_WATER_PLACEHOLDER);
+    fs::write(&path, saved.to_json().unwrap()).unwrap();
+    
+    let mut app = App::new(false);
+    app.input = format!("/load {}", path.display());
+    app.submit_command();
+    assert_eq!(app.selected_array, TABLET_OF_WATER_PLACEHOLDER.name);
+    
+    let mut unknown = saved;
+    unknown.metadata.array = Some("No such tablet".to_string());
+    fs::write(&path, unknown.to_json().unwrap()).unwrap();
+    app.input = format!("/load {}", path.display());
+    app.submit_command();
+    assert_eq!(app.selected_array, TABLET_OF_WATER_PLACEHOLDER.name, "unknown arrays keep the selection");
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_monochrome_theme_recolors_blue_pieces() {
+    use enoch::engine::board::Board;
+    use enoch::engine::types::{Army, PieceKind};
+    use enoch::ui::theme::Theme;
+    use ratatui::style::Color;
+
+    let mut app = App::new(false);
+    let mut board = Board::new(&[]);
+    board.place_piece(Army::Blue, PieceKind::King, 2);
+    board.place_piece(Army::Black, PieceKind::King, 63);
+    app.game.board = board;
+    app.game.state.sync_with_board(&app.game.board);
+
+    // c1 is drawn as a light square, so the king uses the on-light color.
+    let king_fg = |app: &mut App| -> Color {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| render(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let area = buffer.area;
+        (0..area.height)
+            .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+            .map(|pos| &buffer[pos])
+            .find(|cell| cell.symbol() == "K" && cell.bg == app.theme.light_square)
+            .expect("Blue king is drawn on c1")
+            .fg
+    };
+
+    let classic = king_fg(&mut app);
+    assert_eq!(classic, Color::Rgb(0, 0, 200));
+
+    app.input = "/theme monochrome".to_string();
+    app.submit_command();
+    assert_eq!(app.theme, Theme::MONOCHROME);
+    let monochrome = king_fg(&mut app);
+    assert_ne!(monochrome, classic);
+    assert_eq!(monochrome, Theme::MONOCHROME.army_on_light[Army::Blue.index()]);
+}
+
+#[test]
+fn test_cursor_moves_right_to_d1() {
+    use enoch::ui::theme::Theme;
+    use ratatui::style::Modifier;
+
+    let mut app = App::new(false);
+    assert_eq!(app.cursor, 0);
+    for _ in 0..3 {
+        app.move_cursor(1, 0);
+    }
+    assert_eq!(app.cursor, 3);
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let area = buffer.area;
+    let cursor_symbols: Vec<&str> = (0..area.height)
+        .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+        .map(|pos| &buffer[pos])
+        .filter(|cell| cell.bg == Theme::CLASSIC.cursor)
+        .map(|cell| {
+            assert!(cell.modifier.contains(Modifier::UNDERLINED));
+            cell.symbol()
+        })
+        .filter(|symbol| symbol.trim() != "")
+        .collect();
+    // d1 holds the Blue queen in the default array.
+    assert_eq!(cursor_symbols, vec!["Q"]);
+}
+
+#[test]
+fn test_selecting_a_piece_explains_its_rules() {
+    use enoch::engine::types::Army;
+    
+    let mut terminal = Terminal::new(TestBackend::new(200, 50)).unwrap();
+    let mut app = App::new(false);
+    app.selected_army = Some(Army::Blue);
+    // d1 holds the Blue queen in the default array.
+    assert!(app.try_select_square("d1"));
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    let screen = buffer_text(&terminal);
+    assert!(screen.contains("Queen: leaps exactly 2 squares"), "{}", screen);
+    
+    // An empty throne describes throne control instead.
+    app.selected_square = None;
+    app.game.board.clear_square(3);
+    assert!(!app.try_select_square("d1"));
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    let screen = buffer_text(&terminal);
+    assert!(screen.contains("Blue throne: an allied king here takes control"), "{}", screen);
+}
+
+#[test]
+fn test_board_cache_key_tracks_moves_not_repaints() {
+    let mut terminal = Terminal::new(TestBackend::new(132, 46)).unwrap();
+    let mut app = App::new(false);
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    let (key, _) = app.board_cache.clone().expect("board drawn");
+    
+    app.input = "/sta".to_string();
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    assert_eq!(app.board_cache.as_ref().unwrap().0, key, "typing leaves the board alone");
+    
+    assert!(app.try_select_square("e2"));
+    assert!(app.try_select_square("e3"));
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    assert_ne!(app.board_cache.as_ref().unwrap().0, key);
+    assert!(buffer_text(&terminal).contains("Red to move"));
+}
+
+#[test]
+fn test_frames_are_captured_only_for_screenshots() {
+    let path = std::env::temp_dir().join(format!("enoch-screenshot-{}.txt", std::process::id()));
+    let mut terminal = Terminal::new(TestBackend::new(132, 46)).unwrap();
+    let mut app = App::new(false);
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    assert!(app.last_frame.is_none());
+    assert!(!app.save_pending_screenshot());
+    
+    app.input = format!("/screenshot {}", path.display());
+    app.submit_command();
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    assert!(app.save_pending_screenshot());
+    assert!(fs::read_to_string(&path).unwrap().contains("Board:"));
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_capture_frames_records_every_frame() {
+    let mut terminal = Terminal::new(TestBackend::new(132, 46)).unwrap();
+    let mut app = App::new(false);
+    app.capture_frames = true;
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    let frame = app.last_frame().expect("frame captured").to_string();
+    assert!(frame.contains("Board:"), "{}", frame);
+    assert!(frame.contains("Turn: Blue"), "{}", frame);
+    
+    assert!(app.try_select_square("e2"));
+    assert!(app.try_select_square("e3"));
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    assert!(app.last_frame().unwrap().contains("Turn: Red"));
+}
+
+#[test]
+fn test_cursor_clamps_at_board_edge() {
+    let mut app = App::new(false);
+    app.move_cursor(-1, -1);
+    assert_eq!(app.cursor, 0);
+    app.move_cursor(10, 10);
+    assert_eq!(app.cursor, 63);
+}
+
+#[test]
+fn test_enter_on_cursor_selects_and_moves() {
+    use enoch::engine::types::{Army, PieceKind};
+
+    let mut app = App::new(false);
+    // e2, then e3 after selecting.
+    app.move_cursor(4, 1);
+    assert!(app.activate_cursor());
+    assert_eq!(app.selected_square, Some(12));
+    app.move_cursor(0, 1);
+    assert!(app.activate_cursor());
+    assert_eq!(app.game.board.piece_at(20), Some((Army::Blue, PieceKind::Pawn)));
+
+    // Typed coordinates still work alongside the cursor.
+    assert!(app.try_select_square("d7"));
+}
+
+fn assert_compact_layout(width: u16, height: u16) {
+    let screenshot = render_at_size(width, height);
+    println!("{}", screenshot);
+
+    assert!(screenshot.contains("Enochian Chess"));
+    assert!(screenshot.contains("Blue to move"), "turn indicator missing");
+    assert!(screenshot.contains("BLUE'S TURN"), "status line missing");
+    for rank in ["8 ", "1 "] {
+        assert!(screenshot.contains(rank), "rank {} missing", rank.trim());
+    }
+    assert!(screenshot.contains(" A  B  C  D  E  F  G  H "));
+    assert!(!screenshot.contains("Terminal too small"));
+}
+
+#[test]
+fn test_compact_layout_at_60x20() {
+    assert_compact_layout(60, 20);
+}
+
+#[test]
+fn test_compact_layout_at_70x22() {
+    assert_compact_layout(70, 22);
+}
+
+#[test]
+fn test_rules_command_toggles_forced_king() {
+    let mut app = App::new(false);
+    assert!(app.game.config.force_king_move_in_check);
+
+    app.input = "/rules forced-king".to_string();
+    app.submit_command();
+    assert!(!app.game.config.force_king_move_in_check);
+    assert_eq!(app.error_message, None);
+
+    app.input = "/rules".to_string();
+    app.submit_command();
+    assert!(app.status_message.as_deref().unwrap().contains("forced-king off"));
+}
+
+#[test]
+fn test_start_game_adopts_a_custom_array() {
+    use enoch::engine::game::Game;
+    use enoch::engine::load;
+
+    let spec = load::array_spec_from_json(
+        r#"{"name": "Pawn Race", "position": "blue:Ke1,Pa2\nred:Ke8,Ph7\nblack:Ka5\nyellow:Kh4"}"#,
+    )
+    .unwrap();
+    let mut app = App::new(false);
+    app.start_game(Game::from_array_spec(&spec));
+    assert_eq!(app.selected_array, "Pawn Race");
+    assert_eq!(app.game.board.iter_pieces().count(), 6);
+    assert_eq!(app.selected_army, Some(app.game.current_army()));
+}
+
+#[test]
+fn test_relative_coords_read_moves_from_the_armys_side() {
+    use enoch::engine::types::{Army, PieceKind};
+    
+    let mut app = App::new(false);
+    for mv in ["blue: e2-e3", "red: e7-e6"] {
+        app.input = mv.to_string();
+        app.submit_command();
+        assert_eq!(app.error_message, None, "{}", mv);
+    }
+    
+    app.input = "/relcoords".to_string();
+    app.submit_command();
+    assert!(app.relative_coords);
+    // Black's pawns advance down the board, so it sees the board turned
+    // round: its h1-g1 is a8-b8.
+    app.input = "black: h1-g1".to_string();
+    app.submit_command();
+    assert_eq!(app.error_message, None);
+    assert_eq!(app.game.board.occupant_army(57), Some(Army::Black));
+    assert_eq!(app.game.board.piece_at(56), None);
+    
+    // Blue's view is the absolute one, typed squares included.
+    app.selected_army = Some(Army::Blue);
+    assert!(app.try_select_square("d2"));
+    assert_eq!(app.selected_square, Some(11));
+}
+
+#[test]
+fn test_coverage_overlay_shades_blues_third_rank_moves() {
+    use enoch::engine::types::Army;
+
+    let mut app = App::new(false);
+    app.input = "/coverage".to_string();
+    app.submit_command();
+    assert!(app.coverage);
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let area = buffer.area;
+    let symbol = |x: u16, y: u16| buffer[(x, y)].symbol().to_string();
+
+    // Rank 3's label opens its row, just inside the board's left border;
+    // the eight squares share the rest of the row up to the right border.
+    let (x, y) = (0..area.height)
+        .flat_map(|y| (1..area.width).map(move |x| (x, y)))
+        .find(|&(x, y)| symbol(x, y) == "3" && symbol(x - 1, y) == "│")
+        .expect("rank 3 label");
+    let row_start = x + 2;
+    let row_end = (row_start..area.width).find(|&x| symbol(x, y) == "│").unwrap();
+    let square_width = (row_end - row_start) / 8;
+    let third_rank = 0xFFu64 << 16;
+    let reachable = app.game.army_moves_bitboard(Army::Blue) & third_rank;
+    assert_ne!(reachable, 0);
+    for file in 0..8u16 {
+        let cell = &buffer[(row_start + file * square_width, y)];
+        let covered = app.game.army_coverage(Army::Blue) & (1u64 << (16 + file)) != 0;
+        assert_eq!(cell.bg == app.theme.coverage, covered, "file {}", file);
+        if reachable & (1u64 << (16 + file)) != 0 {
+            assert_eq!(cell.bg, app.theme.coverage, "file {} is reachable", file);
+        }
+    }
+    // Shading only changes the background: Black's knight on a3 is still drawn.
+    assert!((row_start..row_end).any(|x| symbol(x, y) == "n"));
+}
+
+#[test]
+fn test_round_counter_advances_after_four_plies() {
+    use enoch::ui::app::CurrentScreen;
+    use std::time::Duration;
+
+    let mut app = App::new(false);
+    assert_eq!(app.round(), 1);
+    for mv in ["blue: e2-e3", "red: e7-e6", "black: a8-b8"] {
+        app.input = mv.to_string();
+        app.submit_command();
+        assert_eq!(app.error_message, None, "{}", mv);
+    }
+    assert_eq!(app.round(), 1);
+    let yellow = app.game.generate_legal_moves(enoch::engine::types::Army::Yellow)[0];
+    app.game.apply_move(enoch::engine::types::Army::Yellow, yellow.from, yellow.to, None).unwrap();
+    assert_eq!(app.round(), 2);
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| render(f, &mut app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let top: String = (0..buffer.area.width).map(|x| buffer[(x, 0)].symbol()).collect();
+    assert!(top.contains("Round 2 · Ply 4 · 00:00"), "{}", top);
+
+    // The clock stands still behind the help screen.
+    app.current_screen = CurrentScreen::Help;
+    app.tick_clock();
+    std::thread::sleep(Duration::from_millis(20));
+    app.tick_clock();
+    assert_eq!(app.game_time, Duration::ZERO);
+    app.current_screen = CurrentScreen::Main;
+    std::thread::sleep(Duration::from_millis(20));
+    app.tick_clock();
+    assert!(app.game_time >= Duration::from_millis(20));
+    assert_eq!(app.think_time.iter().sum::<Duration>(), app.game_time);
+}